@@ -61,6 +61,13 @@ pub struct Parameters {
     #[serde(default = "Parameters::default_commit_sync_batches_ahead")]
     pub commit_sync_batches_ahead: usize,
 
+    /// When the threshold clock has advanced past a round for which this authority is the leader
+    /// but has not proposed yet, propose a block for that penultimate round instead of the latest
+    /// clock round. This reduces the number of missed leaders when the threshold clock advances
+    /// multiple rounds at once, e.g. after synchronizing a batch of blocks.
+    #[serde(default = "Parameters::default_propose_for_penultimate_round_when_leader")]
+    pub propose_for_penultimate_round_when_leader: bool,
+
     /// Anemo network settings.
     #[serde(default = "AnemoParameters::default")]
     pub anemo: AnemoParameters,
@@ -124,6 +131,10 @@ impl Parameters {
     pub(crate) fn default_commit_sync_batches_ahead() -> usize {
         200
     }
+
+    pub(crate) fn default_propose_for_penultimate_round_when_leader() -> bool {
+        false
+    }
 }
 
 impl Default for Parameters {
@@ -138,6 +149,8 @@ impl Default for Parameters {
             commit_sync_parallel_fetches: Parameters::default_commit_sync_parallel_fetches(),
             commit_sync_batch_size: Parameters::default_commit_sync_batch_size(),
             commit_sync_batches_ahead: Parameters::default_commit_sync_batches_ahead(),
+            propose_for_penultimate_round_when_leader:
+                Parameters::default_propose_for_penultimate_round_when_leader(),
             anemo: AnemoParameters::default(),
             tonic: TonicParameters::default(),
         }
@@ -39,6 +39,8 @@ pub(crate) struct CommitObserver {
     /// Persistent storage for blocks, commits and other consensus data.
     store: Arc<dyn Store>,
     leader_schedule: Arc<LeaderSchedule>,
+    /// Index of the last commit sent to the consumer of consensus output.
+    last_sent_commit_index: CommitIndex,
 }
 
 impl CommitObserver {
@@ -55,6 +57,7 @@ impl CommitObserver {
             sender: commit_consumer.sender,
             store,
             leader_schedule,
+            last_sent_commit_index: commit_consumer.last_processed_commit_index,
         };
 
         observer.recover_and_send_commits(commit_consumer.last_processed_commit_index);
@@ -88,6 +91,7 @@ impl CommitObserver {
                 committed_sub_dag.commit_ref,
                 committed_sub_dag.leader
             );
+            self.last_sent_commit_index = committed_sub_dag.commit_ref.index;
             sent_sub_dags.push(committed_sub_dag);
         }
 
@@ -150,6 +154,12 @@ impl CommitObserver {
 
             last_sent_commit_index += 1;
         }
+        self.last_sent_commit_index = last_sent_commit_index;
+    }
+
+    /// Returns the index of the last commit that has been sent to the consumer of consensus output.
+    pub(crate) fn last_sent_commit_index(&self) -> CommitIndex {
+        self.last_sent_commit_index
     }
 
     fn report_metrics(&self, committed: &[CommittedSubDag]) {
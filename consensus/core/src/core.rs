@@ -1,7 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::BTreeSet, iter, sync::Arc, time::Duration, vec};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter,
+    sync::Arc,
+    time::Duration,
+    vec,
+};
 
 #[cfg(test)]
 use consensus_config::{local_committee_and_keys, Stake};
@@ -24,11 +30,11 @@ use crate::{
         VerifiedBlock, GENESIS_ROUND,
     },
     block_manager::BlockManager,
-    commit::CommittedSubDag,
+    commit::{CommitIndex, CommittedSubDag},
     commit_observer::CommitObserver,
     context::Context,
     dag_state::DagState,
-    error::{ConsensusError, ConsensusResult},
+    error::ConsensusResult,
     leader_schedule::LeaderSchedule,
     stake_aggregator::{QuorumThreshold, StakeAggregator},
     threshold_clock::ThresholdClock,
@@ -39,8 +45,8 @@ use crate::{
 };
 #[cfg(test)]
 use crate::{
-    block_verifier::NoopBlockVerifier, storage::mem_store::MemStore, CommitConsumer,
-    TransactionClient,
+    block_verifier::NoopBlockVerifier, error::ConsensusError, storage::mem_store::MemStore,
+    CommitConsumer, TransactionClient,
 };
 
 // Maximum number of commit votes to include in a block.
@@ -243,8 +249,9 @@ impl Core {
         Ok(missing_blocks)
     }
 
-    /// Adds/processed all the newly `accepted_blocks`. We basically try to move the threshold clock and add them to the
-    /// pending ancestors list.
+    /// Adds/processed all the newly `accepted_blocks`. We basically try to move the threshold clock. Accepted blocks are
+    /// not tracked separately as pending ancestors: ancestors are selected from DagState, which yields at most one block
+    /// per authority, so equivocating or redundant blocks from the same author cannot accumulate for a proposal.
     fn add_accepted_blocks(&mut self, accepted_blocks: Vec<VerifiedBlock>) {
         // Advance the threshold clock. If advanced to a new round then send a signal that a new quorum has been received.
         if let Some(new_round) = self
@@ -319,8 +326,13 @@ impl Core {
             return None;
         }
 
+        // As the threshold clock can advance many rounds at once (ex because we synchronized a bulk of blocks),
+        // optionally propose for the penultimate round when this authority is supposed to be its leader, so
+        // we bring down the missed leaders. Proposing for all the intermediate rounds does not make much sense.
+        let proposal_round = self.proposal_round(clock_round);
+
         // There must be a quorum of blocks from the previous round.
-        let quorum_round = self.threshold_clock.get_round().saturating_sub(1);
+        let quorum_round = proposal_round.saturating_sub(1);
 
         // Create a new block either because we want to "forcefully" propose a block due to a leader timeout,
         // or because we are actually ready to produce the block (leader exists and min delay has passed).
@@ -363,14 +375,8 @@ impl Core {
             .with_label_values(&[leader_authority])
             .inc();
 
-        // TODO: produce the block for the clock_round. As the threshold clock can advance many rounds at once (ex
-        // because we synchronized a bulk of blocks) we can decide here whether we want to produce blocks per round
-        // or just the latest one. From earlier experiments I saw only benefit on proposing for the penultimate round
-        // only when the validator was supposed to be the leader of the round - so we bring down the missed leaders.
-        // Probably proposing for all the intermediate rounds might not make much sense.
-
         // Determine the ancestors to be included in proposal
-        let ancestors = self.ancestors_to_propose(clock_round);
+        let ancestors = self.ancestors_to_propose(proposal_round);
         self.context
             .metrics
             .node_metrics
@@ -384,7 +390,7 @@ impl Core {
             assert!(
                 block.timestamp_ms() <= now,
                 "Violation: ancestor block {:?} has timestamp {}, greater than current timestamp {now}. Proposing for round {}.",
-                block, block.timestamp_ms(), clock_round
+                block, block.timestamp_ms(), proposal_round
             );
         });
 
@@ -401,7 +407,7 @@ impl Core {
         // Create the block and insert to storage.
         let block = Block::V1(BlockV1::new(
             self.context.committee.epoch(),
-            clock_round,
+            proposal_round,
             self.context.own_index,
             now,
             ancestors.iter().map(|b| b.reference()).collect(),
@@ -583,6 +589,91 @@ impl Core {
         self.consumer_availability = allow;
     }
 
+    /// Returns the highest round of an accepted block per authority, as known to this node. Authorities
+    /// from which no block has been accepted yet are reported at the genesis round. This is a cheap,
+    /// read-only view meant for diagnostics, e.g. to spot an authority that went silent when consensus stalls.
+    #[allow(unused)]
+    pub(crate) fn dag_frontier(&self) -> BTreeMap<AuthorityIndex, Round> {
+        let mut frontier = self
+            .dag_state
+            .read()
+            .get_last_cached_block_per_authority(Round::MAX)
+            .into_iter()
+            .map(|block| (block.author(), block.round()))
+            .collect::<BTreeMap<_, _>>();
+        let own_round = frontier.entry(self.context.own_index).or_default();
+        *own_round = (*own_round).max(self.last_proposed_round());
+        frontier
+    }
+
+    /// Returns the number of transactions submitted to consensus and still waiting to be included in a proposal.
+    #[allow(unused)]
+    pub(crate) fn pending_transactions(&self) -> usize {
+        self.transaction_consumer.pending_transactions()
+    }
+
+    /// Returns the total size in bytes of the transactions still waiting to be included in a proposal.
+    #[allow(unused)]
+    pub(crate) fn pending_transaction_bytes(&self) -> usize {
+        self.transaction_consumer.pending_transaction_bytes()
+    }
+
+    /// Returns the index of the last commit that has been sent to the consumer of consensus output.
+    #[allow(unused)]
+    pub(crate) fn last_committed_index(&self) -> CommitIndex {
+        self.commit_observer.last_sent_commit_index()
+    }
+
+    /// Returns true if the threshold clock is more than `threshold_rounds` behind the highest accepted round
+    /// in the DAG. Meant for health checks, to tell when this node is falling behind its peers.
+    #[allow(unused)]
+    pub(crate) fn is_lagging(&self, threshold_rounds: Round) -> bool {
+        let highest_accepted_round = self.dag_state.read().highest_accepted_round();
+        highest_accepted_round.saturating_sub(self.threshold_clock.get_round()) > threshold_rounds
+    }
+
+    /// Checks that `block` is consistent with the local state: it is not a genesis block, its ancestors are
+    /// from lower rounds, and all of them are known to this node, either cached or in store. Only used in tests,
+    /// to explain why a block is not accepted.
+    #[cfg(test)]
+    pub(crate) fn validate_block_against_state(
+        &self,
+        block: &VerifiedBlock,
+    ) -> ConsensusResult<()> {
+        if block.round() == GENESIS_ROUND {
+            return Err(ConsensusError::BlockRejected {
+                block_ref: block.reference(),
+                reason: "block is at the genesis round".to_string(),
+            });
+        }
+        if let Some(ancestor) = block
+            .ancestors()
+            .iter()
+            .find(|ancestor| ancestor.round >= block.round())
+        {
+            return Err(ConsensusError::InvalidAncestorRound {
+                ancestor: ancestor.round,
+                block: block.round(),
+            });
+        }
+        let exist = self
+            .dag_state
+            .read()
+            .contains_blocks(block.ancestors().to_vec());
+        if let Some((ancestor, _)) = block
+            .ancestors()
+            .iter()
+            .zip(exist)
+            .find(|(_, exists)| !exists)
+        {
+            return Err(ConsensusError::BlockRejected {
+                block_ref: block.reference(),
+                reason: format!("ancestor {ancestor} is not known"),
+            });
+        }
+        Ok(())
+    }
+
     /// Whether the core should propose new blocks.
     fn should_propose(&self) -> bool {
         self.consumer_availability
@@ -590,6 +681,29 @@ impl Core {
 
     /// Retrieves the next ancestors to propose to form a block at `clock_round` round.
     fn ancestors_to_propose(&mut self, clock_round: Round) -> Vec<VerifiedBlock> {
+        let ancestors = self.select_ancestors(clock_round);
+
+        // Update the last included ancestor block refs
+        for ancestor in &ancestors {
+            self.last_included_ancestors[ancestor.author()] = Some(ancestor.reference());
+        }
+
+        // TODO: this is for temporary sanity check - we might want to remove later on
+        let mut quorum = StakeAggregator::<QuorumThreshold>::new();
+        for ancestor in ancestors
+            .iter()
+            .filter(|block| block.round() == clock_round - 1)
+        {
+            quorum.add(ancestor.author(), &self.context.committee);
+        }
+        assert!(quorum.reached_threshold(&self.context.committee), "Fatal error, quorum not reached for parent round when proposing for round {}. Possible mismatch between DagState and Core.", clock_round);
+
+        ancestors
+    }
+
+    /// Computes the ancestors that a block at `clock_round` round would reference, without updating the
+    /// last included ancestors.
+    fn select_ancestors(&self, clock_round: Round) -> Vec<VerifiedBlock> {
         // Now take the ancestors before the clock_round (excluded) for each authority.
         let ancestors = self
             .dag_state
@@ -603,7 +717,7 @@ impl Core {
 
         // Propose only ancestors of higher rounds than what has already been proposed.
         // And always include own last proposed block first among ancestors.
-        let ancestors = iter::once(self.last_proposed_block.clone())
+        iter::once(self.last_proposed_block.clone())
             .chain(
                 ancestors
                     .into_iter()
@@ -615,24 +729,7 @@ impl Core {
                         Some(block)
                     }),
             )
-            .collect::<Vec<_>>();
-
-        // Update the last included ancestor block refs
-        for ancestor in &ancestors {
-            self.last_included_ancestors[ancestor.author()] = Some(ancestor.reference());
-        }
-
-        // TODO: this is for temporary sanity check - we might want to remove later on
-        let mut quorum = StakeAggregator::<QuorumThreshold>::new();
-        for ancestor in ancestors
-            .iter()
-            .filter(|block| block.round() == clock_round - 1)
-        {
-            quorum.add(ancestor.author(), &self.context.committee);
-        }
-        assert!(quorum.reached_threshold(&self.context.committee), "Fatal error, quorum not reached for parent round when proposing for round {}. Possible mismatch between DagState and Core.", clock_round);
-
-        ancestors
+            .collect()
     }
 
     /// Checks whether all the leaders of the round exist.
@@ -661,6 +758,31 @@ impl Core {
             .collect()
     }
 
+    /// Returns the round to propose a block for, given the current threshold clock round. This is the
+    /// clock round, unless proposing for the penultimate round is enabled and this authority is a leader
+    /// of the penultimate round that has not been proposed for yet.
+    fn proposal_round(&self, clock_round: Round) -> Round {
+        if !self
+            .context
+            .parameters
+            .propose_for_penultimate_round_when_leader
+        {
+            return clock_round;
+        }
+        let penultimate_round = clock_round.saturating_sub(1);
+        if penultimate_round <= self.last_proposed_round() {
+            return clock_round;
+        }
+        if self
+            .leaders(penultimate_round)
+            .iter()
+            .any(|slot| slot.authority == self.context.own_index)
+        {
+            return penultimate_round;
+        }
+        clock_round
+    }
+
     /// Returns the 1st leader of the round.
     fn first_leader(&self, round: Round) -> AuthorityIndex {
         self.leaders(round).first().unwrap().authority
@@ -678,6 +800,29 @@ impl Core {
     fn last_proposed_block(&self) -> &VerifiedBlock {
         &self.last_proposed_block
     }
+
+    /// Returns the references of the ancestors that a block proposed at `clock_round` round would include,
+    /// without consuming them.
+    #[cfg(test)]
+    pub(crate) fn preview_ancestors(&self, clock_round: Round) -> Vec<BlockRef> {
+        self.select_ancestors(clock_round)
+            .iter()
+            .map(|block| block.reference())
+            .collect()
+    }
+
+    /// Sets the threshold clock to the provided round, without requiring the blocks that would advance it.
+    /// The new round is signalled and reported the same way as when the clock advances by accepting blocks.
+    #[cfg(test)]
+    pub(crate) fn advance_clock_for_test(&mut self, round: Round) {
+        self.threshold_clock = ThresholdClock::new(round, self.context.clone());
+        self.signals.new_round(round);
+        self.context
+            .metrics
+            .node_metrics
+            .threshold_clock_round
+            .set(round as i64);
+    }
 }
 
 /// Senders of signals from Core, for outputs and events (ex new block produced).
@@ -1927,6 +2072,58 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_core_last_committed_index() {
+        telemetry_subscribers::init_for_testing();
+        let default_params = Parameters::default();
+
+        let (context, _) = Context::new_for_test(4);
+        let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+        for core_fixture in &cores {
+            assert_eq!(core_fixture.core.last_committed_index(), 0);
+        }
+
+        // Advance the network for a few rounds, the reported commit index should never go backwards.
+        let mut last_round_blocks = Vec::new();
+        let mut last_committed_indexes = vec![0; cores.len()];
+        for _round in 1..=6 {
+            let mut this_round_blocks = Vec::new();
+
+            // Wait for min round delay to allow blocks to be proposed.
+            sleep(default_params.min_round_delay).await;
+
+            for (core_fixture, last_committed_index) in
+                cores.iter_mut().zip(last_committed_indexes.iter_mut())
+            {
+                core_fixture
+                    .core
+                    .add_blocks(last_round_blocks.clone())
+                    .unwrap();
+                this_round_blocks.push(core_fixture.core.last_proposed_block().clone());
+
+                let committed_index = core_fixture.core.last_committed_index();
+                assert!(committed_index >= *last_committed_index);
+                *last_committed_index = committed_index;
+            }
+
+            last_round_blocks = this_round_blocks;
+        }
+
+        for core_fixture in cores {
+            // The reported index should have advanced up to the last commit persisted to store.
+            let last_commit = core_fixture
+                .store
+                .read_last_commit()
+                .unwrap()
+                .expect("last commit should be set");
+            assert!(last_commit.index() > 0);
+            assert_eq!(
+                core_fixture.core.last_committed_index(),
+                last_commit.index()
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_core_compress_proposal_references() {
         telemetry_subscribers::init_for_testing();
@@ -2007,6 +2204,399 @@ mod test {
         assert_eq!(all_stored_commits.len(), 6);
     }
 
+    #[tokio::test]
+    async fn test_core_propose_for_penultimate_round_when_leader() {
+        telemetry_subscribers::init_for_testing();
+
+        for propose_for_penultimate_round in [false, true] {
+            let (mut context, _) = Context::new_for_test(4);
+            context.parameters.propose_for_penultimate_round_when_leader =
+                propose_for_penultimate_round;
+            let min_round_delay = context.parameters.min_round_delay;
+            let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+
+            // Authority 3 is the leader of round 3.
+            let leader_authority = AuthorityIndex::new_for_test(3);
+
+            let mut last_round_blocks = Vec::new();
+            let mut all_blocks = Vec::new();
+            for round in 1..=3 {
+                let mut this_round_blocks = Vec::new();
+                for core_fixture in &mut cores {
+                    // do not produce any block for authority 3
+                    if core_fixture.core.context.own_index == leader_authority {
+                        continue;
+                    }
+                    core_fixture
+                        .core
+                        .add_blocks(last_round_blocks.clone())
+                        .unwrap();
+                    core_fixture.core.new_block(round, true).unwrap();
+
+                    let block = core_fixture.core.last_proposed_block();
+                    assert_eq!(block.round(), round);
+                    this_round_blocks.push(block.clone());
+                }
+                last_round_blocks = this_round_blocks.clone();
+                all_blocks.extend(this_round_blocks);
+            }
+
+            // Send all the blocks to authority 3, which advances its threshold clock to round 4 while
+            // it has not proposed for round 3 yet.
+            let core_fixture = &mut cores[leader_authority];
+            sleep(min_round_delay).await;
+            core_fixture.core.add_blocks(all_blocks).unwrap();
+
+            let block = core_fixture.core.last_proposed_block();
+            if propose_for_penultimate_round {
+                // The block is proposed for round 3, where authority 3 is the leader.
+                assert_eq!(block.round(), 3);
+                for block_ref in block.ancestors() {
+                    if block_ref.author == leader_authority {
+                        assert_eq!(block_ref.round, 1);
+                    } else {
+                        assert_eq!(block_ref.round, 2);
+                    }
+                }
+
+                // The next proposal is for the clock round.
+                sleep(min_round_delay).await;
+                let block = core_fixture.core.try_propose(false).unwrap().unwrap();
+                assert_eq!(block.round(), 4);
+            } else {
+                assert_eq!(block.round(), 4);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_core_preview_ancestors() {
+        telemetry_subscribers::init_for_testing();
+        let (context, _) = Context::new_for_test(4);
+        let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+        let core = &mut cores[0].core;
+
+        // Do not propose while blocks are added, so the own round stays at the block proposed on recovery.
+        core.set_consumer_availability(false);
+
+        let blocks = (1..=3)
+            .map(|authority| VerifiedBlock::new_for_test(TestBlock::new(1, authority).build()))
+            .collect::<Vec<_>>();
+        core.add_blocks(blocks.clone()).unwrap();
+
+        // Previewing the ancestors does not consume them, so it can be repeated.
+        let preview = core.preview_ancestors(2);
+        assert_eq!(preview.len(), 4);
+        assert_eq!(preview[0], core.last_proposed_block().reference());
+        for block in &blocks {
+            assert!(preview.contains(&block.reference()));
+        }
+        assert_eq!(core.preview_ancestors(2), preview);
+
+        // The proposal references exactly the previewed ancestors.
+        core.set_consumer_availability(true);
+        let block = core.try_propose(true).unwrap().unwrap();
+        assert_eq!(block.round(), 2);
+        assert_eq!(block.ancestors(), preview.as_slice());
+
+        // Once proposed, only the own block is left to be included.
+        assert_eq!(
+            core.preview_ancestors(3),
+            vec![core.last_proposed_block().reference()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_core_validate_block_against_state() {
+        telemetry_subscribers::init_for_testing();
+        let (context, _) = Context::new_for_test(4);
+        let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+        let core = &mut cores[0].core;
+        core.set_consumer_availability(false);
+
+        let block_1_1 = VerifiedBlock::new_for_test(TestBlock::new(1, 1).build());
+        core.add_blocks(vec![block_1_1.clone()]).unwrap();
+
+        // A block whose ancestors are all known is valid.
+        let valid = VerifiedBlock::new_for_test(
+            TestBlock::new(2, 1)
+                .set_ancestors(vec![
+                    block_1_1.reference(),
+                    core.last_proposed_block().reference(),
+                ])
+                .build(),
+        );
+        core.validate_block_against_state(&valid).unwrap();
+
+        // A block referencing an ancestor that was never received is rejected.
+        let unknown = VerifiedBlock::new_for_test(TestBlock::new(1, 2).build());
+        let block = VerifiedBlock::new_for_test(
+            TestBlock::new(2, 3)
+                .set_ancestors(vec![block_1_1.reference(), unknown.reference()])
+                .build(),
+        );
+        match core.validate_block_against_state(&block) {
+            Err(ConsensusError::BlockRejected { block_ref, reason }) => {
+                assert_eq!(block_ref, block.reference());
+                assert!(reason.contains(&unknown.reference().to_string()));
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+
+        // Ancestors must be from lower rounds.
+        let block = VerifiedBlock::new_for_test(
+            TestBlock::new(1, 3)
+                .set_ancestors(vec![block_1_1.reference()])
+                .build(),
+        );
+        assert!(matches!(
+            core.validate_block_against_state(&block),
+            Err(ConsensusError::InvalidAncestorRound {
+                ancestor: 1,
+                block: 1
+            })
+        ));
+
+        // Genesis blocks are rejected.
+        let genesis = VerifiedBlock::new_for_test(TestBlock::new(GENESIS_ROUND, 3).build());
+        assert!(matches!(
+            core.validate_block_against_state(&genesis),
+            Err(ConsensusError::BlockRejected { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_core_is_lagging() {
+        telemetry_subscribers::init_for_testing();
+        let (context, _) = Context::new_for_test(4);
+        let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+        let core = &mut cores[0].core;
+        core.set_consumer_availability(false);
+
+        let clock_round = core.threshold_clock.get_round();
+        assert!(!core.is_lagging(0));
+
+        // A chain of blocks from a single peer gets accepted, but does not form a quorum, so the clock does not
+        // advance.
+        let genesis = genesis_blocks(core.context.clone());
+        let mut ancestor = genesis[2].reference();
+        let mut chain_to = |core: &mut Core, from_round: Round, to_round: Round| {
+            let blocks: Vec<_> = (from_round..=to_round)
+                .map(|round| {
+                    let block = VerifiedBlock::new_for_test(
+                        TestBlock::new(round, 2)
+                            .set_ancestors(vec![ancestor])
+                            .build(),
+                    );
+                    ancestor = block.reference();
+                    block
+                })
+                .collect();
+            core.add_blocks(blocks).unwrap();
+        };
+
+        chain_to(core, 1, clock_round + 4);
+        assert_eq!(core.threshold_clock.get_round(), clock_round);
+        assert_eq!(
+            core.dag_state.read().highest_accepted_round(),
+            clock_round + 4
+        );
+        assert!(core.is_lagging(3));
+        assert!(!core.is_lagging(4));
+
+        chain_to(core, clock_round + 5, clock_round + 10);
+        assert_eq!(core.threshold_clock.get_round(), clock_round);
+        assert!(core.is_lagging(9));
+        assert!(!core.is_lagging(10));
+    }
+
+    #[tokio::test]
+    async fn test_core_ancestors_one_block_per_author() {
+        telemetry_subscribers::init_for_testing();
+        let (context, _) = Context::new_for_test(4);
+        let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+        let core = &mut cores[0].core;
+        core.set_consumer_availability(false);
+
+        // Authority 1 equivocates with several blocks at round 1.
+        let equivocating_blocks = (0..3)
+            .map(|timestamp_ms| {
+                VerifiedBlock::new_for_test(
+                    TestBlock::new(1, 1).set_timestamp_ms(timestamp_ms).build(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let other_blocks = (2..=3)
+            .map(|authority| VerifiedBlock::new_for_test(TestBlock::new(1, authority).build()))
+            .collect::<Vec<_>>();
+        core.add_blocks(
+            equivocating_blocks
+                .iter()
+                .chain(&other_blocks)
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        // At most one block per authority is retained as an ancestor.
+        let ancestors = core.preview_ancestors(2);
+        assert_eq!(ancestors.len(), 4);
+        let authors = ancestors
+            .iter()
+            .map(|ancestor| ancestor.author)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(authors.len(), 4);
+        assert_eq!(
+            ancestors
+                .iter()
+                .filter(|ancestor| equivocating_blocks
+                    .iter()
+                    .any(|block| block.reference() == **ancestor))
+                .count(),
+            1
+        );
+
+        core.set_consumer_availability(true);
+        let block = core.try_propose(true).unwrap().unwrap();
+        assert_eq!(block.ancestors(), ancestors.as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_core_dag_frontier() {
+        telemetry_subscribers::init_for_testing();
+        let (context, _) = Context::new_for_test(4);
+        let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+        let core = &mut cores[0].core;
+
+        // Do not propose while blocks are added, so the own round stays at the block proposed on recovery.
+        core.set_consumer_availability(false);
+
+        let block_1_1 = VerifiedBlock::new_for_test(TestBlock::new(1, 1).build());
+        let block_2_1 = VerifiedBlock::new_for_test(
+            TestBlock::new(2, 1)
+                .set_ancestors(vec![block_1_1.reference()])
+                .build(),
+        );
+        let block_1_2 = VerifiedBlock::new_for_test(TestBlock::new(1, 2).build());
+        core.add_blocks(vec![block_1_1, block_2_1, block_1_2])
+            .unwrap();
+
+        let frontier = core.dag_frontier();
+        assert_eq!(frontier.len(), 4);
+        assert_eq!(frontier[&AuthorityIndex::new_for_test(0)], 1);
+        assert_eq!(frontier[&AuthorityIndex::new_for_test(1)], 2);
+        assert_eq!(frontier[&AuthorityIndex::new_for_test(2)], 1);
+        // Nothing has been received from authority 3 yet.
+        assert_eq!(frontier[&AuthorityIndex::new_for_test(3)], GENESIS_ROUND);
+    }
+
+    #[tokio::test]
+    async fn test_core_propose_at_high_round_without_pending_ancestors() {
+        telemetry_subscribers::init_for_testing();
+        let (context, _) = Context::new_for_test(4);
+        let mut cores = create_cores(context, vec![1, 1, 1, 1]);
+        let fixture = &mut cores[0];
+        let core = &mut fixture.core;
+
+        // The block proposed on recovery has already included all the genesis blocks, so there are
+        // no pending ancestors left to propose.
+        assert_eq!(core.last_proposed_round(), 1);
+
+        core.advance_clock_for_test(10);
+        let mut new_round = fixture.signal_receivers.new_round_receiver();
+        assert_eq!(*new_round.borrow_and_update(), 10);
+        assert_eq!(
+            core.context
+                .metrics
+                .node_metrics
+                .threshold_clock_round
+                .get(),
+            10
+        );
+
+        // The leaders of round 9 do not exist, so no block should be proposed.
+        assert!(core.try_propose(false).unwrap().is_none());
+        assert_eq!(core.last_proposed_round(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_core_pending_transactions() {
+        telemetry_subscribers::init_for_testing();
+        let (context, mut key_pairs) = Context::new_for_test(4);
+        let context = Arc::new(context);
+
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+
+        let block_manager = BlockManager::new(
+            context.clone(),
+            dag_state.clone(),
+            Arc::new(NoopBlockVerifier),
+        );
+        let leader_schedule = Arc::new(LeaderSchedule::from_store(
+            context.clone(),
+            dag_state.clone(),
+        ));
+
+        let (transaction_client, tx_receiver) = TransactionClient::new(context.clone());
+        let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
+        let (signals, signal_receivers) = CoreSignals::new(context.clone());
+        // Need at least one subscriber to the block broadcast channel.
+        let _block_receiver = signal_receivers.block_broadcast_receiver();
+
+        let (sender, _receiver) = unbounded_channel("consensus_output");
+        let commit_observer = CommitObserver::new(
+            context.clone(),
+            CommitConsumer::new(sender.clone(), 0, 0),
+            dag_state.clone(),
+            store.clone(),
+            leader_schedule.clone(),
+        );
+
+        let mut core = Core::new(
+            context.clone(),
+            leader_schedule,
+            transaction_consumer,
+            block_manager,
+            true,
+            commit_observer,
+            signals,
+            key_pairs.remove(context.own_index.value()).1,
+            dag_state.clone(),
+        );
+        assert_eq!(core.pending_transactions(), 0);
+        assert_eq!(core.pending_transaction_bytes(), 0);
+
+        // Submit some transactions, the backlog should grow.
+        let mut total_bytes = 0;
+        for index in 0..3 {
+            let transaction =
+                bcs::to_bytes(&format!("Transaction {index}")).expect("Shouldn't fail");
+            total_bytes += transaction.len();
+            let _w = transaction_client
+                .submit_no_wait(vec![transaction])
+                .await
+                .unwrap();
+        }
+        assert_eq!(core.pending_transactions(), 3);
+        assert_eq!(core.pending_transaction_bytes(), total_bytes);
+
+        // Form a quorum for round 1, so new blocks get proposed and the transactions get included.
+        let last_proposed_round = core.last_proposed_round();
+        let blocks = vec![
+            VerifiedBlock::new_for_test(TestBlock::new(1, 1).build()),
+            VerifiedBlock::new_for_test(TestBlock::new(1, 2).build()),
+        ];
+        // Wait for min round delay to allow blocks to be proposed.
+        sleep(context.parameters.min_round_delay).await;
+        _ = core.add_blocks(blocks);
+        assert!(core.last_proposed_round() > last_proposed_round);
+
+        // The backlog should now be drained.
+        assert_eq!(core.pending_transactions(), 0);
+        assert_eq!(core.pending_transaction_bytes(), 0);
+    }
+
     pub(crate) async fn receive<T: Copy>(timeout: Duration, mut receiver: watch::Receiver<T>) -> T {
         tokio::time::timeout(timeout, receiver.changed())
             .await
@@ -124,6 +124,7 @@ pub(crate) struct NodeMetrics {
     pub(crate) invalid_blocks: IntCounterVec,
     pub(crate) rejected_blocks: IntCounterVec,
     pub(crate) rejected_future_blocks: IntCounterVec,
+    pub(crate) rejected_oversized_transactions: IntCounter,
     pub(crate) subscribed_blocks: IntCounterVec,
     pub(crate) verified_blocks: IntCounterVec,
     pub(crate) committed_leaders_total: IntCounterVec,
@@ -322,6 +323,11 @@ impl NodeMetrics {
                 &["authority"],
                 registry,
             ).unwrap(),
+            rejected_oversized_transactions: register_int_counter_with_registry!(
+                "rejected_oversized_transactions",
+                "Number of transactions dropped at consumption because they can never fit in a block",
+                registry,
+            ).unwrap(),
             subscribed_blocks: register_int_counter_vec_with_registry!(
                 "subscribed_blocks",
                 "Number of blocks received from each peer before verification",
@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 use mysten_metrics::monitored_mpsc::{channel, Receiver, Sender};
 use sui_protocol_config::ProtocolConfig;
@@ -30,31 +33,80 @@ pub(crate) struct TransactionsGuard {
     transactions: Vec<Transaction>,
 
     included_in_block_ack: oneshot::Sender<BlockRef>,
+
+    // Set when some of the transactions have been dropped by the `TransactionConsumer` for being oversized.
+    // The inclusion of such a batch is never acknowledged, and its `included_in_block_ack` is dropped instead.
+    has_dropped_transactions: bool,
+}
+
+/// Keeps track of the transactions that have been submitted via the `TransactionClient` but not yet
+/// consumed by the `TransactionConsumer`.
+#[derive(Default)]
+struct PendingTransactions {
+    count: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+impl PendingTransactions {
+    fn add(&self, count: usize, bytes: usize) {
+        self.count.fetch_add(count, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn remove(&self, count: usize, bytes: usize) {
+        self.count.fetch_sub(count, Ordering::Relaxed);
+        self.bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+/// The receiving end of the channel created by `TransactionClient::new`, to be handed over to the `TransactionConsumer`.
+pub(crate) struct TransactionReceiver {
+    receiver: Receiver<TransactionsGuard>,
+    pending: Arc<PendingTransactions>,
 }
 
 /// The TransactionConsumer is responsible for fetching the next transactions to be included for the block proposals.
 /// The transactions are submitted to a channel which is shared between the TransactionConsumer and the TransactionClient
 /// and are pulled every time the `next` method is called.
 pub(crate) struct TransactionConsumer {
+    context: Arc<Context>,
     tx_receiver: Receiver<TransactionsGuard>,
+    pending: Arc<PendingTransactions>,
     max_consumed_bytes_per_request: u64,
+    // The largest transaction that can ever be included in a block. Transactions above this size are
+    // dropped at consumption, otherwise they would be deferred forever and block all transactions behind them.
+    max_transaction_size: u64,
     max_consumed_transactions_per_request: u64,
     pending_transactions: Option<TransactionsGuard>,
 }
 
 impl TransactionConsumer {
     pub(crate) fn new(
-        tx_receiver: Receiver<TransactionsGuard>,
+        tx_receiver: TransactionReceiver,
         context: Arc<Context>,
         max_consumed_transactions_per_request: Option<u64>,
     ) -> Self {
+        let max_consumed_bytes_per_request = context
+            .protocol_config
+            .consensus_max_transactions_in_block_bytes();
+        let max_transaction_size = context
+            .protocol_config
+            .consensus_max_transaction_size_bytes()
+            .min(max_consumed_bytes_per_request);
+        let max_consumed_transactions_per_request = max_consumed_transactions_per_request
+            .unwrap_or_else(|| {
+                context
+                    .protocol_config
+                    .consensus_max_transactions_in_block_as_option()
+                    .unwrap_or(MAX_CONSUMED_TRANSACTIONS_PER_REQUEST)
+            });
         Self {
-            tx_receiver,
-            max_consumed_bytes_per_request: context
-                .protocol_config
-                .consensus_max_transactions_in_block_bytes(),
-            max_consumed_transactions_per_request: max_consumed_transactions_per_request
-                .unwrap_or(MAX_CONSUMED_TRANSACTIONS_PER_REQUEST),
+            context,
+            tx_receiver: tx_receiver.receiver,
+            pending: tx_receiver.pending,
+            max_consumed_bytes_per_request,
+            max_transaction_size,
+            max_consumed_transactions_per_request,
             pending_transactions: None,
         }
     }
@@ -64,6 +116,8 @@ impl TransactionConsumer {
     // This returns one or more transactions to be included in the block and a callback to acknowledge the inclusion of those transactions.
     // Note that a TransactionsGuard may be partially consumed and the rest saved for the next pull, in which case its `included_in_block_ack`
     // will not be signalled in the callback.
+    // Transactions larger than `max_transaction_size` are dropped, as they could never be included in a block. The
+    // `included_in_block_ack` of a batch with dropped transactions is dropped instead of signalled, so its receiver errors.
     pub(crate) fn next(&mut self) -> (Vec<Transaction>, Box<dyn FnOnce(BlockRef)>) {
         let mut transactions = Vec::new();
         let mut acks = Vec::new();
@@ -72,18 +126,35 @@ impl TransactionConsumer {
         // Handle one batch of incoming transactions from TransactionGuard.
         // Returns the remaining txs as a new TransactionGuard, if the batch breaks any limit.
         let mut handle_txs = |t: TransactionsGuard| -> Option<TransactionsGuard> {
-            // Here we assume that a transaction can always fit in `max_fetched_bytes_per_request`
+            let mut has_dropped_transactions = t.has_dropped_transactions;
             let remaining_txs: Vec<_> = t
                 .transactions
                 .into_iter()
                 .filter_map(|tx| {
-                    if (total_size + tx.data().len()) as u64 > self.max_consumed_bytes_per_request
+                    if tx.data().len() as u64 > self.max_transaction_size {
+                        self.pending.remove(1, tx.data().len());
+                        // The transaction can never fit in a block, drop it so it does not wedge the proposals.
+                        warn!(
+                            "Dropping transaction of size {}B, over the limit of {}B",
+                            tx.data().len(),
+                            self.max_transaction_size
+                        );
+                        self.context
+                            .metrics
+                            .node_metrics
+                            .rejected_oversized_transactions
+                            .inc();
+                        has_dropped_transactions = true;
+                        None
+                    } else if (total_size + tx.data().len()) as u64
+                        > self.max_consumed_bytes_per_request
                         || transactions.len() as u64 >= self.max_consumed_transactions_per_request
                     {
                         // Adding this tx would exceed the size limit or the number of txs limit, cache it for the next pull.
                         Some(tx)
                     } else {
                         total_size += tx.data().len();
+                        self.pending.remove(1, tx.data().len());
                         transactions.push(tx);
                         None
                     }
@@ -93,7 +164,10 @@ impl TransactionConsumer {
             if remaining_txs.is_empty() {
                 // The batch has been fully consumed, register its ack.
                 // In case a batch gets split, ack shall only be sent when the last transaction is included in the block.
-                acks.push(t.included_in_block_ack);
+                // A batch with dropped transactions is never acknowledged, its ack is dropped here instead.
+                if !has_dropped_transactions {
+                    acks.push(t.included_in_block_ack);
+                }
                 None
             } else {
                 // If we went over the any limit while processing the batch, return the remainings.
@@ -101,6 +175,7 @@ impl TransactionConsumer {
                 Some(TransactionsGuard {
                     transactions: remaining_txs,
                     included_in_block_ack: t.included_in_block_ack,
+                    has_dropped_transactions,
                 })
             }
         };
@@ -129,6 +204,16 @@ impl TransactionConsumer {
         )
     }
 
+    /// Returns the number of transactions that have been submitted but not yet consumed.
+    pub(crate) fn pending_transactions(&self) -> usize {
+        self.pending.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total size in bytes of the transactions that have been submitted but not yet consumed.
+    pub(crate) fn pending_transaction_bytes(&self) -> usize {
+        self.pending.bytes.load(Ordering::Relaxed)
+    }
+
     #[cfg(test)]
     fn is_empty(&mut self) -> bool {
         if self.pending_transactions.is_some() {
@@ -145,6 +230,7 @@ impl TransactionConsumer {
 #[derive(Clone)]
 pub struct TransactionClient {
     sender: Sender<TransactionsGuard>,
+    pending: Arc<PendingTransactions>,
     max_transaction_size: u64,
 }
 
@@ -158,17 +244,19 @@ pub enum ClientError {
 }
 
 impl TransactionClient {
-    pub(crate) fn new(context: Arc<Context>) -> (Self, Receiver<TransactionsGuard>) {
+    pub(crate) fn new(context: Arc<Context>) -> (Self, TransactionReceiver) {
         let (sender, receiver) = channel("consensus_input", MAX_PENDING_TRANSACTIONS);
+        let pending = Arc::new(PendingTransactions::default());
 
         (
             Self {
                 sender,
+                pending: pending.clone(),
                 max_transaction_size: context
                     .protocol_config
                     .consensus_max_transaction_size_bytes(),
             },
-            receiver,
+            TransactionReceiver { receiver, pending },
         )
     }
 
@@ -188,7 +276,7 @@ impl TransactionClient {
     /// That shouldn't be the common case as sizes should be aligned between consensus and client. The method returns
     /// a receiver to wait on until the transactions has been included in the next block to get proposed. The consumer should
     /// wait on it to consider as inclusion acknowledgement. If the receiver errors then consensus is shutting down and transaction
-    /// has not been included to any block.
+    /// has not been included to any block, or some of the transactions have been dropped for being oversized.
     /// If multiple transactions are submitted, the receiver will be signalled when the last transaction is included in the block.
     pub(crate) async fn submit_no_wait(
         &self,
@@ -204,14 +292,23 @@ impl TransactionClient {
             }
         }
 
+        let count = transactions.len();
+        let bytes = transactions.iter().map(|t| t.len()).sum();
         let t = TransactionsGuard {
             transactions: transactions.into_iter().map(Transaction::new).collect(),
             included_in_block_ack: included_in_block_ack_send,
+            has_dropped_transactions: false,
         };
+        // Account for the transactions before sending them, so the consumer never observes them as consumed
+        // before they have been counted.
+        self.pending.add(count, bytes);
         self.sender
             .send(t)
             .await
-            .tap_err(|e| error!("Submit transactions failed with {:?}", e))
+            .tap_err(|e| {
+                self.pending.remove(count, bytes);
+                error!("Submit transactions failed with {:?}", e)
+            })
             .map_err(|e| ClientError::ConsensusShuttingDown(e.to_string()))?;
         Ok(included_in_block_ack_receive)
     }
@@ -381,6 +478,46 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn submit_over_max_transactions_in_block_and_consume() {
+        let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+            config.set_consensus_max_transaction_size_bytes_for_testing(2_000);
+            config.set_consensus_max_transactions_in_block_bytes_for_testing(2_000);
+            config.set_consensus_max_transactions_in_block_for_testing(5);
+            config
+        });
+
+        let context = Arc::new(Context::new_for_test(4).0);
+        let (client, tx_receiver) = TransactionClient::new(context.clone());
+        let mut consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
+
+        // submit many tiny transactions, well below the bytes limit
+        for i in 0..12 {
+            let transaction =
+                bcs::to_bytes(&format!("t{i}")).expect("Serialization should not fail.");
+            let _w = client
+                .submit_no_wait(vec![transaction])
+                .await
+                .expect("Shouldn't submit successfully transaction");
+        }
+
+        // the number of transactions pulled per block is capped by the count limit
+        let mut all_transactions = Vec::new();
+        for expected in [5, 5, 2] {
+            let (transactions, _ack_transactions) = consumer.next();
+            assert_eq!(transactions.len(), expected);
+            all_transactions.extend(transactions);
+        }
+
+        // try to pull again transactions, result should be empty
+        assert!(consumer.is_empty());
+
+        for (i, t) in all_transactions.iter().enumerate() {
+            let t: String = bcs::from_bytes(t.data()).unwrap();
+            assert_eq!(format!("t{i}"), t);
+        }
+    }
+
     #[tokio::test]
     async fn submit_large_batch_and_ack() {
         let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
@@ -471,4 +608,65 @@ mod tests {
             assert!(w.await.is_ok());
         }
     }
+
+    #[tokio::test]
+    async fn drop_oversized_transaction_on_consume() {
+        // Allow the client to accept transactions that can never fit in a block, to emulate misaligned limits.
+        let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+            config.set_consensus_max_transaction_size_bytes_for_testing(2_000);
+            config.set_consensus_max_transactions_in_block_bytes_for_testing(100);
+            config
+        });
+
+        let context = Arc::new(Context::new_for_test(4).0);
+        let (client, tx_receiver) = TransactionClient::new(context.clone());
+        let mut consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
+
+        let small_transaction = |i: usize| {
+            bcs::to_bytes(&format!("transaction {i}")).expect("Serialization should not fail.")
+        };
+        let mut receivers = Vec::new();
+        receivers.push(
+            client
+                .submit_no_wait(vec![small_transaction(0)])
+                .await
+                .expect("Shouldn't submit successfully transaction"),
+        );
+        receivers.push(
+            client
+                .submit_no_wait(vec![vec![0u8; 200]])
+                .await
+                .expect("Shouldn't submit successfully transaction"),
+        );
+        receivers.push(
+            client
+                .submit_no_wait(vec![small_transaction(1)])
+                .await
+                .expect("Shouldn't submit successfully transaction"),
+        );
+
+        // The oversized transaction is dropped and does not prevent the following ones from being consumed.
+        let (transactions, ack_transactions) = consumer.next();
+        assert_eq!(transactions.len(), 2);
+        for (i, t) in transactions.iter().enumerate() {
+            let t: String = bcs::from_bytes(t.data()).unwrap();
+            assert_eq!(format!("transaction {i}").to_string(), t);
+        }
+        assert_eq!(
+            context
+                .metrics
+                .node_metrics
+                .rejected_oversized_transactions
+                .get(),
+            1
+        );
+        assert!(consumer.is_empty());
+
+        ack_transactions(BlockRef::MIN);
+        let mut receivers = receivers.into_iter();
+        assert!(receivers.next().unwrap().await.is_ok());
+        // The inclusion of the oversized transaction is not acknowledged.
+        assert!(receivers.next().unwrap().await.is_err());
+        assert!(receivers.next().unwrap().await.is_ok());
+    }
 }
@@ -11,39 +11,51 @@ mod tests;
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
 use anyhow::{anyhow, Result};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 use fastcrypto::hash::{HashFunction, Sha3_256};
+use futures::{StreamExt, TryStreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
+use integer_encoding::{VarInt, VarIntReader};
 use num_enum::IntoPrimitive;
 use num_enum::TryFromPrimitive;
 use object_store::path::Path;
 use prometheus::Registry;
+use rand::Rng;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::io::{BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::io::{self, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::num::NonZeroUsize;
 use std::ops::Range;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use sui_config::genesis::Genesis;
-use sui_config::node::ArchiveReaderConfig;
+use sui_config::node::{ArchiveReaderConfig, ArchiveRetryConfig};
 use sui_config::object_storage_config::ObjectStoreConfig;
-use sui_storage::blob::{Blob, BlobEncoding};
-use sui_storage::object_store::util::{get, put};
+use sui_storage::blob::{Blob, BlobEncoding, BlobIter, BLOB_ENCODING_BYTES};
 use sui_storage::object_store::{ObjectStoreGetExt, ObjectStorePutExt};
-use sui_storage::{compute_sha3_checksum, compute_sha3_checksum_for_bytes, SHA3_BYTES};
+use sui_storage::{
+    compute_sha3_checksum, compute_sha3_checksum_for_bytes, FileCompression, Sha3Writer,
+    StorageFormat, SHA3_BYTES,
+};
 use sui_types::base_types::ExecutionData;
-use sui_types::messages_checkpoint::{FullCheckpointContents, VerifiedCheckpointContents};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, FullCheckpointContents, VerifiedCheckpointContents,
+};
 use sui_types::storage::{SingleCheckpointSharedInMemoryStore, WriteStore};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[allow(rustdoc::invalid_html_tags)]
 /// Checkpoints and summaries are persisted as blob files. Files are committed to local store
 /// by duration or file size. Committed files are synced with the remote store continuously. Files are
-/// optionally compressed with the zstd compression format. Filenames follow the format
+/// optionally compressed with the zstd or lz4 compression format, as recorded in the header of
+/// each file, so one archive may hold files of both formats. Filenames follow the format
 /// <checkpoint_seq_num>.<suffix> where `checkpoint_seq_num` is the first checkpoint present in that
 /// file. MANIFEST is the index and source of truth for all files present in the archive.
+/// Transaction events may optionally be archived as well, in event files which hold one blob with
+/// the events of all the transactions of each checkpoint.
 ///
 /// State Archival Directory Layout
 ///  - archive/
@@ -53,6 +65,7 @@ use tracing::{error, info};
 ///        - 0.sum
 ///        - 1000.chk
 ///        - 1000.sum
+///        - 1000.evt
 ///        - 3000.chk
 ///        - 3000.sum
 ///        - ...
@@ -92,20 +105,119 @@ use tracing::{error, info};
 ///└──────────────────────────────┘
 pub const CHECKPOINT_FILE_MAGIC: u32 = 0x0000DEAD;
 pub const SUMMARY_FILE_MAGIC: u32 = 0x0000CAFE;
+pub const EVENTS_FILE_MAGIC: u32 = 0x0000BEEF;
 const MANIFEST_FILE_MAGIC: u32 = 0x00C0FFEE;
 const MAGIC_BYTES: usize = 4;
 const CHECKPOINT_FILE_SUFFIX: &str = "chk";
 const SUMMARY_FILE_SUFFIX: &str = "sum";
+const EVENTS_FILE_SUFFIX: &str = "evt";
 const EPOCH_DIR_PREFIX: &str = "epoch_";
 const MANIFEST_FILENAME: &str = "MANIFEST";
+/// Deadline for reading the manifest, after which the remote store is considered unavailable
+pub const DEFAULT_MANIFEST_READ_TIMEOUT: Duration = Duration::from_secs(300);
 
 #[derive(
-    Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, TryFromPrimitive, IntoPrimitive,
+    Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, TryFromPrimitive, IntoPrimitive,
 )]
 #[repr(u8)]
 pub enum FileType {
     CheckpointContent = 0,
     CheckpointSummary,
+    Events,
+}
+
+/// Header at the start of every checkpoint, summary and events blob file
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BlobFileHeader {
+    pub magic: u32,
+    pub storage_format: StorageFormat,
+    pub compression: FileCompression,
+}
+
+impl BlobFileHeader {
+    /// Size of the encoded header in bytes
+    pub const SIZE: usize = MAGIC_BYTES + 2;
+
+    pub fn new(magic: u32, storage_format: StorageFormat, compression: FileCompression) -> Self {
+        BlobFileHeader {
+            magic,
+            storage_format,
+            compression,
+        }
+    }
+
+    /// Read the header from the start of a blob file, rejecting unknown magic bytes
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self> {
+        let magic = reader.read_u32::<BigEndian>()?;
+        Self::validate_magic(magic)?;
+        let storage_format = StorageFormat::try_from(reader.read_u8()?)?;
+        let compression = FileCompression::try_from(reader.read_u8()?)?;
+        Ok(BlobFileHeader {
+            magic,
+            storage_format,
+            compression,
+        })
+    }
+
+    /// Read the header from the start of a blob file, failing if it is not of the expected type
+    pub fn read_expected<R: Read>(reader: &mut R, expected_magic: u32) -> Result<Self> {
+        let header = Self::read(reader)?;
+        if header.magic != expected_magic {
+            return Err(anyhow!(
+                "Unexpected magic in blob file header: {:#010x}, expected: {:#010x}",
+                header.magic,
+                expected_magic
+            ));
+        }
+        Ok(header)
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        Self::validate_magic(self.magic)?;
+        writer.write_u32::<BigEndian>(self.magic)?;
+        writer.write_u8(self.storage_format.into())?;
+        writer.write_u8(self.compression.into())?;
+        Ok(())
+    }
+
+    fn validate_magic(magic: u32) -> Result<()> {
+        if magic != CHECKPOINT_FILE_MAGIC
+            && magic != SUMMARY_FILE_MAGIC
+            && magic != EVENTS_FILE_MAGIC
+        {
+            return Err(anyhow!(
+                "Unknown magic in blob file header: {:#010x}, expected checkpoint ({:#010x}), summary ({:#010x}) or events ({:#010x}) magic",
+                magic,
+                CHECKPOINT_FILE_MAGIC,
+                SUMMARY_FILE_MAGIC,
+                EVENTS_FILE_MAGIC
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Validate the header of a downloaded blob file against the expected magic and return a reader
+/// over its decompressed blobs
+pub(crate) fn open_blob_file(
+    expected_magic: u32,
+    bytes: Bytes,
+) -> Result<(Box<dyn Read>, StorageFormat)> {
+    let mut reader = bytes.reader();
+    let header = BlobFileHeader::read_expected(&mut reader, expected_magic)?;
+    let reader = header.compression.bytes_decompress(reader.into_inner())?;
+    Ok((reader, header.storage_format))
+}
+
+/// Iterate over the items stored in a downloaded blob file with the expected magic
+pub(crate) fn make_blob_file_iterator<T: DeserializeOwned>(
+    expected_magic: u32,
+    bytes: Bytes,
+) -> Result<impl Iterator<Item = T>> {
+    let (reader, storage_format) = open_blob_file(expected_magic, bytes)?;
+    match storage_format {
+        StorageFormat::Blob => Ok(BlobIter::new(reader)),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -116,9 +228,13 @@ pub struct FileMetadata {
     pub sha3_digest: [u8; 32],
 }
 
+fn epoch_dir_path(epoch_num: u64) -> Path {
+    Path::from(format!("{}{}", EPOCH_DIR_PREFIX, epoch_num))
+}
+
 impl FileMetadata {
     pub fn file_path(&self) -> Path {
-        let dir_path = Path::from(format!("{}{}", EPOCH_DIR_PREFIX, self.epoch_num));
+        let dir_path = epoch_dir_path(self.epoch_num);
         match self.file_type {
             FileType::CheckpointContent => dir_path.child(&*format!(
                 "{}.{CHECKPOINT_FILE_SUFFIX}",
@@ -128,6 +244,10 @@ impl FileMetadata {
                 "{}.{SUMMARY_FILE_SUFFIX}",
                 self.checkpoint_seq_range.start
             )),
+            FileType::Events => dir_path.child(&*format!(
+                "{}.{EVENTS_FILE_SUFFIX}",
+                self.checkpoint_seq_range.start
+            )),
         }
     }
 }
@@ -140,12 +260,43 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+/// Metadata of an archived file, along with its size and creation time
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FileMetadataV2 {
+    pub metadata: FileMetadata,
+    /// Size of the file in bytes
+    pub file_size: u64,
+    /// Creation time of the file, in seconds since the unix epoch
+    pub created_at: u64,
+}
+
+impl From<FileMetadata> for FileMetadataV2 {
+    /// The size and creation time of the file are unknown, and left as 0
+    fn from(metadata: FileMetadata) -> Self {
+        FileMetadataV2 {
+            metadata,
+            file_size: 0,
+            created_at: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ManifestV2 {
+    pub archive_version: u8,
+    pub next_checkpoint_seq_num: u64,
+    pub file_metadata: Vec<FileMetadataV2>,
+    pub epoch: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    V2(ManifestV2),
 }
 
 impl Manifest {
+    /// Creates an empty V1 manifest, which every reader can decode
     pub fn new(epoch: u64, next_checkpoint_seq_num: u64) -> Self {
         Manifest::V1(ManifestV1 {
             archive_version: 1,
@@ -154,55 +305,356 @@ impl Manifest {
             epoch,
         })
     }
+    /// Creates an empty V2 manifest, which records the size and creation time of every file. Only
+    /// readers which know about V2 can decode it, see `upgrade_to_v2`.
+    pub fn new_v2(epoch: u64, next_checkpoint_seq_num: u64) -> Self {
+        Manifest::V2(ManifestV2 {
+            archive_version: 2,
+            next_checkpoint_seq_num,
+            file_metadata: vec![],
+            epoch,
+        })
+    }
+    /// Converts a V1 manifest to V2, recording the size and creation time of its files as unknown.
+    /// A V2 manifest is left as is.
+    pub fn upgrade_to_v2(&mut self) {
+        if let Manifest::V1(manifest) = self {
+            *self = Manifest::V2(ManifestV2 {
+                archive_version: 2,
+                next_checkpoint_seq_num: manifest.next_checkpoint_seq_num,
+                file_metadata: std::mem::take(&mut manifest.file_metadata)
+                    .into_iter()
+                    .map(FileMetadataV2::from)
+                    .collect(),
+                epoch: manifest.epoch,
+            });
+        }
+    }
     pub fn files(&self) -> Vec<FileMetadata> {
         match self {
             Manifest::V1(manifest) => manifest.file_metadata.clone(),
+            Manifest::V2(manifest) => manifest
+                .file_metadata
+                .iter()
+                .map(|f| f.metadata.clone())
+                .collect(),
+        }
+    }
+    /// Returns the files along with their size and creation time, or `None` if this manifest
+    /// does not record them
+    pub fn files_v2(&self) -> Option<Vec<FileMetadataV2>> {
+        match self {
+            Manifest::V1(_) => None,
+            Manifest::V2(manifest) => Some(manifest.file_metadata.clone()),
         }
     }
     pub fn epoch_num(&self) -> u64 {
         match self {
             Manifest::V1(manifest) => manifest.epoch,
+            Manifest::V2(manifest) => manifest.epoch,
         }
     }
+    /// Returns the distinct epoch directories holding the archived files, ordered by epoch.
+    pub fn epoch_dir_paths(&self) -> Vec<Path> {
+        self.files()
+            .iter()
+            .map(|f| f.epoch_num)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(epoch_dir_path)
+            .collect()
+    }
     pub fn next_checkpoint_seq_num(&self) -> u64 {
         match self {
             Manifest::V1(manifest) => manifest.next_checkpoint_seq_num,
+            Manifest::V2(manifest) => manifest.next_checkpoint_seq_num,
+        }
+    }
+    /// Returns the files in this manifest which are not in `previous`. Files are identified by
+    /// their file type and checkpoint range.
+    pub fn files_added_since(&self, previous: &Manifest) -> Vec<FileMetadata> {
+        let previous_files = previous.files_by_key();
+        self.files()
+            .into_iter()
+            .filter(|f| {
+                !previous_files.contains_key(&(f.file_type, f.checkpoint_seq_range.clone()))
+            })
+            .collect()
+    }
+    /// Returns the files in this manifest which are also in `previous`, but with a different
+    /// sha3 digest.
+    pub fn files_changed_since(&self, previous: &Manifest) -> Vec<FileMetadata> {
+        let previous_files = previous.files_by_key();
+        self.files()
+            .into_iter()
+            .filter(|f| {
+                previous_files
+                    .get(&(f.file_type, f.checkpoint_seq_range.clone()))
+                    .is_some_and(|digest| *digest != f.sha3_digest)
+            })
+            .collect()
+    }
+    /// Checks that this manifest does not regress from `previous`: every file in `previous` must
+    /// still be present with the same digest, and `next_checkpoint_seq_num` must not go backwards.
+    pub fn verify_no_regression_since(&self, previous: &Manifest) -> Result<()> {
+        if self.next_checkpoint_seq_num() < previous.next_checkpoint_seq_num() {
+            return Err(anyhow!(
+                "Manifest regresses next checkpoint from {} to {}",
+                previous.next_checkpoint_seq_num(),
+                self.next_checkpoint_seq_num()
+            ));
+        }
+        let removed = previous.files_added_since(self);
+        if !removed.is_empty() {
+            return Err(anyhow!(
+                "Manifest is missing {} previously archived files, first: {:?}",
+                removed.len(),
+                removed[0].file_path()
+            ));
+        }
+        let changed = self.files_changed_since(previous);
+        if !changed.is_empty() {
+            return Err(anyhow!(
+                "Manifest changes the digest of {} previously archived files, first: {:?}",
+                changed.len(),
+                changed[0].file_path()
+            ));
         }
+        Ok(())
     }
-    pub fn next_checkpoint_after_epoch(&self, epoch_num: u64) -> u64 {
+    /// Returns the files which appear more than once in this manifest, i.e. with the same file
+    /// type and checkpoint range as an earlier entry, wherever they are. `update` appends files
+    /// without any check, so archives assembled from several sources may contain duplicates.
+    pub fn find_duplicate_files(&self) -> Vec<FileMetadata> {
+        let mut seen = HashSet::new();
+        self.files()
+            .into_iter()
+            .filter(|f| !seen.insert((f.file_type, f.checkpoint_seq_range.clone())))
+            .collect()
+    }
+    /// Removes the files returned by `find_duplicate_files`, keeping the first entry for each file
+    /// type and checkpoint range.
+    pub fn dedup(&mut self) {
         match self {
             Manifest::V1(manifest) => {
-                let mut summary_files: Vec<_> = manifest
+                let mut seen = HashSet::new();
+                manifest
                     .file_metadata
-                    .clone()
+                    .retain(|f| seen.insert((f.file_type, f.checkpoint_seq_range.clone())));
+            }
+            Manifest::V2(manifest) => {
+                let mut seen = HashSet::new();
+                manifest.file_metadata.retain(|f| {
+                    seen.insert((
+                        f.metadata.file_type,
+                        f.metadata.checkpoint_seq_range.clone(),
+                    ))
+                });
+            }
+        }
+    }
+    fn files_by_key(&self) -> HashMap<(FileType, Range<u64>), [u8; 32]> {
+        self.files()
+            .into_iter()
+            .map(|f| ((f.file_type, f.checkpoint_seq_range), f.sha3_digest))
+            .collect()
+    }
+    /// Returns the first checkpoint of the archive, which is genesis unless the epochs before
+    /// the first archived file were pruned, see `prune_epochs_before`.
+    pub fn first_checkpoint_seq_num(&self) -> u64 {
+        let mut summary_files: Vec<_> = self
+            .files()
+            .into_iter()
+            .filter(|f| f.file_type == FileType::CheckpointSummary)
+            .collect();
+        summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        low_watermark(&summary_files)
+    }
+    /// Returns the summary files sorted by checkpoint, or an error unless they cover a contiguous
+    /// range of checkpoints starting at `first_checkpoint_seq_num`.
+    fn contiguous_summary_files(&self) -> Result<Vec<FileMetadata>> {
+        let mut summary_files: Vec<_> = self
+            .files()
+            .into_iter()
+            .filter(|f| f.file_type == FileType::CheckpointSummary)
+            .collect();
+        if summary_files.is_empty() {
+            return Err(anyhow!("archive has no summary files"));
+        }
+        summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        verify_contiguous_files(&summary_files)?;
+        Ok(summary_files)
+    }
+    /// Returns the ranges of checkpoints which are missing between the summary files of the
+    /// archive, in ascending order. Checkpoints before the first file are not reported.
+    pub fn find_gaps(&self) -> Vec<Range<u64>> {
+        let mut summary_files: Vec<_> = self
+            .files()
+            .into_iter()
+            .filter(|f| f.file_type == FileType::CheckpointSummary)
+            .collect();
+        summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        summary_files
+            .windows(2)
+            .filter(|w| w[1].checkpoint_seq_range.start > w[0].checkpoint_seq_range.end)
+            .map(|w| w[0].checkpoint_seq_range.end..w[1].checkpoint_seq_range.start)
+            .collect()
+    }
+    /// Returns the range of checkpoints covered by the archive, `0..next_checkpoint_seq_num`, or
+    /// `None` if its summary files do not cover all of it, i.e. if the archive is empty, does not
+    /// start at genesis or has gaps (see `find_gaps`).
+    pub fn covered_range(&self) -> Option<Range<u64>> {
+        let summary_ranges: Vec<_> = self
+            .files()
+            .into_iter()
+            .filter(|f| f.file_type == FileType::CheckpointSummary)
+            .map(|f| f.checkpoint_seq_range)
+            .collect();
+        let start = summary_ranges.iter().map(|r| r.start).min()?;
+        let end = summary_ranges.iter().map(|r| r.end).max()?;
+        let covered =
+            start == 0 && end == self.next_checkpoint_seq_num() && self.find_gaps().is_empty();
+        covered.then(|| 0..end)
+    }
+    /// Checks that the summary files of the archive cover a contiguous range of checkpoints
+    /// starting at `first_checkpoint_seq_num`. This fails for an empty archive.
+    pub fn verify_contiguity(&self) -> Result<()> {
+        self.contiguous_summary_files().map(|_| ())
+    }
+    /// Returns the first checkpoint of the earliest epoch after `epoch_num` in the archive, or
+    /// `u64::MAX` if the archive has no later epoch yet. Fails unless the archive is contiguous,
+    /// see `verify_contiguity`. See also `last_checkpoint_of_epoch`.
+    pub fn next_checkpoint_after_epoch(&self, epoch_num: u64) -> Result<u64> {
+        Ok(self
+            .contiguous_summary_files()?
+            .iter()
+            .find(|f| f.epoch_num > epoch_num)
+            .map(|f| f.checkpoint_seq_range.start)
+            .unwrap_or(u64::MAX))
+    }
+    /// Returns the last checkpoint of `epoch_num`, which is the checkpoint just before
+    /// `next_checkpoint_after_epoch(epoch_num)`. Returns `None` if the archive does not yet
+    /// contain a later epoch, as the end of `epoch_num` is then not known.
+    pub fn last_checkpoint_of_epoch(&self, epoch_num: u64) -> Result<Option<u64>> {
+        Ok(match self.next_checkpoint_after_epoch(epoch_num)? {
+            u64::MAX => None,
+            next_checkpoint => next_checkpoint.checked_sub(1),
+        })
+    }
+    /// Builds the index used to find the file containing a checkpoint. The index is a snapshot of
+    /// the manifest, so it should be rebuilt whenever the manifest changes.
+    pub fn index(&self) -> ManifestIndex {
+        let mut files_by_type: HashMap<FileType, Vec<FileMetadata>> = HashMap::new();
+        for file in self.files() {
+            files_by_type.entry(file.file_type).or_default().push(file);
+        }
+        for files in files_by_type.values_mut() {
+            files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        }
+        ManifestIndex {
+            next_checkpoint_seq_num: self.next_checkpoint_seq_num(),
+            files_by_type,
+        }
+    }
+    /// Returns the epochs for which all the checkpoints are in the archive, in ascending order. An
+    /// epoch is complete if its summary files cover a contiguous range of checkpoints, which its
+    /// content files cover as well, and which starts where the previous epoch ends (or at the first
+    /// checkpoint of the archive)
+    /// and ends where a later epoch starts. The last epoch in the archive is thus never complete,
+    /// as its end is not known yet.
+    pub fn complete_epochs(&self) -> Vec<u64> {
+        let first_checkpoint = self.first_checkpoint_seq_num();
+        let mut summary_ranges: BTreeMap<u64, Vec<(u64, u64)>> = BTreeMap::new();
+        let mut content_ranges: BTreeMap<u64, Vec<(u64, u64)>> = BTreeMap::new();
+        for f in self.files() {
+            let ranges = match f.file_type {
+                FileType::CheckpointSummary => &mut summary_ranges,
+                FileType::CheckpointContent => &mut content_ranges,
+                // events are optional, and do not make an epoch any less complete
+                FileType::Events => continue,
+            };
+            ranges
+                .entry(f.epoch_num)
+                .or_default()
+                .push((f.checkpoint_seq_range.start, f.checkpoint_seq_range.end));
+        }
+        let summary_starts: HashSet<u64> = summary_ranges
+            .values()
+            .flatten()
+            .map(|(start, _)| *start)
+            .collect();
+        let summary_ends: HashSet<u64> = summary_ranges
+            .values()
+            .flatten()
+            .map(|(_, end)| *end)
+            .collect();
+        summary_ranges
+            .into_iter()
+            .filter_map(|(epoch_num, mut summaries)| {
+                summaries.sort();
+                let mut contents = content_ranges.remove(&epoch_num)?;
+                contents.sort();
+                let start = summaries.first()?.0;
+                let end = summaries.last()?.1;
+                let complete = summaries == contents
+                    && summaries.windows(2).all(|w| w[0].1 == w[1].0)
+                    && (start == first_checkpoint || summary_ends.contains(&start))
+                    && summary_starts.contains(&end);
+                complete.then_some(epoch_num)
+            })
+            .collect()
+    }
+    /// Removes the metadata of all files of epochs before `epoch` from the manifest and returns
+    /// it. `next_checkpoint_seq_num` is left untouched. Fails if `epoch` is after the current
+    /// epoch of the manifest, as the current epoch must not be pruned.
+    pub fn prune_epochs_before(&mut self, epoch: u64) -> Result<Vec<FileMetadata>> {
+        if epoch > self.epoch_num() {
+            return Err(anyhow!(
+                "Cannot prune epochs before {} as it would prune current epoch {}",
+                epoch,
+                self.epoch_num()
+            ));
+        }
+        match self {
+            Manifest::V1(manifest) => {
+                let (pruned, retained) = std::mem::take(&mut manifest.file_metadata)
+                    .into_iter()
+                    .partition(|f| f.epoch_num < epoch);
+                manifest.file_metadata = retained;
+                Ok(pruned)
+            }
+            Manifest::V2(manifest) => {
+                let (pruned, retained): (Vec<_>, _) = std::mem::take(&mut manifest.file_metadata)
                     .into_iter()
-                    .filter(|f| f.file_type == FileType::CheckpointSummary)
-                    .collect();
-                summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
-                assert!(summary_files
-                    .windows(2)
-                    .all(|w| w[1].checkpoint_seq_range.start == w[0].checkpoint_seq_range.end));
-                assert_eq!(summary_files.first().unwrap().checkpoint_seq_range.start, 0);
-                summary_files
-                    .iter()
-                    .find(|f| f.epoch_num > epoch_num)
-                    .map(|f| f.checkpoint_seq_range.start)
-                    .unwrap_or(u64::MAX)
+                    .partition(|f| f.metadata.epoch_num < epoch);
+                manifest.file_metadata = retained;
+                Ok(pruned.into_iter().map(|f| f.metadata).collect())
             }
         }
     }
-    pub fn update(
+    /// Appends the given files to the manifest, along with the events file of the same checkpoint
+    /// range if there is one. A V1 manifest drops the size and creation time of the files, and a
+    /// V2 manifest records them as unknown for files given as plain `FileMetadata`.
+    pub fn update<F: Into<FileMetadataV2>>(
         &mut self,
         epoch_num: u64,
         checkpoint_sequence_number: u64,
-        checkpoint_file_metadata: FileMetadata,
-        summary_file_metadata: FileMetadata,
+        checkpoint_file_metadata: F,
+        summary_file_metadata: F,
+        events_file_metadata: Option<F>,
     ) {
+        let files = [checkpoint_file_metadata, summary_file_metadata]
+            .into_iter()
+            .chain(events_file_metadata)
+            .map(Into::<FileMetadataV2>::into);
         match self {
             Manifest::V1(manifest) => {
-                manifest
-                    .file_metadata
-                    .extend(vec![checkpoint_file_metadata, summary_file_metadata]);
+                manifest.file_metadata.extend(files.map(|f| f.metadata));
+                manifest.epoch = epoch_num;
+                manifest.next_checkpoint_seq_num = checkpoint_sequence_number;
+            }
+            Manifest::V2(manifest) => {
+                manifest.file_metadata.extend(files);
                 manifest.epoch = epoch_num;
                 manifest.next_checkpoint_seq_num = checkpoint_sequence_number;
             }
@@ -210,10 +662,41 @@ impl Manifest {
     }
 }
 
+/// Files of a manifest grouped by file type and sorted by the start of their checkpoint range,
+/// built once with `Manifest::index` so that lookups don't have to scan the whole manifest
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ManifestIndex {
+    next_checkpoint_seq_num: u64,
+    files_by_type: HashMap<FileType, Vec<FileMetadata>>,
+}
+
+impl ManifestIndex {
+    /// Returns the file of `file_type` whose checkpoint range contains `seq`, found by binary
+    /// search. Returns `None` if `seq` is not archived yet, or falls in a gap between files.
+    pub fn file_for_checkpoint(&self, seq: u64, file_type: FileType) -> Option<FileMetadata> {
+        if seq >= self.next_checkpoint_seq_num {
+            return None;
+        }
+        let files = self.files_by_type.get(&file_type)?;
+        files
+            .binary_search_by(|f| {
+                if f.checkpoint_seq_range.end <= seq {
+                    std::cmp::Ordering::Less
+                } else if f.checkpoint_seq_range.start > seq {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .ok()
+            .map(|index| files[index].clone())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct CheckpointUpdates {
-    checkpoint_file_metadata: FileMetadata,
-    summary_file_metadata: FileMetadata,
+    checkpoint_file_metadata: FileMetadataV2,
+    summary_file_metadata: FileMetadataV2,
     manifest: Manifest,
 }
 
@@ -221,8 +704,8 @@ impl CheckpointUpdates {
     pub fn new(
         epoch_num: u64,
         checkpoint_sequence_number: u64,
-        checkpoint_file_metadata: FileMetadata,
-        summary_file_metadata: FileMetadata,
+        checkpoint_file_metadata: FileMetadataV2,
+        summary_file_metadata: FileMetadataV2,
         manifest: &mut Manifest,
     ) -> Self {
         manifest.update(
@@ -230,6 +713,7 @@ impl CheckpointUpdates {
             checkpoint_sequence_number,
             checkpoint_file_metadata.clone(),
             summary_file_metadata.clone(),
+            None,
         );
         CheckpointUpdates {
             checkpoint_file_metadata,
@@ -238,16 +722,49 @@ impl CheckpointUpdates {
         }
     }
     pub fn content_file_path(&self) -> Path {
-        self.checkpoint_file_metadata.file_path()
+        self.checkpoint_file_metadata.metadata.file_path()
     }
     pub fn summary_file_path(&self) -> Path {
-        self.summary_file_metadata.file_path()
+        self.summary_file_metadata.metadata.file_path()
     }
     pub fn manifest_file_path(&self) -> Path {
         Path::from(MANIFEST_FILENAME)
     }
 }
 
+/// Returns the first checkpoint which `files`, sorted by checkpoint, must cover. This is genesis,
+/// unless the epochs before the first file were pruned from the archive. Pruning removes whole
+/// epochs, so a file of epoch 0 must still start at genesis.
+fn low_watermark(files: &[FileMetadata]) -> u64 {
+    match files.first() {
+        Some(first) if first.epoch_num > 0 => first.checkpoint_seq_range.start,
+        _ => 0,
+    }
+}
+
+/// Checks that `files`, sorted by checkpoint, cover a contiguous range of checkpoints starting at
+/// genesis, or at the low watermark of a pruned archive
+pub(crate) fn verify_contiguous_files(files: &[FileMetadata]) -> Result<()> {
+    if let Some(first) = files.first() {
+        if first.checkpoint_seq_range.start != low_watermark(files) {
+            return Err(anyhow!(
+                "archive does not start at checkpoint 0, first file covers checkpoints {:?}",
+                first.checkpoint_seq_range
+            ));
+        }
+    }
+    for w in files.windows(2) {
+        if w[1].checkpoint_seq_range.start != w[0].checkpoint_seq_range.end {
+            return Err(anyhow!(
+                "gap between checkpoint ranges {:?} and {:?}",
+                w[0].checkpoint_seq_range,
+                w[1].checkpoint_seq_range
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn create_file_metadata(
     file_path: &std::path::Path,
     file_type: FileType,
@@ -255,13 +772,12 @@ pub fn create_file_metadata(
     checkpoint_seq_range: Range<u64>,
 ) -> Result<FileMetadata> {
     let sha3_digest = compute_sha3_checksum(file_path)?;
-    let file_metadata = FileMetadata {
+    Ok(create_file_metadata_with_digest(
+        sha3_digest,
         file_type,
         epoch_num,
         checkpoint_seq_range,
-        sha3_digest,
-    };
-    Ok(file_metadata)
+    ))
 }
 
 pub fn create_file_metadata_from_bytes(
@@ -271,19 +787,184 @@ pub fn create_file_metadata_from_bytes(
     checkpoint_seq_range: Range<u64>,
 ) -> Result<FileMetadata> {
     let sha3_digest = compute_sha3_checksum_for_bytes(bytes)?;
-    let file_metadata = FileMetadata {
+    Ok(create_file_metadata_with_digest(
+        sha3_digest,
+        file_type,
+        epoch_num,
+        checkpoint_seq_range,
+    ))
+}
+
+/// Creates file metadata from a digest which was already computed, e.g. with a
+/// `sui_storage::Sha3Writer` while the file was being written.
+pub fn create_file_metadata_with_digest(
+    sha3_digest: [u8; 32],
+    file_type: FileType,
+    epoch_num: u64,
+    checkpoint_seq_range: Range<u64>,
+) -> FileMetadata {
+    FileMetadata {
         file_type,
         epoch_num,
         checkpoint_seq_range,
         sha3_digest,
-    };
-    Ok(file_metadata)
+    }
+}
+
+/// Retry policy for reading and writing archive files in the remote object store. Transient
+/// errors are retried with an exponential backoff, while errors which cannot go away by retrying
+/// (ex. a missing file) fail immediately. Each delay is randomly stretched by up to half its
+/// length, so that many readers failing at once do not retry in lockstep.
+#[derive(Copy, Clone, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: usize,
+    /// Delay before the first retry, doubled after every failed attempt.
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: usize, base_delay: Duration) -> Self {
+        assert!(max_attempts > 0, "Retry policy needs at least one attempt");
+        Self {
+            max_attempts,
+            base_delay,
+        }
+    }
+
+    async fn retry<T, F, Fut>(&self, src: &Path, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut next_delay = self.base_delay;
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Ok(result) => return Ok(result),
+                Err(e) if attempt < self.max_attempts && is_retryable_error(&e) => {
+                    let delay = with_jitter(next_delay);
+                    warn!(
+                        "Attempt {attempt} of {} for file: {src} failed with error: {e:?}, retrying in {delay:?}",
+                        self.max_attempts
+                    );
+                    tokio::time::sleep(delay).await;
+                    next_delay = next_delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                Err(e) => {
+                    error!("Failed to access file: {src} in object store with error: {e:?}");
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+/// Add a random jitter of up to half of `delay` to it
+fn with_jitter(delay: Duration) -> Duration {
+    delay + delay.mul_f64(rand::thread_rng().gen_range(0.0..0.5))
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        ArchiveRetryConfig::default().into()
+    }
+}
+
+impl From<ArchiveRetryConfig> for RetryPolicy {
+    fn from(config: ArchiveRetryConfig) -> Self {
+        Self::new(
+            config.max_attempts,
+            Duration::from_millis(config.base_delay_ms),
+        )
+    }
+}
+
+/// Whether an object store error is worth retrying. Only errors which are known to be transient
+/// are: timeouts, connection resets and server side (5xx) errors. Anything else, ex. a missing
+/// file, a denied access or an unrecognized error, fails right away.
+pub fn is_retryable_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            return err.is_timeout() || err.status().is_some_and(|status| status.is_server_error());
+        }
+        if let Some(err) = cause.downcast_ref::<std::io::Error>() {
+            return matches!(
+                err.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::Interrupted
+            );
+        }
+        cause.is::<tokio::time::error::Elapsed>()
+    })
+}
+
+/// Read the file at the given path from the remote store, retrying transient errors according to
+/// the retry policy
+pub async fn get_with_retry<S: ObjectStoreGetExt>(
+    remote_store: &S,
+    src: &Path,
+    retry_policy: &RetryPolicy,
+) -> Result<Bytes> {
+    retry_policy
+        .retry(src, || remote_store.get_bytes(src))
+        .await
+}
+
+/// Write the bytes at the given path in the remote store, retrying transient errors according to
+/// the retry policy. Empty files are not written
+pub async fn put_with_retry<S: ObjectStorePutExt>(
+    remote_store: &S,
+    src: &Path,
+    bytes: Bytes,
+    retry_policy: &RetryPolicy,
+) -> Result<()> {
+    if bytes.is_empty() {
+        warn!("Not copying empty file: {:?}", src);
+        return Ok(());
+    }
+    retry_policy
+        .retry(src, || remote_store.put_bytes(src, bytes.clone()))
+        .await
 }
 
 pub async fn read_manifest<S: ObjectStoreGetExt>(remote_store: S) -> Result<Manifest> {
+    read_manifest_with_retry(remote_store, &RetryPolicy::default()).await
+}
+
+pub async fn read_manifest_with_retry<S: ObjectStoreGetExt>(
+    remote_store: S,
+    retry_policy: &RetryPolicy,
+) -> Result<Manifest> {
+    read_manifest_with_timeout(
+        remote_store,
+        retry_policy,
+        Some(DEFAULT_MANIFEST_READ_TIMEOUT),
+    )
+    .await
+}
+
+/// Read the manifest from the remote store, giving up once `timeout` has elapsed (including any
+/// retries) so that an unresponsive store does not stall the caller indefinitely. No deadline is
+/// applied if `timeout` is `None`
+pub async fn read_manifest_with_timeout<S: ObjectStoreGetExt>(
+    remote_store: S,
+    retry_policy: &RetryPolicy,
+    timeout: Option<Duration>,
+) -> Result<Manifest> {
     let manifest_file_path = Path::from(MANIFEST_FILENAME);
-    let vec = get(&remote_store, &manifest_file_path).await?.to_vec();
-    read_manifest_from_bytes(vec)
+    let get = get_with_retry(&remote_store, &manifest_file_path, retry_policy);
+    let bytes = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, get).await.map_err(|_| {
+            anyhow!("Timed out after {timeout:?} reading manifest from: {remote_store}")
+        })??,
+        None => get.await?,
+    };
+    read_manifest_from_bytes(bytes.to_vec())
 }
 
 pub fn read_manifest_from_bytes(vec: Vec<u8>) -> Result<Manifest> {
@@ -328,13 +1009,241 @@ pub fn finalize_manifest(manifest: Manifest) -> Result<Bytes> {
     Ok(Bytes::from(buf.into_inner()?))
 }
 
+/// Reads a MANIFEST file from the local filesystem. The checksum is verified by streaming the
+/// file through the hasher, so the file is never held in memory in full.
+pub fn read_manifest_from_path(path: &std::path::Path) -> Result<Manifest> {
+    let mut file = fs::File::open(path)?;
+    let manifest_file_size = file.metadata()?.len();
+    if manifest_file_size < (MAGIC_BYTES + SHA3_BYTES) as u64 {
+        return Err(anyhow!(
+            "Manifest truncated, file size: {} bytes",
+            manifest_file_size
+        ));
+    }
+    let magic = file.read_u32::<BigEndian>()?;
+    if magic != MANIFEST_FILE_MAGIC {
+        return Err(anyhow!("Unexpected magic byte in manifest: {}", magic));
+    }
+    // The blob is prefixed with its length, which tells how large the file should be
+    let blob_len = file.read_varint::<u64>()?;
+    let expected_file_size =
+        (MAGIC_BYTES + blob_len.required_space() + BLOB_ENCODING_BYTES + SHA3_BYTES) as u64
+            + blob_len;
+    if manifest_file_size < expected_file_size {
+        return Err(anyhow!(
+            "Manifest truncated, file size: {} bytes, expected: {} bytes",
+            manifest_file_size,
+            expected_file_size
+        ));
+    }
+    file.rewind()?;
+    let mut hasher = Sha3_256::default();
+    io::copy(
+        &mut (&mut file).take(manifest_file_size - SHA3_BYTES as u64),
+        &mut hasher,
+    )?;
+    let mut sha3_digest = [0u8; SHA3_BYTES];
+    file.read_exact(&mut sha3_digest)?;
+    let computed_digest = hasher.finalize().digest;
+    if computed_digest != sha3_digest {
+        return Err(anyhow!(
+            "Manifest corrupted, computed checksum: {:?}, stored checksum: {:?}",
+            computed_digest,
+            sha3_digest
+        ));
+    }
+    file.seek(SeekFrom::Start(MAGIC_BYTES as u64))?;
+    Blob::read(&mut BufReader::new(file))?.decode()
+}
+
+/// Writes `manifest` to a file on the local filesystem, in the same format as `finalize_manifest`.
+pub fn write_manifest_to_path(manifest: &Manifest, path: &std::path::Path) -> Result<()> {
+    let mut writer = BufWriter::new(Sha3Writer::new(fs::File::create(path)?));
+    writer.write_u32::<BigEndian>(MANIFEST_FILE_MAGIC)?;
+    Blob::encode(manifest, BlobEncoding::Bcs)?.write(&mut writer)?;
+    let (mut file, computed_digest) = writer.into_inner()?.finalize();
+    file.write_all(&computed_digest)?;
+    file.sync_all()?;
+    Ok(())
+}
+
 pub async fn write_manifest<S: ObjectStorePutExt>(
     manifest: Manifest,
     remote_store: S,
 ) -> Result<()> {
     let path = Path::from(MANIFEST_FILENAME);
     let bytes = finalize_manifest(manifest)?;
-    put(&remote_store, &path, bytes).await?;
+    put_with_retry(&remote_store, &path, bytes, &RetryPolicy::default()).await?;
+    Ok(())
+}
+
+/// Write the manifest to the remote store, but only if it does not regress from the manifest
+/// already there (see `Manifest::verify_no_regression_since`). A missing remote manifest is not
+/// an error. Setting `force` skips the check and overwrites the remote manifest unconditionally
+pub async fn write_manifest_checked<S: ObjectStoreGetExt + ObjectStorePutExt>(
+    manifest: Manifest,
+    remote_store: S,
+    force: bool,
+) -> Result<()> {
+    if !force {
+        let path = Path::from(MANIFEST_FILENAME);
+        match get_with_retry(&remote_store, &path, &RetryPolicy::default()).await {
+            Ok(bytes) => {
+                let previous = read_manifest_from_bytes(bytes.to_vec())?;
+                manifest.verify_no_regression_since(&previous)?;
+            }
+            Err(err) if is_not_found_error(&err) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    write_manifest(manifest, remote_store).await
+}
+
+pub(crate) fn is_not_found_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<object_store::Error>(),
+            Some(object_store::Error::NotFound { .. })
+        ) || cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|err| err.kind() == std::io::ErrorKind::NotFound)
+    })
+}
+
+/// The outcome of checking every file in an archive against its MANIFEST
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ArchiveVerificationReport {
+    /// Number of files listed in the manifest
+    pub num_files: usize,
+    /// Files whose contents do not match the digest in the manifest
+    pub mismatched_files: Vec<FileMetadata>,
+    /// Files listed in the manifest which are missing from the store
+    pub missing_files: Vec<FileMetadata>,
+    /// Files which match their digest, but have an unexpected header or do not start at the first
+    /// checkpoint of their range
+    pub invalid_files: Vec<FileMetadata>,
+}
+
+impl ArchiveVerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.mismatched_files.is_empty()
+            && self.missing_files.is_empty()
+            && self.invalid_files.is_empty()
+    }
+}
+
+enum ArchivedFileStatus {
+    Valid,
+    Mismatched,
+    Missing,
+    Invalid,
+}
+
+/// Read the MANIFEST from the remote store and check the sha3 digest of every file it lists. All
+/// mismatched and missing files are collected in the returned report instead of failing on the
+/// first one. Errors other than a missing file, ex. an unreachable store, are still returned.
+pub async fn verify_archive<S: ObjectStoreGetExt>(
+    remote_store: S,
+) -> Result<ArchiveVerificationReport> {
+    verify_archived_files(remote_store, 1, false).await
+}
+
+/// Like `verify_archive`, but downloads up to `concurrency` files in parallel, and also checks
+/// that every file that matches its digest has the expected header. The first summary in a
+/// summary file must be of the first checkpoint in the range of the file.
+pub async fn verify_archive_integrity<S: ObjectStoreGetExt>(
+    remote_store: S,
+    concurrency: usize,
+) -> Result<ArchiveVerificationReport> {
+    verify_archived_files(remote_store, concurrency, true).await
+}
+
+async fn verify_archived_files<S: ObjectStoreGetExt>(
+    remote_store: S,
+    concurrency: usize,
+    verify_contents: bool,
+) -> Result<ArchiveVerificationReport> {
+    let retry_policy = RetryPolicy::default();
+    let manifest_bytes =
+        get_with_retry(&remote_store, &Path::from(MANIFEST_FILENAME), &retry_policy).await?;
+    let manifest = read_manifest_from_bytes(manifest_bytes.to_vec())?;
+    let files = manifest.files();
+    let statuses: Vec<_> = futures::stream::iter(files.iter())
+        .map(|file_metadata| {
+            verify_archived_file(&remote_store, file_metadata, &retry_policy, verify_contents)
+        })
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    let mut report = ArchiveVerificationReport {
+        num_files: files.len(),
+        ..Default::default()
+    };
+    for (file_metadata, status) in files.into_iter().zip(statuses) {
+        match status {
+            ArchivedFileStatus::Valid => {}
+            ArchivedFileStatus::Mismatched => report.mismatched_files.push(file_metadata),
+            ArchivedFileStatus::Missing => report.missing_files.push(file_metadata),
+            ArchivedFileStatus::Invalid => report.invalid_files.push(file_metadata),
+        }
+    }
+    Ok(report)
+}
+
+async fn verify_archived_file<S: ObjectStoreGetExt>(
+    remote_store: &S,
+    file_metadata: &FileMetadata,
+    retry_policy: &RetryPolicy,
+    verify_contents: bool,
+) -> Result<ArchivedFileStatus> {
+    let file_path = file_metadata.file_path();
+    let bytes = match get_with_retry(remote_store, &file_path, retry_policy).await {
+        Ok(bytes) => bytes,
+        Err(err) if is_not_found_error(&err) => {
+            error!("Archive file is missing: {}", file_path);
+            return Ok(ArchivedFileStatus::Missing);
+        }
+        Err(err) => return Err(err),
+    };
+    if compute_sha3_checksum_for_bytes(bytes.clone())? != file_metadata.sha3_digest {
+        error!("Archive file is corrupted: {}", file_path);
+        return Ok(ArchivedFileStatus::Mismatched);
+    }
+    if verify_contents {
+        if let Err(err) = verify_archived_file_contents(file_metadata, bytes) {
+            error!("Archive file is invalid: {}, error: {:?}", file_path, err);
+            return Ok(ArchivedFileStatus::Invalid);
+        }
+    }
+    Ok(ArchivedFileStatus::Valid)
+}
+
+/// Checks the header of an archived file. Checkpoint contents and events do not carry their
+/// sequence number, so only summary files are checked to start at the first checkpoint of their
+/// range.
+fn verify_archived_file_contents(file_metadata: &FileMetadata, bytes: Bytes) -> Result<()> {
+    match file_metadata.file_type {
+        FileType::CheckpointContent => {
+            open_blob_file(CHECKPOINT_FILE_MAGIC, bytes)?;
+        }
+        FileType::Events => {
+            open_blob_file(EVENTS_FILE_MAGIC, bytes)?;
+        }
+        FileType::CheckpointSummary => {
+            let first =
+                make_blob_file_iterator::<CertifiedCheckpointSummary>(SUMMARY_FILE_MAGIC, bytes)?
+                    .next()
+                    .ok_or_else(|| anyhow!("No checkpoint summary in file"))?;
+            if first.sequence_number != file_metadata.checkpoint_seq_range.start {
+                return Err(anyhow!(
+                    "First checkpoint in file is {}, expected {}",
+                    first.sequence_number,
+                    file_metadata.checkpoint_seq_range.start
+                ));
+            }
+        }
+    }
     Ok(())
 }
 
@@ -344,6 +1253,8 @@ pub async fn read_manifest_as_json(remote_store_config: ObjectStoreConfig) -> Re
         remote_store_config,
         download_concurrency: NonZeroUsize::new(1).unwrap(),
         use_for_pruning_watermark: false,
+        verify_checksums: false,
+        retry_config: ArchiveRetryConfig::default(),
     };
     let archive_reader = ArchiveReader::new(config, &metrics)?;
     archive_reader.sync_manifest_once().await?;
@@ -419,6 +1330,8 @@ pub async fn verify_archive_with_checksums(
         remote_store_config,
         download_concurrency: NonZeroUsize::new(concurrency).unwrap(),
         use_for_pruning_watermark: false,
+        verify_checksums: false,
+        retry_config: ArchiveRetryConfig::default(),
     };
     let archive_reader = ArchiveReader::new(config, &metrics)?;
     archive_reader.sync_manifest_once().await?;
@@ -452,6 +1365,8 @@ where
         remote_store_config,
         download_concurrency: NonZeroUsize::new(concurrency).unwrap(),
         use_for_pruning_watermark: false,
+        verify_checksums: true,
+        retry_config: ArchiveRetryConfig::default(),
     };
     let archive_reader = ArchiveReader::new(config, &metrics)?;
     archive_reader.sync_manifest_once().await?;
@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    read_manifest, FileMetadata, FileType, Manifest, CHECKPOINT_FILE_MAGIC, SUMMARY_FILE_MAGIC,
+    get_with_retry, make_blob_file_iterator, open_blob_file, read_manifest_with_retry,
+    verify_contiguous_files, FileMetadata, FileType, Manifest, ManifestIndex, RetryPolicy,
+    CHECKPOINT_FILE_MAGIC, EVENTS_FILE_MAGIC, SUMMARY_FILE_MAGIC,
 };
 use anyhow::{anyhow, Context, Result};
-use bytes::buf::Reader;
-use bytes::{Buf, Bytes};
-use futures::{StreamExt, TryStreamExt};
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt};
 use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
 use rand::seq::SliceRandom;
 use std::borrow::Borrow;
@@ -17,10 +18,11 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use sui_config::node::ArchiveReaderConfig;
+use sui_storage::blob::Blob;
 use sui_storage::object_store::http::HttpDownloaderBuilder;
-use sui_storage::object_store::util::get;
 use sui_storage::object_store::ObjectStoreGetExt;
-use sui_storage::{compute_sha3_checksum_for_bytes, make_iterator, verify_checkpoint};
+use sui_storage::{compute_sha3_checksum_for_bytes, verify_checkpoint, StorageFormat};
+use sui_types::effects::TransactionEvents;
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointSequenceNumber,
     FullCheckpointContents as CheckpointContents, VerifiedCheckpoint, VerifiedCheckpointContents,
@@ -28,7 +30,7 @@ use sui_types::messages_checkpoint::{
 use sui_types::storage::WriteStore;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{oneshot, Mutex};
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug)]
 pub struct ArchiveReaderMetrics {
@@ -135,7 +137,12 @@ pub struct ArchiveReader {
     concurrency: usize,
     sender: Arc<Sender<()>>,
     manifest: Arc<Mutex<Manifest>>,
+    /// Index of `manifest`, rebuilt whenever the manifest is synced. Only locked while holding the
+    /// manifest lock, so that the two stay consistent
+    manifest_index: Arc<Mutex<ManifestIndex>>,
     use_for_pruning_watermark: bool,
+    verify_checksums: bool,
+    retry_policy: RetryPolicy,
     remote_object_store: Arc<dyn ObjectStoreGetExt>,
     archive_reader_metrics: Arc<ArchiveReaderMetrics>,
 }
@@ -154,21 +161,33 @@ impl ArchiveReader {
         };
         let (sender, recv) = oneshot::channel();
         let manifest = Arc::new(Mutex::new(Manifest::new(0, 0)));
+        let manifest_index = Arc::new(Mutex::new(ManifestIndex::default()));
+        let retry_policy = RetryPolicy::from(config.retry_config);
         // Start a background tokio task to keep local manifest in sync with remote
-        Self::spawn_manifest_sync_task(remote_object_store.clone(), manifest.clone(), recv);
+        Self::spawn_manifest_sync_task(
+            remote_object_store.clone(),
+            manifest.clone(),
+            manifest_index.clone(),
+            retry_policy,
+            recv,
+        );
         Ok(ArchiveReader {
             bucket,
             manifest,
+            manifest_index,
             sender: Arc::new(sender),
             remote_object_store,
             use_for_pruning_watermark: config.use_for_pruning_watermark,
+            verify_checksums: config.verify_checksums,
+            retry_policy,
             concurrency: config.download_concurrency.get(),
             archive_reader_metrics: metrics.clone(),
         })
     }
 
     /// This function verifies that the files in archive cover the entire range of checkpoints from
-    /// sequence number 0 until the latest available checkpoint with no missing checkpoint
+    /// sequence number 0, or the first checkpoint left by pruning, until the latest available
+    /// checkpoint with no missing checkpoint
     pub async fn verify_manifest(
         &self,
         manifest: Manifest,
@@ -187,28 +206,34 @@ impl ArchiveReader {
             .into_iter()
             .filter(|f| f.file_type == FileType::CheckpointContent)
             .collect();
-        assert_eq!(summary_files.len(), contents_files.len());
+        if summary_files.len() != contents_files.len() {
+            return Err(anyhow!(
+                "Archive has {} summary files but {} content files",
+                summary_files.len(),
+                contents_files.len()
+            ));
+        }
 
         summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
         contents_files.sort_by_key(|f| f.checkpoint_seq_range.start);
 
-        assert!(summary_files
-            .windows(2)
-            .all(|w| w[1].checkpoint_seq_range.start == w[0].checkpoint_seq_range.end));
-        assert!(contents_files
-            .windows(2)
-            .all(|w| w[1].checkpoint_seq_range.start == w[0].checkpoint_seq_range.end));
+        verify_contiguous_files(&summary_files)?;
+        verify_contiguous_files(&contents_files)?;
 
         let files: Vec<(FileMetadata, FileMetadata)> = summary_files
             .into_iter()
             .zip(contents_files.into_iter())
             .map(|(s, c)| {
-                assert_eq!(s.checkpoint_seq_range, c.checkpoint_seq_range);
-                (s, c)
+                if s.checkpoint_seq_range != c.checkpoint_seq_range {
+                    return Err(anyhow!(
+                        "Summary file covers checkpoints {:?} but content file covers {:?}",
+                        s.checkpoint_seq_range,
+                        c.checkpoint_seq_range
+                    ));
+                }
+                Ok((s, c))
             })
-            .collect();
-
-        assert_eq!(files.first().unwrap().0.checkpoint_seq_range.start, 0);
+            .collect::<Result<_>>()?;
 
         Ok(files)
     }
@@ -220,15 +245,24 @@ impl ArchiveReader {
         files: Vec<(FileMetadata, FileMetadata)>,
     ) -> Result<()> {
         let remote_object_store = self.remote_object_store.clone();
+        let retry_policy = self.retry_policy;
         futures::stream::iter(files.iter())
             .enumerate()
             .map(|(_, (summary_metadata, content_metadata))| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
-                    let summary_data =
-                        get(&remote_object_store, &summary_metadata.file_path()).await?;
-                    let content_data =
-                        get(&remote_object_store, &content_metadata.file_path()).await?;
+                    let summary_data = get_with_retry(
+                        &remote_object_store,
+                        &summary_metadata.file_path(),
+                        &retry_policy,
+                    )
+                    .await?;
+                    let content_data = get_with_retry(
+                        &remote_object_store,
+                        &content_metadata.file_path(),
+                        &retry_policy,
+                    )
+                    .await?;
                     Ok::<((Bytes, &FileMetadata), (Bytes, &FileMetadata)), anyhow::Error>((
                         (summary_data, summary_metadata),
                         (content_data, content_metadata),
@@ -278,14 +312,21 @@ impl ArchiveReader {
             .get_summary_files_for_range(checkpoint_range.clone())
             .await?;
         let remote_object_store = self.remote_object_store.clone();
+        let verify_checksums = self.verify_checksums;
+        let retry_policy = self.retry_policy;
         let stream = futures::stream::iter(summary_files.iter())
             .enumerate()
             .filter(|(index, _s)| future::ready(*index >= start_index && *index < end_index))
             .map(|(_, summary_metadata)| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
-                    let summary_data =
-                        get(&remote_object_store, &summary_metadata.file_path()).await?;
+                    let summary_data = Self::get_file(
+                        &remote_object_store,
+                        summary_metadata,
+                        verify_checksums,
+                        &retry_policy,
+                    )
+                    .await?;
                     Ok::<Bytes, anyhow::Error>(summary_data)
                 }
             })
@@ -293,23 +334,23 @@ impl ArchiveReader {
         stream
             .buffer_unordered(self.concurrency)
             .try_for_each(|summary_data| {
-                let result: Result<(), anyhow::Error> =
-                    make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
-                        SUMMARY_FILE_MAGIC,
-                        summary_data.reader(),
-                    )
-                    .and_then(|summary_iter| {
-                        summary_iter
-                            .filter(|s| {
-                                s.sequence_number >= checkpoint_range.start
-                                    && s.sequence_number < checkpoint_range.end
-                            })
-                            .try_for_each(|summary| {
-                                Self::insert_certified_checkpoint(&store, summary)?;
-                                checkpoint_counter.fetch_add(1, Ordering::Relaxed);
-                                Ok::<(), anyhow::Error>(())
-                            })
-                    });
+                let result: Result<(), anyhow::Error> = make_blob_file_iterator::<
+                    CertifiedCheckpointSummary,
+                >(
+                    SUMMARY_FILE_MAGIC, summary_data
+                )
+                .and_then(|summary_iter| {
+                    summary_iter
+                        .filter(|s| {
+                            s.sequence_number >= checkpoint_range.start
+                                && s.sequence_number < checkpoint_range.end
+                        })
+                        .try_for_each(|summary| {
+                            Self::insert_certified_checkpoint(&store, summary)?;
+                            checkpoint_counter.fetch_add(1, Ordering::Relaxed);
+                            Ok::<(), anyhow::Error>(())
+                        })
+                });
                 futures::future::ready(result)
             })
             .await
@@ -328,12 +369,19 @@ impl ArchiveReader {
     {
         let summary_files = self.get_summary_files_for_list(skiplist.clone()).await?;
         let remote_object_store = self.remote_object_store.clone();
+        let verify_checksums = self.verify_checksums;
+        let retry_policy = self.retry_policy;
         let stream = futures::stream::iter(summary_files.iter())
             .map(|summary_metadata| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
-                    let summary_data =
-                        get(&remote_object_store, &summary_metadata.file_path()).await?;
+                    let summary_data = Self::get_file(
+                        &remote_object_store,
+                        summary_metadata,
+                        verify_checksums,
+                        &retry_policy,
+                    )
+                    .await?;
                     Ok::<Bytes, anyhow::Error>(summary_data)
                 }
             })
@@ -342,23 +390,227 @@ impl ArchiveReader {
         stream
             .buffer_unordered(self.concurrency)
             .try_for_each(|summary_data| {
-                let result: Result<(), anyhow::Error> =
-                    make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
+                let result: Result<(), anyhow::Error> = make_blob_file_iterator::<
+                    CertifiedCheckpointSummary,
+                >(
+                    SUMMARY_FILE_MAGIC, summary_data
+                )
+                .and_then(|summary_iter| {
+                    summary_iter
+                        .filter(|s| skiplist.contains(&s.sequence_number))
+                        .try_for_each(|summary| {
+                            Self::insert_certified_checkpoint(&store, summary)?;
+                            checkpoint_counter.fetch_add(1, Ordering::Relaxed);
+                            Ok::<(), anyhow::Error>(())
+                        })
+                });
+                futures::future::ready(result)
+            })
+            .await
+    }
+
+    /// Stream the checkpoint summaries in the given checkpoint range, in order of sequence number.
+    /// Only summary files are downloaded and checkpoint contents files are never fetched, which
+    /// makes this suitable for clients that only need to verify the chain of summaries
+    pub async fn read_summaries_only(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<impl Stream<Item = Result<CertifiedCheckpointSummary>>> {
+        let (summary_files, start_index, end_index) = self
+            .get_summary_files_for_range(checkpoint_range.clone())
+            .await?;
+        let remote_object_store = self.remote_object_store.clone();
+        let verify_checksums = self.verify_checksums;
+        let retry_policy = self.retry_policy;
+        let stream =
+            futures::stream::iter(summary_files.into_iter().take(end_index).skip(start_index))
+                .map(move |summary_metadata| {
+                    let remote_object_store = remote_object_store.clone();
+                    async move {
+                        Self::get_file(
+                            &remote_object_store,
+                            &summary_metadata,
+                            verify_checksums,
+                            &retry_policy,
+                        )
+                        .await
+                    }
+                })
+                .buffered(self.concurrency)
+                .and_then(move |summary_data| {
+                    let summaries = make_blob_file_iterator::<CertifiedCheckpointSummary>(
                         SUMMARY_FILE_MAGIC,
-                        summary_data.reader(),
+                        summary_data,
                     )
-                    .and_then(|summary_iter| {
-                        summary_iter
-                            .filter(|s| skiplist.contains(&s.sequence_number))
-                            .try_for_each(|summary| {
-                                Self::insert_certified_checkpoint(&store, summary)?;
-                                checkpoint_counter.fetch_add(1, Ordering::Relaxed);
-                                Ok::<(), anyhow::Error>(())
-                            })
+                    .map(|summary_iter| {
+                        futures::stream::iter(
+                            summary_iter
+                                .filter(|s| checkpoint_range.contains(&s.sequence_number))
+                                .map(Ok::<CertifiedCheckpointSummary, anyhow::Error>)
+                                .collect::<Vec<_>>(),
+                        )
                     });
-                futures::future::ready(result)
+                    futures::future::ready(summaries)
+                })
+                .try_flatten();
+        Ok(stream)
+    }
+
+    /// Stream the checkpoints in the given checkpoint range, in order of sequence number. Reading
+    /// starts at the file containing the start of the range and stops once the end of the range is
+    /// reached, possibly in the middle of a file. Files are decoded one checkpoint at a time, so at
+    /// most `concurrency` pairs of summary and content files are held in memory at once
+    pub async fn read_checkpoint_range(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<impl Stream<Item = Result<(CertifiedCheckpointSummary, CheckpointContents)>>> {
+        let (manifest, start_file) = {
+            let manifest = self.manifest.lock().await;
+            let start_file = self
+                .manifest_index
+                .lock()
+                .await
+                .file_for_checkpoint(checkpoint_range.start, FileType::CheckpointSummary);
+            (manifest.clone(), start_file)
+        };
+        let start_file = start_file.context(format!(
+            "Archive does not contain checkpoint {}",
+            checkpoint_range.start
+        ))?;
+        let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
+        let start_index = files.partition_point(|(s, _c)| {
+            s.checkpoint_seq_range.start < start_file.checkpoint_seq_range.start
+        });
+        let end = checkpoint_range.end;
+        let remote_object_store = self.remote_object_store.clone();
+        let verify_checksums = self.verify_checksums;
+        let retry_policy = self.retry_policy;
+        let stream = futures::stream::iter(
+            files
+                .into_iter()
+                .skip(start_index)
+                .take_while(move |(s, _c)| s.checkpoint_seq_range.start < end),
+        )
+        .map(move |(summary_metadata, content_metadata)| {
+            let remote_object_store = remote_object_store.clone();
+            async move {
+                let summary_data = Self::get_file(
+                    &remote_object_store,
+                    &summary_metadata,
+                    verify_checksums,
+                    &retry_policy,
+                )
+                .await?;
+                let content_data = Self::get_file(
+                    &remote_object_store,
+                    &content_metadata,
+                    verify_checksums,
+                    &retry_policy,
+                )
+                .await?;
+                Ok::<(Bytes, Bytes), anyhow::Error>((summary_data, content_data))
+            }
+        })
+        .buffered(self.concurrency)
+        .and_then(move |(summary_data, content_data)| {
+            let checkpoint_range = checkpoint_range.clone();
+            let checkpoints = make_blob_file_iterator::<CertifiedCheckpointSummary>(
+                SUMMARY_FILE_MAGIC,
+                summary_data,
+            )
+            .and_then(|summary_iter| {
+                make_blob_file_iterator::<CheckpointContents>(CHECKPOINT_FILE_MAGIC, content_data)
+                    .map(|content_iter| (summary_iter, content_iter))
             })
-            .await
+            .map(|(summary_iter, content_iter)| {
+                futures::stream::iter(
+                    summary_iter
+                        .zip(content_iter)
+                        .skip_while(move |(s, _c)| s.sequence_number < checkpoint_range.start)
+                        .take_while(move |(s, _c)| s.sequence_number < end)
+                        .map(|(summary, contents)| {
+                            contents.verify_digests(summary.content_digest)?;
+                            Ok::<_, anyhow::Error>((summary, contents))
+                        }),
+                )
+            });
+            futures::future::ready(checkpoints)
+        })
+        .try_flatten();
+        Ok(stream)
+    }
+
+    /// Stream the events of the transactions of every checkpoint in the given checkpoint range, in
+    /// order of sequence number. Events are optional in the archive, so this fails if there is no
+    /// events file for the start of the range, and checkpoints whose events were not archived are
+    /// skipped
+    pub async fn read_events(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<impl Stream<Item = Result<(CheckpointSequenceNumber, Vec<TransactionEvents>)>>>
+    {
+        let (manifest, start_file) = {
+            let manifest = self.manifest.lock().await;
+            let start_file = self
+                .manifest_index
+                .lock()
+                .await
+                .file_for_checkpoint(checkpoint_range.start, FileType::Events);
+            (manifest.clone(), start_file)
+        };
+        start_file.context(format!(
+            "Archive has no events for checkpoint {}",
+            checkpoint_range.start
+        ))?;
+        let mut events_files: Vec<_> = manifest
+            .files()
+            .into_iter()
+            .filter(|f| {
+                f.file_type == FileType::Events
+                    && f.checkpoint_seq_range.end > checkpoint_range.start
+                    && f.checkpoint_seq_range.start < checkpoint_range.end
+            })
+            .collect();
+        events_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        let (start, end) = (checkpoint_range.start, checkpoint_range.end);
+        let remote_object_store = self.remote_object_store.clone();
+        let verify_checksums = self.verify_checksums;
+        let retry_policy = self.retry_policy;
+        let stream = futures::stream::iter(events_files)
+            .map(move |events_metadata| {
+                let remote_object_store = remote_object_store.clone();
+                async move {
+                    let events_data = Self::get_file(
+                        &remote_object_store,
+                        &events_metadata,
+                        verify_checksums,
+                        &retry_policy,
+                    )
+                    .await?;
+                    Ok::<_, anyhow::Error>((events_metadata.checkpoint_seq_range, events_data))
+                }
+            })
+            .buffered(self.concurrency)
+            .and_then(move |(file_range, events_data)| {
+                let events = make_blob_file_iterator::<Vec<TransactionEvents>>(
+                    EVENTS_FILE_MAGIC,
+                    events_data,
+                )
+                .map(|events_iter| {
+                    // Each blob holds the events of one checkpoint, starting from the first
+                    // checkpoint of the file
+                    futures::stream::iter(
+                        file_range
+                            .zip(events_iter)
+                            .skip_while(move |(seq, _)| *seq < start)
+                            .take_while(move |(seq, _)| *seq < end)
+                            .map(Ok::<_, anyhow::Error>),
+                    )
+                });
+                futures::future::ready(events)
+            })
+            .try_flatten();
+        Ok(stream)
     }
 
     /// Load checkpoints+txns+effects from archive into the input store `S` for the given
@@ -391,6 +643,7 @@ impl ArchiveReader {
         }
 
         let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
+        Self::check_checkpoint_not_pruned(&files[0].0, checkpoint_range.start)?;
 
         let start_index = match files.binary_search_by_key(&checkpoint_range.start, |(s, _c)| {
             s.checkpoint_seq_range.start
@@ -407,32 +660,43 @@ impl ArchiveReader {
         };
 
         let remote_object_store = self.remote_object_store.clone();
+        let verify_checksums = self.verify_checksums;
+        let retry_policy = self.retry_policy;
         futures::stream::iter(files.iter())
             .enumerate()
             .filter(|(index, (_s, _c))| future::ready(*index >= start_index && *index < end_index))
             .map(|(_, (summary_metadata, content_metadata))| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
-                    let summary_data =
-                        get(&remote_object_store, &summary_metadata.file_path()).await?;
-                    let content_data =
-                        get(&remote_object_store, &content_metadata.file_path()).await?;
+                    let summary_data = Self::get_file(
+                        &remote_object_store,
+                        summary_metadata,
+                        verify_checksums,
+                        &retry_policy,
+                    )
+                    .await?;
+                    let content_data = Self::get_file(
+                        &remote_object_store,
+                        content_metadata,
+                        verify_checksums,
+                        &retry_policy,
+                    )
+                    .await?;
                     Ok::<(Bytes, Bytes), anyhow::Error>((summary_data, content_data))
                 }
             })
             .boxed()
             .buffered(self.concurrency)
             .try_for_each(|(summary_data, content_data)| {
-                let result: Result<(), anyhow::Error> = make_iterator::<
+                let result: Result<(), anyhow::Error> = make_blob_file_iterator::<
                     CertifiedCheckpointSummary,
-                    Reader<Bytes>,
                 >(
-                    SUMMARY_FILE_MAGIC, summary_data.reader()
+                    SUMMARY_FILE_MAGIC, summary_data
                 )
                 .and_then(|s| {
-                    make_iterator::<CheckpointContents, Reader<Bytes>>(
+                    make_blob_file_iterator::<CheckpointContents>(
                         CHECKPOINT_FILE_MAGIC,
-                        content_data.reader(),
+                        content_data,
                     )
                     .map(|c| (s, c))
                 })
@@ -477,6 +741,45 @@ impl ArchiveReader {
             .await
     }
 
+    /// Returns the number of transactions in the given checkpoint. Only the blob framing of the
+    /// checkpoint contents file and the length prefix of the checkpoint's contents are read, so
+    /// this avoids deserializing and materializing the full checkpoint contents.
+    pub async fn get_checkpoint_transaction_count(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<u64> {
+        let manifest = self.manifest.lock().await.clone();
+        let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
+        let index = files
+            .binary_search_by(|(_s, c)| {
+                if checkpoint < c.checkpoint_seq_range.start {
+                    std::cmp::Ordering::Greater
+                } else if checkpoint >= c.checkpoint_seq_range.end {
+                    std::cmp::Ordering::Less
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .map_err(|_| anyhow!("Archive does not contain checkpoint {checkpoint}"))?;
+        let content_metadata = &files[index].1;
+        let content_data = Self::get_file(
+            &self.remote_object_store,
+            content_metadata,
+            self.verify_checksums,
+            &self.retry_policy,
+        )
+        .await?;
+        let (mut reader, storage_format) = open_blob_file(CHECKPOINT_FILE_MAGIC, content_data)?;
+        match storage_format {
+            StorageFormat::Blob => {
+                for _ in content_metadata.checkpoint_seq_range.start..checkpoint {
+                    Blob::skip(&mut reader)?;
+                }
+                Blob::read_sequence_len(&mut reader)
+            }
+        }
+    }
+
     /// Return latest available checkpoint in archive
     pub async fn latest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber> {
         let manifest = self.manifest.lock().await.clone();
@@ -495,7 +798,13 @@ impl ArchiveReader {
     }
 
     pub async fn sync_manifest_once(&self) -> Result<()> {
-        Self::sync_manifest(self.remote_object_store.clone(), self.manifest.clone()).await?;
+        Self::sync_manifest(
+            self.remote_object_store.clone(),
+            self.manifest.clone(),
+            self.manifest_index.clone(),
+            &self.retry_policy,
+        )
+        .await?;
         Ok(())
     }
 
@@ -506,13 +815,36 @@ impl ArchiveReader {
     async fn sync_manifest(
         remote_store: Arc<dyn ObjectStoreGetExt>,
         manifest: Arc<Mutex<Manifest>>,
+        manifest_index: Arc<Mutex<ManifestIndex>>,
+        retry_policy: &RetryPolicy,
     ) -> Result<()> {
-        let new_manifest = read_manifest(remote_store.clone()).await?;
+        let new_manifest = read_manifest_with_retry(remote_store.clone(), retry_policy).await?;
         let mut locked = manifest.lock().await;
+        *manifest_index.lock().await = new_manifest.index();
         *locked = new_manifest;
         Ok(())
     }
 
+    /// Download the given archive file, retrying transient errors. If `verify_checksums` is set,
+    /// the downloaded bytes are checked against the checksum recorded in the manifest before being
+    /// returned. A checksum mismatch is not retried
+    async fn get_file(
+        remote_object_store: &Arc<dyn ObjectStoreGetExt>,
+        file_metadata: &FileMetadata,
+        verify_checksums: bool,
+        retry_policy: &RetryPolicy,
+    ) -> Result<Bytes> {
+        let file_path = file_metadata.file_path();
+        let data = get_with_retry(remote_object_store, &file_path, retry_policy).await?;
+        if verify_checksums {
+            let checksum = compute_sha3_checksum_for_bytes(data.clone())?;
+            if checksum != file_metadata.sha3_digest {
+                return Err(anyhow!("Checksum doesn't match for file: {:?}", file_path));
+            }
+        }
+        Ok(data)
+    }
+
     /// Insert checkpoint summary without verifying it
     fn insert_certified_checkpoint<S>(
         store: &S,
@@ -572,6 +904,23 @@ impl ArchiveReader {
             .map_err(|e| anyhow!("Failed to get verified checkpoint: {:?}", e))
     }
 
+    /// Fails if `checkpoint` is before `first_file`, the first summary file of a verified manifest,
+    /// which happens once the epoch of `checkpoint` has been pruned from the archive
+    fn check_checkpoint_not_pruned(
+        first_file: &FileMetadata,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        let first_available_checkpoint = first_file.checkpoint_seq_range.start;
+        if checkpoint < first_available_checkpoint {
+            return Err(anyhow!(
+                "Checkpoint {} has been pruned, first available checkpoint is: {}",
+                checkpoint,
+                first_available_checkpoint
+            ));
+        }
+        Ok(())
+    }
+
     async fn get_summary_files_for_range(
         &self,
         checkpoint_range: Range<CheckpointSequenceNumber>,
@@ -596,6 +945,7 @@ impl ArchiveReader {
             .iter()
             .map(|(s, _)| s.clone())
             .collect();
+        Self::check_checkpoint_not_pruned(&summary_files[0], checkpoint_range.start)?;
 
         let start_index = match summary_files
             .binary_search_by_key(&checkpoint_range.start, |s| s.checkpoint_seq_range.start)
@@ -653,7 +1003,7 @@ impl ArchiveReader {
                         std::cmp::Ordering::Equal
                     }
                 })
-                .expect("Archive does not contain checkpoint {checkpoint}");
+                .map_err(|_| anyhow!("Archive does not contain checkpoint {checkpoint}"))?;
             summaries_filtered.push(summary_files[index].clone());
         }
 
@@ -663,6 +1013,8 @@ impl ArchiveReader {
     fn spawn_manifest_sync_task<S: ObjectStoreGetExt + Clone>(
         remote_store: S,
         manifest: Arc<Mutex<Manifest>>,
+        manifest_index: Arc<Mutex<ManifestIndex>>,
+        retry_policy: RetryPolicy,
         mut recv: oneshot::Receiver<()>,
     ) {
         tokio::task::spawn(async move {
@@ -670,15 +1022,21 @@ impl ArchiveReader {
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
-                        let new_manifest = read_manifest(remote_store.clone()).await?;
-                        let mut locked = manifest.lock().await;
-                        *locked = new_manifest;
+                        // A failed sync is tried again on the next tick, rather than leaving the
+                        // manifest stale for the lifetime of the reader
+                        match read_manifest_with_retry(remote_store.clone(), &retry_policy).await {
+                            Ok(new_manifest) => {
+                                let mut locked = manifest.lock().await;
+                                *manifest_index.lock().await = new_manifest.index();
+                                *locked = new_manifest;
+                            }
+                            Err(err) => warn!("Failed to sync archive manifest: {err:?}"),
+                        }
                     }
                     _ = &mut recv => break,
                 }
             }
             info!("Terminating the manifest sync loop");
-            Ok::<(), anyhow::Error>(())
         });
     }
 }
@@ -2,25 +2,43 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
-use crate::writer::ArchiveWriter;
-use crate::{read_manifest, verify_archive_with_local_store, write_manifest, Manifest};
+use crate::writer::{prune_archive_epochs_before, ArchiveWriter};
+use crate::{
+    create_file_metadata, create_file_metadata_from_bytes, finalize_manifest, get_with_retry,
+    make_blob_file_iterator, put_with_retry, read_manifest, read_manifest_from_bytes,
+    read_manifest_from_path, read_manifest_with_retry, read_manifest_with_timeout, verify_archive,
+    verify_archive_integrity, verify_archive_with_local_store, with_jitter, write_manifest,
+    write_manifest_checked, write_manifest_to_path, BlobFileHeader, FileMetadata, FileMetadataV2,
+    FileType, Manifest, RetryPolicy, CHECKPOINT_FILE_MAGIC, EVENTS_FILE_MAGIC, SUMMARY_FILE_MAGIC,
+};
 use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::TryStreamExt;
 use more_asserts as ma;
+use object_store::path::Path;
 use object_store::DynObjectStore;
 use prometheus::Registry;
+use std::fmt;
 use std::fs;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use sui_config::node::ArchiveReaderConfig;
+use sui_config::node::{ArchiveReaderConfig, ArchiveRetryConfig};
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::util::path_to_filesystem;
-use sui_storage::{FileCompression, StorageFormat};
+use sui_storage::object_store::ObjectStoreGetExt;
+use sui_storage::{
+    compress_with_level, FileCompression, Sha3Writer, StorageFormat, DEFAULT_ZSTD_COMPRESSION_LEVEL,
+};
 use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
+use sui_types::effects::TransactionEvents;
+use sui_types::event::Event;
 use sui_types::messages_checkpoint::{VerifiedCheckpoint, VerifiedCheckpointContents};
 use sui_types::storage::{ReadStore, SharedInMemoryStore, SingleCheckpointSharedInMemoryStore};
 use tempfile::tempdir;
@@ -37,12 +55,75 @@ struct TestState {
     committee: CommitteeFixture,
 }
 
+/// Object store which fails the first `failures` reads with a transient error before serving
+/// reads from the inner store
+struct FlakyStore {
+    inner: Arc<DynObjectStore>,
+    failures: usize,
+    attempts: Arc<AtomicUsize>,
+}
+
+impl fmt::Display for FlakyStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "flaky:{}", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStoreGetExt for FlakyStore {
+    async fn get_bytes(&self, src: &Path) -> Result<Bytes> {
+        let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+        if attempt < self.failures {
+            return Err(object_store::Error::Generic {
+                store: "flaky",
+                source: Box::new(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "connection reset by peer",
+                )),
+            }
+            .into());
+        }
+        self.inner.get_bytes(src).await
+    }
+}
+
+/// Object store which never responds to reads
+struct UnresponsiveStore;
+
+impl fmt::Display for UnresponsiveStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unresponsive")
+    }
+}
+
+#[async_trait]
+impl ObjectStoreGetExt for UnresponsiveStore {
+    async fn get_bytes(&self, _src: &Path) -> Result<Bytes> {
+        futures::future::pending().await
+    }
+}
+
 fn temp_dir() -> std::path::PathBuf {
     tempdir()
         .expect("Failed to open temporary directory")
         .into_path()
 }
 
+fn file_metadata_from_slice(
+    bytes: &[u8],
+    file_type: FileType,
+    epoch_num: u64,
+    checkpoint_seq_range: std::ops::Range<u64>,
+) -> FileMetadata {
+    create_file_metadata_from_bytes(
+        Bytes::copy_from_slice(bytes),
+        file_type,
+        epoch_num,
+        checkpoint_seq_range,
+    )
+    .expect("Failed to compute file checksum")
+}
+
 async fn write_new_checkpoints_to_store(
     test_state: &TestState,
     store: SharedInMemoryStore,
@@ -93,6 +174,8 @@ async fn setup_test_state(temp_dir: PathBuf) -> anyhow::Result<TestState> {
         remote_store_config: remote_store_config.clone(),
         download_concurrency: NonZeroUsize::new(2).unwrap(),
         use_for_pruning_watermark: false,
+        verify_checksums: true,
+        retry_config: ArchiveRetryConfig::default(),
     };
     let metrics = ArchiveReaderMetrics::new(&Registry::default());
     let archive_reader = ArchiveReader::new(archive_reader_config, &metrics)?;
@@ -158,6 +241,32 @@ async fn test_archive_basic() -> Result<(), anyhow::Error> {
     let kill = test_state.archive_writer.start(test_store.clone()).await?;
     insert_checkpoints_and_verify_manifest(&test_state, test_store, None).await?;
     kill.send(())?;
+
+    // The writer keeps writing V1 manifests unless told otherwise
+    let manifest = read_manifest(test_state.remote_store.clone()).await?;
+    assert!(matches!(manifest, Manifest::V1(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_manifest_v2() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let mut test_state = setup_test_state(temp_dir()).await?;
+    test_state.archive_writer = test_state.archive_writer.with_manifest_v2(true);
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    insert_checkpoints_and_verify_manifest(&test_state, test_store, None).await?;
+    kill.send(())?;
+
+    // The writer records the size and creation time of every archived file
+    let manifest = read_manifest(test_state.remote_store.clone()).await?;
+    let files = manifest.files_v2().context("Expected a V2 manifest")?;
+    assert!(!files.is_empty());
+    for file in files {
+        let file_path =
+            path_to_filesystem(test_state.remote_path.clone(), &file.metadata.file_path())?;
+        assert_eq!(file.file_size, fs::metadata(file_path)?.len());
+        ma::assert_gt!(file.created_at, 0);
+    }
     Ok(())
 }
 
@@ -193,6 +302,1047 @@ async fn test_manifest_serde() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_manifest_path_roundtrip() -> Result<()> {
+    let mut manifest = Manifest::new(0, 0);
+    for i in 0..2000u64 {
+        let epoch_num = i / 100;
+        let range = i * 10..(i + 1) * 10;
+        let bytes = i.to_be_bytes();
+        manifest.update(
+            epoch_num,
+            range.end,
+            file_metadata_from_slice(
+                &bytes,
+                FileType::CheckpointContent,
+                epoch_num,
+                range.clone(),
+            ),
+            file_metadata_from_slice(&bytes, FileType::CheckpointSummary, epoch_num, range),
+            None,
+        );
+    }
+    let dir = temp_dir();
+    let manifest_path = dir.join("MANIFEST");
+    write_manifest_to_path(&manifest, &manifest_path)?;
+
+    let bytes = fs::read(&manifest_path)?;
+    assert_eq!(bytes, finalize_manifest(manifest.clone())?.to_vec());
+    assert_eq!(read_manifest_from_path(&manifest_path)?, manifest);
+
+    let assert_rejected = |corrupted: &[u8], expected_error: &str| {
+        let corrupted_path = dir.join("CORRUPTED_MANIFEST");
+        fs::write(&corrupted_path, corrupted).unwrap();
+        let err = read_manifest_from_path(&corrupted_path).unwrap_err();
+        assert!(
+            err.to_string().contains(expected_error),
+            "expected '{expected_error}', got: {err}"
+        );
+    };
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] ^= 0xff;
+    assert_rejected(&bad_magic, "Unexpected magic byte");
+
+    assert_rejected(&bytes[..bytes.len() - 1], "Manifest truncated");
+    assert_rejected(&bytes[..6], "Manifest truncated");
+
+    let mut flipped = bytes.clone();
+    let middle = flipped.len() / 2;
+    flipped[middle] ^= 0xff;
+    assert_rejected(&flipped, "Manifest corrupted");
+
+    let mut bad_trailer = bytes.clone();
+    *bad_trailer.last_mut().unwrap() ^= 0xff;
+    assert_rejected(&bad_trailer, "Manifest corrupted");
+    Ok(())
+}
+
+#[test]
+fn test_is_retryable_error() {
+    let generic = |source: Box<dyn std::error::Error + Send + Sync>| -> anyhow::Error {
+        object_store::Error::Generic {
+            store: "test",
+            source,
+        }
+        .into()
+    };
+    let io_error = |kind| Box::new(std::io::Error::new(kind, "io error"));
+
+    // Timeouts and connection resets are transient
+    assert!(crate::is_retryable_error(&generic(io_error(
+        std::io::ErrorKind::ConnectionReset
+    ))));
+    assert!(crate::is_retryable_error(&generic(io_error(
+        std::io::ErrorKind::TimedOut
+    ))));
+    // Anything else is not, ex. a client error or an unrecognized error
+    assert!(!crate::is_retryable_error(&generic(
+        "HTTP status client error (403 Forbidden)".into()
+    )));
+    assert!(!crate::is_retryable_error(&generic(io_error(
+        std::io::ErrorKind::PermissionDenied
+    ))));
+    assert!(!crate::is_retryable_error(
+        &object_store::Error::NotFound {
+            path: "missing".to_string(),
+            source: "not found".into(),
+        }
+        .into()
+    ));
+    assert!(!crate::is_retryable_error(&anyhow!("unknown error")));
+}
+
+#[tokio::test]
+async fn test_read_manifest_with_retry() -> Result<()> {
+    let original_manifest = Manifest::new(0, 100);
+    let remote_store = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir()),
+        ..Default::default()
+    }
+    .make()?;
+    write_manifest(original_manifest.clone(), remote_store.clone()).await?;
+    let retry_policy = RetryPolicy::new(3, Duration::from_millis(10));
+
+    // Transient errors are retried until the read succeeds
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let flaky_store = FlakyStore {
+        inner: remote_store.clone(),
+        failures: 2,
+        attempts: attempts.clone(),
+    };
+    let downloaded_manifest = read_manifest_with_retry(flaky_store, &retry_policy).await?;
+    assert_eq!(downloaded_manifest, original_manifest);
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+    // The read fails once all the attempts are exhausted
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let flaky_store = FlakyStore {
+        inner: remote_store.clone(),
+        failures: 3,
+        attempts: attempts.clone(),
+    };
+    assert!(read_manifest_with_retry(flaky_store, &retry_policy)
+        .await
+        .is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+    // A missing file fails fast without any retry
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let flaky_store = FlakyStore {
+        inner: remote_store,
+        failures: 0,
+        attempts: attempts.clone(),
+    };
+    assert!(
+        get_with_retry(&flaky_store, &Path::from("missing"), &retry_policy)
+            .await
+            .is_err()
+    );
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn test_retry_delay_jitter() {
+    let delay = Duration::from_millis(100);
+    for _ in 0..100 {
+        let jittered = with_jitter(delay);
+        ma::assert_ge!(jittered, delay);
+        ma::assert_lt!(jittered, delay + delay / 2);
+    }
+    assert_eq!(with_jitter(Duration::ZERO), Duration::ZERO);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_read_manifest_with_timeout() -> Result<()> {
+    let timeout = Duration::from_secs(10);
+    let start = tokio::time::Instant::now();
+    let result = tokio::time::timeout(
+        timeout * 2,
+        read_manifest_with_timeout(UnresponsiveStore, &RetryPolicy::default(), Some(timeout)),
+    )
+    .await
+    .expect("Manifest read should time out within the configured bound");
+    let err = result.expect_err("Manifest read from an unresponsive store should fail");
+    assert!(err.to_string().contains("Timed out"), "{err:?}");
+    ma::assert_ge!(start.elapsed(), timeout);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_manifest_checked() -> Result<()> {
+    let remote_store = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir()),
+        ..Default::default()
+    }
+    .make()?;
+    let file = |file_type, start: u64, end: u64, contents: u8| {
+        file_metadata_from_slice(&[contents], file_type, 0, start..end)
+    };
+
+    // Writing without a remote manifest succeeds
+    let mut manifest = Manifest::new(0, 0);
+    manifest.update(
+        0,
+        10,
+        file(FileType::CheckpointContent, 0, 10, 1),
+        file(FileType::CheckpointSummary, 0, 10, 2),
+        None,
+    );
+    write_manifest_checked(manifest.clone(), remote_store.clone(), false).await?;
+    assert_eq!(read_manifest(remote_store.clone()).await?, manifest);
+
+    // Extending the manifest is accepted
+    let mut extended = manifest.clone();
+    extended.update(
+        0,
+        20,
+        file(FileType::CheckpointContent, 10, 20, 3),
+        file(FileType::CheckpointSummary, 10, 20, 4),
+        None,
+    );
+    write_manifest_checked(extended.clone(), remote_store.clone(), false).await?;
+    assert_eq!(read_manifest(remote_store.clone()).await?, extended);
+
+    // A manifest with fewer files and a lower next checkpoint is rejected
+    assert!(
+        write_manifest_checked(manifest.clone(), remote_store.clone(), false)
+            .await
+            .is_err()
+    );
+    assert_eq!(read_manifest(remote_store.clone()).await?, extended);
+
+    // So is one which changes the digest of an existing file
+    let mut changed = Manifest::new(0, 0);
+    changed.update(
+        0,
+        10,
+        file(FileType::CheckpointContent, 0, 10, 1),
+        file(FileType::CheckpointSummary, 0, 10, 2),
+        None,
+    );
+    changed.update(
+        0,
+        20,
+        file(FileType::CheckpointContent, 10, 20, 5),
+        file(FileType::CheckpointSummary, 10, 20, 4),
+        None,
+    );
+    assert!(write_manifest_checked(changed, remote_store.clone(), false)
+        .await
+        .is_err());
+    assert_eq!(read_manifest(remote_store.clone()).await?, extended);
+
+    // Unless the write is forced
+    write_manifest_checked(manifest.clone(), remote_store.clone(), true).await?;
+    assert_eq!(read_manifest(remote_store).await?, manifest);
+    Ok(())
+}
+
+#[test]
+fn test_compressed_blob_file_roundtrip() -> Result<()> {
+    let items: Vec<u64> = (0..1000).collect();
+    for (compression, level) in [
+        (FileCompression::None, 0),
+        (FileCompression::Zstd, 1),
+        (FileCompression::Zstd, 19),
+        (FileCompression::Lz4, 0),
+    ] {
+        let mut file = vec![];
+        BlobFileHeader::new(CHECKPOINT_FILE_MAGIC, StorageFormat::Blob, compression)
+            .write(&mut file)?;
+        for item in &items {
+            Blob::encode(item, BlobEncoding::Bcs)?.write(&mut file)?;
+        }
+        let file_path = temp_dir().join("0.chk");
+        let mut compressed = File::create(&file_path)?;
+        compress_with_level(&mut file.as_slice(), &mut compressed, level)?;
+        drop(compressed);
+
+        let bytes = Bytes::from(fs::read(&file_path)?);
+        if compression != FileCompression::None {
+            ma::assert_lt!(bytes.len(), file.len());
+        } else {
+            assert_eq!(bytes.len(), file.len());
+        }
+        let read_items: Vec<u64> = make_blob_file_iterator(CHECKPOINT_FILE_MAGIC, bytes)?.collect();
+        assert_eq!(read_items, items);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_zstd_and_lz4_blob_files_decode_identically() -> Result<()> {
+    let items: Vec<Vec<u64>> = (0..10_000).map(|i| vec![i; 16]).collect();
+    let mut file = vec![];
+    BlobFileHeader::new(
+        CHECKPOINT_FILE_MAGIC,
+        StorageFormat::Blob,
+        FileCompression::None,
+    )
+    .write(&mut file)?;
+    for item in &items {
+        Blob::encode(item, BlobEncoding::Bcs)?.write(&mut file)?;
+    }
+
+    let mut decoded = vec![];
+    for compression in [FileCompression::Zstd, FileCompression::Lz4] {
+        // Only the compression byte of the header differs between the two files
+        let mut uncompressed = file.clone();
+        uncompressed[BlobFileHeader::SIZE - 1] = compression.into();
+        let mut compressed = vec![];
+        compress_with_level(
+            &mut uncompressed.as_slice(),
+            &mut compressed,
+            DEFAULT_ZSTD_COMPRESSION_LEVEL,
+        )?;
+        let bytes = Bytes::from(compressed);
+
+        let read_items: Vec<Vec<u64>> =
+            make_blob_file_iterator(CHECKPOINT_FILE_MAGIC, bytes)?.collect();
+        decoded.push(read_items);
+    }
+    assert_eq!(decoded[0], items);
+    assert_eq!(decoded[1], items);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verify_archive_reports_bad_files() -> Result<()> {
+    let remote_store = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir()),
+        ..Default::default()
+    }
+    .make()?;
+    let mut manifest = Manifest::new(0, 0);
+    let mut files = vec![];
+    for (i, start) in [0u64, 10, 20].into_iter().enumerate() {
+        let range = start..start + 10;
+        let contents = Bytes::from(vec![i as u8; 100]);
+        let checkpoint_file = create_file_metadata_from_bytes(
+            contents.clone(),
+            FileType::CheckpointContent,
+            0,
+            range.clone(),
+        )?;
+        let summary_file = create_file_metadata_from_bytes(
+            contents.clone(),
+            FileType::CheckpointSummary,
+            0,
+            range,
+        )?;
+        files.push((checkpoint_file.clone(), contents.clone()));
+        files.push((summary_file.clone(), contents));
+        manifest.update(0, start + 10, checkpoint_file, summary_file, None);
+    }
+    write_manifest(manifest, remote_store.clone()).await?;
+
+    let retry_policy = RetryPolicy::default();
+    for (file, contents) in &files {
+        put_with_retry(
+            &remote_store,
+            &file.file_path(),
+            contents.clone(),
+            &retry_policy,
+        )
+        .await?;
+    }
+    let report = verify_archive(remote_store.clone()).await?;
+    assert!(report.is_valid());
+    assert_eq!(report.num_files, 6);
+
+    // Corrupt one file and delete another
+    let (corrupted, _) = &files[1];
+    put_with_retry(
+        &remote_store,
+        &corrupted.file_path(),
+        Bytes::from(vec![0xffu8; 100]),
+        &retry_policy,
+    )
+    .await?;
+    let (missing, _) = &files[4];
+    remote_store.delete(&missing.file_path()).await?;
+
+    let report = verify_archive(remote_store).await?;
+    assert!(!report.is_valid());
+    assert_eq!(report.num_files, 6);
+    assert_eq!(report.mismatched_files, vec![corrupted.clone()]);
+    assert_eq!(report.missing_files, vec![missing.clone()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prune_archive_epochs_before() -> Result<()> {
+    let remote_store = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir()),
+        ..Default::default()
+    }
+    .make()?;
+    let retry_policy = RetryPolicy::default();
+    // 5 epochs of 2 checkpoint ranges each
+    let mut manifest = Manifest::new(0, 0);
+    for epoch_num in 0..5u64 {
+        for start in [epoch_num * 20, epoch_num * 20 + 10] {
+            let range = start..start + 10;
+            let contents = Bytes::from(start.to_be_bytes().to_vec());
+            let checkpoint_file = create_file_metadata_from_bytes(
+                contents.clone(),
+                FileType::CheckpointContent,
+                epoch_num,
+                range.clone(),
+            )?;
+            let summary_file = create_file_metadata_from_bytes(
+                contents.clone(),
+                FileType::CheckpointSummary,
+                epoch_num,
+                range,
+            )?;
+            for file in [&checkpoint_file, &summary_file] {
+                put_with_retry(
+                    &remote_store,
+                    &file.file_path(),
+                    contents.clone(),
+                    &retry_policy,
+                )
+                .await?;
+            }
+            manifest.update(epoch_num, start + 10, checkpoint_file, summary_file, None);
+        }
+    }
+    write_manifest(manifest.clone(), remote_store.clone()).await?;
+
+    // The current epoch cannot be pruned
+    assert!(prune_archive_epochs_before(remote_store.clone(), 5)
+        .await
+        .is_err());
+    assert_eq!(read_manifest(remote_store.clone()).await?, manifest);
+
+    let pruned = prune_archive_epochs_before(remote_store.clone(), 3).await?;
+    assert_eq!(pruned.len(), 12);
+    assert!(pruned.iter().all(|f| f.epoch_num < 3));
+
+    let pruned_manifest = read_manifest(remote_store.clone()).await?;
+    let files = pruned_manifest.files();
+    assert_eq!(files.len(), 8);
+    assert!(files.iter().all(|f| f.epoch_num >= 3));
+    assert_eq!(pruned_manifest.next_checkpoint_seq_num(), 100);
+    assert_eq!(pruned_manifest.epoch_num(), 4);
+    // The pruned archive starts at the first checkpoint of epoch 3, and is still contiguous
+    assert_eq!(pruned_manifest.first_checkpoint_seq_num(), 60);
+    pruned_manifest.verify_contiguity()?;
+    assert_eq!(pruned_manifest.next_checkpoint_after_epoch(3)?, 80);
+    assert_eq!(pruned_manifest.complete_epochs(), vec![3]);
+
+    for file in &pruned {
+        let err = get_with_retry(&remote_store, &file.file_path(), &retry_policy)
+            .await
+            .expect_err("Pruned file should be deleted");
+        assert!(!crate::is_retryable_error(&err));
+    }
+    for file in &files {
+        get_with_retry(&remote_store, &file.file_path(), &retry_policy).await?;
+    }
+
+    // Pruning again is a no-op
+    assert!(prune_archive_epochs_before(remote_store.clone(), 3)
+        .await?
+        .is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_read_pruned_archive() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+    kill.send(())?;
+    let remote_store = test_state.remote_store.clone();
+    let retry_policy = RetryPolicy::default();
+    let manifest = read_manifest(remote_store.clone()).await?;
+
+    // Move the second half of the archived files to epoch 1, so that epoch 0 can be pruned
+    let sorted_files = |file_type: FileType| {
+        let mut files: Vec<_> = manifest
+            .files()
+            .into_iter()
+            .filter(|f| f.file_type == file_type)
+            .collect();
+        files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        files
+    };
+    let summary_files = sorted_files(FileType::CheckpointSummary);
+    let content_files = sorted_files(FileType::CheckpointContent);
+    ma::assert_ge!(summary_files.len(), 2);
+    let split = summary_files.len() / 2;
+    let first_retained_checkpoint = summary_files[split].checkpoint_seq_range.start;
+    let mut epoch_manifest = Manifest::new(0, 0);
+    for (i, (mut summary_file, mut content_file)) in
+        summary_files.into_iter().zip(content_files).enumerate()
+    {
+        if i >= split {
+            for file in [&mut summary_file, &mut content_file] {
+                let data = get_with_retry(&remote_store, &file.file_path(), &retry_policy).await?;
+                file.epoch_num = 1;
+                put_with_retry(&remote_store, &file.file_path(), data, &retry_policy).await?;
+            }
+        }
+        epoch_manifest.update(
+            summary_file.epoch_num,
+            summary_file.checkpoint_seq_range.end,
+            content_file,
+            summary_file,
+            None,
+        );
+    }
+    write_manifest(epoch_manifest, remote_store.clone()).await?;
+    prune_archive_epochs_before(remote_store.clone(), 1).await?;
+    test_state.archive_reader.sync_manifest_once().await?;
+
+    let latest_archived_checkpoint_seq_num = test_state
+        .archive_reader
+        .latest_available_checkpoint()
+        .await?;
+    let range = first_retained_checkpoint..(latest_archived_checkpoint_seq_num + 1);
+    let checkpoints: Vec<_> = test_state
+        .archive_reader
+        .read_checkpoint_range(range.clone())
+        .await?
+        .try_collect()
+        .await?;
+    assert_eq!(
+        checkpoints
+            .iter()
+            .map(|(s, _c)| s.sequence_number)
+            .collect::<Vec<_>>(),
+        range.clone().collect::<Vec<_>>()
+    );
+    for (summary, contents) in checkpoints {
+        let expected = test_store
+            .get_checkpoint_by_sequence_number(summary.sequence_number)?
+            .context("Missing checkpoint")?;
+        assert_eq!(&summary, expected.inner());
+        assert_eq!(
+            test_state
+                .archive_reader
+                .get_checkpoint_transaction_count(summary.sequence_number)
+                .await?,
+            contents.size() as u64
+        );
+    }
+
+    // Pruned checkpoints can no longer be read
+    assert!(test_state
+        .archive_reader
+        .read_checkpoint_range(0..range.end)
+        .await
+        .is_err());
+    assert!(test_state
+        .archive_reader
+        .get_checkpoint_transaction_count(0)
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_blob_file_header_roundtrip() -> Result<()> {
+    for magic in [CHECKPOINT_FILE_MAGIC, SUMMARY_FILE_MAGIC] {
+        let header = BlobFileHeader::new(magic, StorageFormat::Blob, FileCompression::Zstd);
+        let mut buf = vec![];
+        header.write(&mut buf)?;
+        assert_eq!(buf.len(), BlobFileHeader::SIZE);
+        assert_eq!(BlobFileHeader::read(&mut buf.as_slice())?, header);
+        assert_eq!(
+            BlobFileHeader::read_expected(&mut buf.as_slice(), magic)?,
+            header
+        );
+    }
+
+    // A summary file is rejected where a checkpoint file is expected
+    let mut buf = vec![];
+    BlobFileHeader::new(
+        SUMMARY_FILE_MAGIC,
+        StorageFormat::Blob,
+        FileCompression::None,
+    )
+    .write(&mut buf)?;
+    let err = BlobFileHeader::read_expected(&mut buf.as_slice(), CHECKPOINT_FILE_MAGIC)
+        .expect_err("Mismatched magic should be rejected");
+    assert!(err.to_string().contains("Unexpected magic"));
+
+    // Unknown magic is rejected on both write and read
+    let header = BlobFileHeader::new(0x00BADBAD, StorageFormat::Blob, FileCompression::None);
+    let err = header
+        .write(&mut Vec::<u8>::new())
+        .expect_err("Unknown magic should be rejected");
+    assert!(err.to_string().contains("Unknown magic"));
+    let mut buf = 0x00BADBADu32.to_be_bytes().to_vec();
+    buf.extend([StorageFormat::Blob.into(), FileCompression::None.into()]);
+    let err =
+        BlobFileHeader::read(&mut buf.as_slice()).expect_err("Unknown magic should be rejected");
+    assert!(err.to_string().contains("Unknown magic"));
+    Ok(())
+}
+
+#[test]
+fn test_manifest_epoch_dir_paths() {
+    let mut manifest = Manifest::new(0, 0);
+    assert!(manifest.epoch_dir_paths().is_empty());
+    for (epoch_num, range) in [(0, 0..10), (0, 10..20), (2, 20..30), (10, 30..40)] {
+        manifest.update(
+            epoch_num,
+            range.end,
+            file_metadata_from_slice(&[], FileType::CheckpointContent, epoch_num, range.clone()),
+            file_metadata_from_slice(&[], FileType::CheckpointSummary, epoch_num, range),
+            None,
+        );
+    }
+    let paths: Vec<_> = manifest
+        .epoch_dir_paths()
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+    assert_eq!(paths, vec!["epoch_0", "epoch_2", "epoch_10"]);
+    for file in manifest.files() {
+        assert!(manifest
+            .epoch_dir_paths()
+            .iter()
+            .any(|dir| file.file_path().prefix_matches(dir)));
+    }
+}
+
+#[test]
+fn test_manifest_files_added_and_changed_since() {
+    let add_files = |manifest: &mut Manifest, epoch_num: u64, range: std::ops::Range<u64>| {
+        manifest.update(
+            epoch_num,
+            range.end,
+            file_metadata_from_slice(&[], FileType::CheckpointContent, epoch_num, range.clone()),
+            file_metadata_from_slice(&[], FileType::CheckpointSummary, epoch_num, range),
+            None,
+        );
+    };
+
+    let mut previous = Manifest::new(0, 0);
+    add_files(&mut previous, 0, 0..10);
+    add_files(&mut previous, 0, 10..20);
+
+    let mut current = previous.clone();
+    add_files(&mut current, 1, 20..30);
+
+    let added = current.files_added_since(&previous);
+    assert_eq!(added.len(), 2);
+    assert!(added
+        .iter()
+        .all(|f| f.epoch_num == 1 && f.checkpoint_seq_range == (20..30)));
+    assert!(added
+        .iter()
+        .any(|f| f.file_type == FileType::CheckpointContent));
+    assert!(added
+        .iter()
+        .any(|f| f.file_type == FileType::CheckpointSummary));
+    assert!(current.files_changed_since(&previous).is_empty());
+    assert!(previous.files_added_since(&current).is_empty());
+
+    // rewriting an existing file with different contents is reported as a change
+    let mut rewritten = Manifest::new(0, 0);
+    rewritten.update(
+        0,
+        10,
+        file_metadata_from_slice(b"rewritten", FileType::CheckpointContent, 0, 0..10),
+        file_metadata_from_slice(&[], FileType::CheckpointSummary, 0, 0..10),
+        None,
+    );
+    let changed = rewritten.files_changed_since(&previous);
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].file_type, FileType::CheckpointContent);
+    assert!(rewritten.files_added_since(&previous).is_empty());
+}
+
+#[test]
+fn test_manifest_find_duplicate_files_and_dedup() {
+    let add_files = |manifest: &mut Manifest, range: std::ops::Range<u64>, contents: &[u8]| {
+        manifest.update(
+            0,
+            range.end,
+            file_metadata_from_slice(contents, FileType::CheckpointContent, 0, range.clone()),
+            file_metadata_from_slice(contents, FileType::CheckpointSummary, 0, range),
+            None,
+        );
+    };
+
+    let mut manifest = Manifest::new(0, 0);
+    add_files(&mut manifest, 0..10, &[]);
+    add_files(&mut manifest, 10..20, &[]);
+    assert!(manifest.find_duplicate_files().is_empty());
+    let original_files = manifest.files();
+
+    // the same ranges appended again, non adjacently and with different contents
+    add_files(&mut manifest, 0..10, &[1]);
+    add_files(&mut manifest, 20..30, &[]);
+    add_files(&mut manifest, 0..10, &[2]);
+    let new_files = manifest.files()[original_files.len() + 2..].to_vec();
+
+    let duplicates = manifest.find_duplicate_files();
+    assert_eq!(duplicates.len(), 4);
+    assert!(duplicates.iter().all(|f| f.checkpoint_seq_range == (0..10)));
+    assert_eq!(duplicates[0].sha3_digest, manifest.files()[4].sha3_digest);
+
+    manifest.dedup();
+    assert!(manifest.find_duplicate_files().is_empty());
+    let mut expected = original_files;
+    expected.extend(new_files[..2].iter().cloned());
+    assert_eq!(manifest.files(), expected);
+}
+
+#[test]
+fn test_manifest_complete_epochs() {
+    let add_files = |manifest: &mut Manifest, epoch_num: u64, range: std::ops::Range<u64>| {
+        manifest.update(
+            epoch_num,
+            range.end,
+            file_metadata_from_slice(&[], FileType::CheckpointContent, epoch_num, range.clone()),
+            file_metadata_from_slice(&[], FileType::CheckpointSummary, epoch_num, range),
+            None,
+        );
+    };
+
+    let mut manifest = Manifest::new(0, 0);
+    add_files(&mut manifest, 0, 0..10);
+    add_files(&mut manifest, 0, 10..20);
+    add_files(&mut manifest, 1, 20..30);
+    add_files(&mut manifest, 2, 30..40);
+    add_files(&mut manifest, 3, 40..50);
+    // the end of the last epoch is not known
+    assert_eq!(manifest.complete_epochs(), vec![0, 1, 2]);
+
+    // drop the content file of epoch 1
+    let Manifest::V1(mut manifest_v1) = manifest else {
+        panic!("expected a V1 manifest");
+    };
+    manifest_v1
+        .file_metadata
+        .retain(|f| !(f.epoch_num == 1 && f.file_type == FileType::CheckpointContent));
+    let manifest = Manifest::V1(manifest_v1);
+    assert_eq!(manifest.complete_epochs(), vec![0, 2]);
+
+    // an epoch with a gap in its checkpoints is not complete
+    let mut manifest = Manifest::new(0, 0);
+    add_files(&mut manifest, 0, 0..10);
+    add_files(&mut manifest, 1, 10..20);
+    add_files(&mut manifest, 1, 25..30);
+    add_files(&mut manifest, 2, 30..40);
+    add_files(&mut manifest, 3, 40..50);
+    assert_eq!(manifest.complete_epochs(), vec![0, 2]);
+}
+
+#[test]
+fn test_manifest_last_checkpoint_of_epoch() {
+    let mut manifest = Manifest::new(0, 0);
+    for (epoch_num, range) in [(0, 0..10), (0, 10..20), (1, 20..30), (3, 30..40)] {
+        manifest.update(
+            epoch_num,
+            range.end,
+            file_metadata_from_slice(&[], FileType::CheckpointContent, epoch_num, range.clone()),
+            file_metadata_from_slice(&[], FileType::CheckpointSummary, epoch_num, range),
+            None,
+        );
+    }
+    manifest.verify_contiguity().unwrap();
+    assert_eq!(manifest.last_checkpoint_of_epoch(0).unwrap(), Some(19));
+    assert_eq!(
+        manifest.last_checkpoint_of_epoch(0).unwrap(),
+        manifest
+            .next_checkpoint_after_epoch(0)
+            .unwrap()
+            .checked_sub(1)
+    );
+    assert_eq!(manifest.next_checkpoint_after_epoch(1).unwrap(), 30);
+    assert_eq!(manifest.last_checkpoint_of_epoch(1).unwrap(), Some(29));
+    // epoch 2 has no checkpoints, so it ends where epoch 1 does
+    assert_eq!(manifest.last_checkpoint_of_epoch(2).unwrap(), Some(29));
+    // the end of the latest epoch is not known yet
+    assert_eq!(manifest.last_checkpoint_of_epoch(3).unwrap(), None);
+}
+
+#[test]
+fn test_manifest_v1_and_v2_serde() -> Result<()> {
+    let checkpoint_file = file_metadata_from_slice(&[1], FileType::CheckpointContent, 0, 0..10);
+    let summary_file = file_metadata_from_slice(&[2], FileType::CheckpointSummary, 0, 0..10);
+
+    // A V1 manifest still round-trips, and does not record file sizes
+    let mut manifest_v1 = Manifest::new(0, 0);
+    manifest_v1.update(0, 10, checkpoint_file.clone(), summary_file.clone(), None);
+    let decoded = read_manifest_from_bytes(finalize_manifest(manifest_v1.clone())?.to_vec())?;
+    assert!(matches!(decoded, Manifest::V1(_)));
+    assert_eq!(decoded, manifest_v1);
+    assert_eq!(decoded.files_v2(), None);
+
+    let checkpoint_file_v2 = FileMetadataV2 {
+        metadata: checkpoint_file.clone(),
+        file_size: 1024,
+        created_at: 1_700_000_000,
+    };
+    let summary_file_v2 = FileMetadataV2 {
+        metadata: summary_file.clone(),
+        file_size: 256,
+        created_at: 1_700_000_001,
+    };
+    let mut manifest_v2 = Manifest::new_v2(0, 0);
+    manifest_v2.update(
+        0,
+        10,
+        checkpoint_file_v2.clone(),
+        summary_file_v2.clone(),
+        None,
+    );
+    let decoded = read_manifest_from_bytes(finalize_manifest(manifest_v2.clone())?.to_vec())?;
+    assert!(matches!(decoded, Manifest::V2(_)));
+    assert_eq!(decoded, manifest_v2);
+    assert_eq!(
+        decoded.files_v2(),
+        Some(vec![checkpoint_file_v2, summary_file_v2])
+    );
+
+    // Both versions expose the same files
+    assert_eq!(decoded.files(), manifest_v1.files());
+    assert_eq!(decoded.next_checkpoint_seq_num(), 10);
+    assert_eq!(decoded.epoch_num(), 0);
+
+    // Files added without size and creation time are recorded as unknown
+    manifest_v2.update(0, 20, checkpoint_file.clone(), summary_file.clone(), None);
+    let files = manifest_v2.files_v2().unwrap();
+    assert_eq!(files.len(), 4);
+    assert_eq!(files[2], FileMetadataV2::from(checkpoint_file));
+    assert_eq!(files[3].file_size, 0);
+
+    // An upgraded V1 manifest keeps its files, with their size and creation time unknown
+    let mut upgraded = manifest_v1.clone();
+    upgraded.upgrade_to_v2();
+    assert!(matches!(upgraded, Manifest::V2(_)));
+    assert_eq!(upgraded.files(), manifest_v1.files());
+    assert_eq!(
+        upgraded.next_checkpoint_seq_num(),
+        manifest_v1.next_checkpoint_seq_num()
+    );
+    assert!(upgraded
+        .files_v2()
+        .unwrap()
+        .iter()
+        .all(|f| f.file_size == 0 && f.created_at == 0));
+    Ok(())
+}
+
+#[test]
+fn test_manifest_file_for_checkpoint() {
+    let index_with_ranges = |ranges: &[std::ops::Range<u64>]| {
+        let mut manifest = Manifest::new(0, 0);
+        for range in ranges {
+            manifest.update(
+                0,
+                range.end,
+                file_metadata_from_slice(&[], FileType::CheckpointContent, 0, range.clone()),
+                file_metadata_from_slice(&[], FileType::CheckpointSummary, 0, range.clone()),
+                None,
+            );
+        }
+        manifest.index()
+    };
+
+    // Files of different sizes, not listed in checkpoint order
+    let index = index_with_ranges(&[10..25, 0..10, 25..26, 26..40]);
+    for (seq, expected) in [
+        (0, 0..10),
+        (9, 0..10),
+        (10, 10..25),
+        (24, 10..25),
+        (25, 25..26),
+        (26, 26..40),
+        (39, 26..40),
+    ] {
+        for file_type in [FileType::CheckpointContent, FileType::CheckpointSummary] {
+            let file = index
+                .file_for_checkpoint(seq, file_type)
+                .unwrap_or_else(|| panic!("no file for checkpoint {seq}"));
+            assert_eq!(file.checkpoint_seq_range, expected);
+            assert_eq!(file.file_type, file_type);
+        }
+    }
+    // No events were archived
+    assert_eq!(index.file_for_checkpoint(0, FileType::Events), None);
+    // Not archived yet
+    assert_eq!(
+        index.file_for_checkpoint(40, FileType::CheckpointSummary),
+        None
+    );
+
+    // A checkpoint in a gap between files
+    let index = index_with_ranges(&[0..10, 20..30]);
+    assert_eq!(
+        index.file_for_checkpoint(15, FileType::CheckpointContent),
+        None
+    );
+    assert!(index
+        .file_for_checkpoint(20, FileType::CheckpointContent)
+        .is_some());
+}
+
+#[test]
+fn test_manifest_find_gaps() {
+    let mut manifest = Manifest::new(0, 0);
+    for start in (0..1000).step_by(100).chain((2000..3000).step_by(100)) {
+        let range = start..start + 100;
+        manifest.update(
+            0,
+            range.end,
+            file_metadata_from_slice(&[], FileType::CheckpointContent, 0, range.clone()),
+            file_metadata_from_slice(&[], FileType::CheckpointSummary, 0, range),
+            None,
+        );
+    }
+    assert_eq!(manifest.find_gaps(), vec![1000..2000]);
+    let err = manifest
+        .next_checkpoint_after_epoch(0)
+        .expect_err("Gapped manifest should be rejected");
+    assert!(err
+        .to_string()
+        .contains("gap between checkpoint ranges 900..1000 and 2000..2100"));
+
+    assert!(Manifest::new(0, 0).find_gaps().is_empty());
+}
+
+#[test]
+fn test_manifest_covered_range() {
+    let manifest_with_ranges = |ranges: &[std::ops::Range<u64>]| {
+        let mut manifest = Manifest::new(0, 0);
+        for range in ranges {
+            manifest.update(
+                0,
+                range.end,
+                file_metadata_from_slice(&[], FileType::CheckpointContent, 0, range.clone()),
+                file_metadata_from_slice(&[], FileType::CheckpointSummary, 0, range.clone()),
+                None,
+            );
+        }
+        manifest
+    };
+    assert_eq!(
+        index_with_ranges(&[0..100, 100..200, 200..300]).covered_range(),
+        Some(0..300)
+    );
+    // missing first file
+    assert_eq!(
+        index_with_ranges(&[100..200, 200..300]).covered_range(),
+        None
+    );
+    // hole in the middle
+    assert_eq!(index_with_ranges(&[0..100, 200..300]).covered_range(), None);
+    assert_eq!(Manifest::new(0, 0).covered_range(), None);
+}
+
+#[test]
+fn test_manifest_contiguity_errors() {
+    let manifest_with_ranges = |ranges: &[std::ops::Range<u64>]| {
+        let mut manifest = Manifest::new(0, 0);
+        for range in ranges {
+            manifest.update(
+                0,
+                range.end,
+                file_metadata_from_slice(&[], FileType::CheckpointContent, 0, range.clone()),
+                file_metadata_from_slice(&[], FileType::CheckpointSummary, 0, range.clone()),
+                None,
+            );
+        }
+        manifest
+    };
+    let assert_error = |manifest: &Manifest, expected_error: &str| {
+        for err in [
+            manifest.verify_contiguity().unwrap_err(),
+            manifest.next_checkpoint_after_epoch(0).unwrap_err(),
+            manifest.last_checkpoint_of_epoch(0).unwrap_err(),
+        ] {
+            assert!(
+                err.to_string().contains(expected_error),
+                "expected '{expected_error}', got: {err}"
+            );
+        }
+    };
+
+    assert_error(&Manifest::new(0, 0), "archive has no summary files");
+    assert_error(
+        &index_with_ranges(&[0..10, 20..30]),
+        "gap between checkpoint ranges 0..10 and 20..30",
+    );
+    // ex. an archive whose older epochs were pruned
+    assert_error(
+        &index_with_ranges(&[10..20, 20..30]),
+        "archive does not start at checkpoint 0",
+    );
+}
+
+#[tokio::test]
+async fn test_reader_rejects_gapped_manifest() -> Result<()> {
+    let mut manifest = Manifest::new(0, 0);
+    for range in [0..10, 20..30] {
+        manifest.update(
+            0,
+            range.end,
+            file_metadata_from_slice(&[], FileType::CheckpointContent, 0, range.clone()),
+            file_metadata_from_slice(&[], FileType::CheckpointSummary, 0, range),
+            None,
+        );
+    }
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir()),
+        ..Default::default()
+    };
+    let archive_reader = ArchiveReader::new(
+        ArchiveReaderConfig {
+            remote_store_config,
+            download_concurrency: NonZeroUsize::new(1).unwrap(),
+            use_for_pruning_watermark: false,
+            verify_checksums: false,
+            retry_config: ArchiveRetryConfig::default(),
+        },
+        &ArchiveReaderMetrics::new(&Registry::default()),
+    )?;
+    let err = archive_reader
+        .verify_manifest(manifest)
+        .await
+        .expect_err("Gapped manifest should be rejected");
+    assert!(err.to_string().contains("gap between checkpoint ranges"));
+    Ok(())
+}
+
+#[test]
+fn test_streaming_sha3_matches_file_metadata() -> Result<()> {
+    let file_path = temp_dir().join("0.chk");
+    let data: Vec<u8> = (0..10_000u32).flat_map(|i| i.to_be_bytes()).collect();
+    let mut writer = BufWriter::new(Sha3Writer::new(File::create(&file_path)?));
+    for chunk in data.chunks(777) {
+        writer.write_all(chunk)?;
+    }
+    let (_file, streamed_digest) = writer.into_inner()?.finalize();
+
+    let from_file = create_file_metadata(&file_path, FileType::CheckpointContent, 0, 0..10)?;
+    let from_bytes = create_file_metadata_from_bytes(
+        bytes::Bytes::from(data.clone()),
+        FileType::CheckpointContent,
+        0,
+        0..10,
+    )?;
+    assert_eq!(from_file.sha3_digest, streamed_digest);
+    assert_eq!(from_bytes, from_file);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_archive_reader_e2e() -> Result<(), anyhow::Error> {
     let test_store = SharedInMemoryStore::default();
@@ -252,6 +1402,305 @@ async fn test_archive_reader_e2e() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_archive_read_summaries_only() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+    kill.send(())?;
+    test_state.archive_reader.sync_manifest_once().await?;
+    let manifest = test_state.archive_reader.get_manifest().await?;
+
+    // Remove all checkpoint contents files so that any attempt to read one of them fails
+    for file in manifest
+        .files()
+        .into_iter()
+        .filter(|f| f.file_type == FileType::CheckpointContent)
+    {
+        fs::remove_file(path_to_filesystem(
+            test_state.remote_path.clone(),
+            &file.file_path(),
+        )?)?;
+    }
+
+    let latest_archived_checkpoint_seq_num = test_state
+        .archive_reader
+        .latest_available_checkpoint()
+        .await?;
+    let summaries: Vec<_> = test_state
+        .archive_reader
+        .read_summaries_only(1..(latest_archived_checkpoint_seq_num + 1))
+        .await?
+        .try_collect()
+        .await?;
+    assert_eq!(
+        summaries
+            .iter()
+            .map(|s| s.sequence_number)
+            .collect::<Vec<_>>(),
+        (1..=latest_archived_checkpoint_seq_num).collect::<Vec<_>>()
+    );
+    for summary in summaries {
+        let expected = test_store
+            .get_checkpoint_by_sequence_number(summary.sequence_number)?
+            .context("Missing checkpoint")?;
+        assert_eq!(&summary, expected.inner());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_read_checkpoint_range() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+    kill.send(())?;
+    test_state.archive_reader.sync_manifest_once().await?;
+    let manifest = test_state.archive_reader.get_manifest().await?;
+
+    // The small file size limit makes the writer split the epoch into many files
+    let mut summary_files: Vec<_> = manifest
+        .files()
+        .into_iter()
+        .filter(|f| f.file_type == FileType::CheckpointSummary && f.epoch_num == 0)
+        .collect();
+    summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+    ma::assert_ge!(summary_files.len(), 3);
+
+    // Start and stop in the middle of files whenever they hold more than one checkpoint
+    let first = &summary_files[1].checkpoint_seq_range;
+    let last = &summary_files[summary_files.len() - 2].checkpoint_seq_range;
+    let range = (first.start + (first.end - first.start) / 2)..(last.start + 1);
+    let checkpoints: Vec<_> = test_state
+        .archive_reader
+        .read_checkpoint_range(range.clone())
+        .await?
+        .try_collect()
+        .await?;
+    assert_eq!(
+        checkpoints
+            .iter()
+            .map(|(s, _c)| s.sequence_number)
+            .collect::<Vec<_>>(),
+        range.collect::<Vec<_>>()
+    );
+    for (summary, contents) in checkpoints {
+        let expected = test_store
+            .get_checkpoint_by_sequence_number(summary.sequence_number)?
+            .context("Missing checkpoint")?;
+        assert_eq!(&summary, expected.inner());
+        let expected_contents = test_store
+            .get_full_checkpoint_contents_by_sequence_number(summary.sequence_number)?
+            .context("Missing checkpoint contents")?;
+        assert_eq!(contents, expected_contents);
+    }
+
+    // Checkpoints past the end of the archive can't be streamed
+    assert!(test_state
+        .archive_reader
+        .read_checkpoint_range(manifest.next_checkpoint_seq_num()..u64::MAX)
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_read_events() -> Result<()> {
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir()),
+        ..Default::default()
+    };
+    let remote_store = remote_store_config.make()?;
+    let retry_policy = RetryPolicy::default();
+    let mut manifest = Manifest::new(0, 0);
+    let mut expected_events = vec![];
+    for start in [0u64, 10] {
+        let range = start..start + 10;
+        let contents = Bytes::from(vec![0u8; 100]);
+        let checkpoint_file = create_file_metadata_from_bytes(
+            contents.clone(),
+            FileType::CheckpointContent,
+            0,
+            range.clone(),
+        )?;
+        let summary_file = create_file_metadata_from_bytes(
+            contents,
+            FileType::CheckpointSummary,
+            0,
+            range.clone(),
+        )?;
+        let mut events_file = vec![];
+        BlobFileHeader::new(
+            EVENTS_FILE_MAGIC,
+            StorageFormat::Blob,
+            FileCompression::None,
+        )
+        .write(&mut events_file)?;
+        for seq in range.clone() {
+            let events = vec![TransactionEvents {
+                data: vec![Event::random_for_testing()],
+            }];
+            Blob::encode(&events, BlobEncoding::Bcs)?.write(&mut events_file)?;
+            expected_events.push((seq, events));
+        }
+        let events_file = Bytes::from(events_file);
+        let events_metadata =
+            create_file_metadata_from_bytes(events_file.clone(), FileType::Events, 0, range)?;
+        assert_eq!(
+            events_metadata.file_path(),
+            Path::from(format!("epoch_0/{start}.evt"))
+        );
+        put_with_retry(
+            &remote_store,
+            &events_metadata.file_path(),
+            events_file,
+            &retry_policy,
+        )
+        .await?;
+        manifest.update(
+            0,
+            start + 10,
+            checkpoint_file,
+            summary_file,
+            Some(events_metadata),
+        );
+    }
+    write_manifest(manifest.clone(), remote_store).await?;
+
+    // Events files are ignored when looking at the checkpoint ranges of the archive
+    assert_eq!(manifest.files().len(), 6);
+    manifest.verify_contiguity()?;
+    assert!(manifest.find_gaps().is_empty());
+    assert_eq!(manifest.next_checkpoint_after_epoch(0)?, u64::MAX);
+
+    let archive_reader_config = ArchiveReaderConfig {
+        remote_store_config,
+        download_concurrency: NonZeroUsize::new(2).unwrap(),
+        use_for_pruning_watermark: false,
+        verify_checksums: true,
+        retry_config: ArchiveRetryConfig::default(),
+    };
+    let metrics = ArchiveReaderMetrics::new(&Registry::default());
+    let archive_reader = ArchiveReader::new(archive_reader_config, &metrics)?;
+    archive_reader.sync_manifest_once().await?;
+
+    // The range starts and ends in the middle of the two events files
+    let events: Vec<_> = archive_reader
+        .read_events(5..15)
+        .await?
+        .try_collect()
+        .await?;
+    assert_eq!(events, expected_events[5..15]);
+    assert!(archive_reader.read_events(20..30).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_checkpoint_transaction_count() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+    test_state.archive_reader.sync_manifest_once().await?;
+    let latest_archived_checkpoint_seq_num = test_state
+        .archive_reader
+        .latest_available_checkpoint()
+        .await?;
+    for seq in 0..=latest_archived_checkpoint_seq_num {
+        let contents = test_store
+            .get_full_checkpoint_contents_by_sequence_number(seq)?
+            .context("Missing checkpoint contents")?;
+        assert_eq!(
+            test_state
+                .archive_reader
+                .get_checkpoint_transaction_count(seq)
+                .await?,
+            contents.size() as u64
+        );
+    }
+    assert!(test_state
+        .archive_reader
+        .get_checkpoint_transaction_count(latest_archived_checkpoint_seq_num + 1)
+        .await
+        .is_err());
+    kill.send(())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_reader_rejects_corrupted_file() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+    kill.send(())?;
+    test_state.archive_reader.sync_manifest_once().await?;
+    let manifest = test_state.archive_reader.get_manifest().await?;
+    let content_file = manifest
+        .files()
+        .into_iter()
+        .find(|f| f.file_type == FileType::CheckpointContent && f.checkpoint_seq_range.start == 0)
+        .context("Missing checkpoint contents file")?;
+    assert!(test_state
+        .archive_reader
+        .get_checkpoint_transaction_count(0)
+        .await
+        .is_ok());
+
+    // Flip the last byte of the contents file without changing its length
+    let file_path = path_to_filesystem(test_state.remote_path.clone(), &content_file.file_path())?;
+    let mut bytes = fs::read(&file_path)?;
+    let last = bytes.last_mut().context("Empty checkpoint contents file")?;
+    *last ^= 0xff;
+    fs::write(&file_path, bytes)?;
+
+    let err = test_state
+        .archive_reader
+        .get_checkpoint_transaction_count(0)
+        .await
+        .expect_err("Corrupted file should be rejected");
+    assert!(err.to_string().contains("Checksum doesn't match"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verify_archive_integrity() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+    kill.send(())?;
+    let manifest = read_manifest(test_state.remote_store.clone()).await?;
+    let num_files = manifest.files().len();
+
+    let report = verify_archive_integrity(test_state.remote_store.clone(), 4).await?;
+    assert!(report.is_valid(), "{report:?}");
+    assert_eq!(report.num_files, num_files);
+
+    // Flip a byte in the middle of a summary file without changing its length
+    let summary_file = manifest
+        .files()
+        .into_iter()
+        .find(|f| f.file_type == FileType::CheckpointSummary && f.checkpoint_seq_range.start > 0)
+        .context("Missing checkpoint summary file")?;
+    let file_path = path_to_filesystem(test_state.remote_path.clone(), &summary_file.file_path())?;
+    let mut bytes = fs::read(&file_path)?;
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 0xff;
+    fs::write(&file_path, bytes)?;
+
+    let report = verify_archive_integrity(test_state.remote_store.clone(), 4).await?;
+    assert!(!report.is_valid());
+    assert_eq!(report.num_files, num_files);
+    assert_eq!(report.mismatched_files, vec![summary_file]);
+    assert!(report.missing_files.is_empty());
+    assert!(report.invalid_files.is_empty());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_verify_archive_with_oneshot_store() -> Result<(), anyhow::Error> {
     let test_store = SharedInMemoryStore::default();
@@ -3,27 +3,31 @@
 #![allow(dead_code)]
 
 use crate::{
-    create_file_metadata, read_manifest, write_manifest, CheckpointUpdates, FileMetadata, FileType,
-    Manifest, CHECKPOINT_FILE_MAGIC, CHECKPOINT_FILE_SUFFIX, EPOCH_DIR_PREFIX, MAGIC_BYTES,
-    SUMMARY_FILE_MAGIC, SUMMARY_FILE_SUFFIX,
+    create_file_metadata_with_digest, is_not_found_error, read_manifest, write_manifest,
+    BlobFileHeader, CheckpointUpdates, FileMetadata, FileMetadataV2, FileType, Manifest,
+    CHECKPOINT_FILE_MAGIC, CHECKPOINT_FILE_SUFFIX, EPOCH_DIR_PREFIX, SUMMARY_FILE_MAGIC,
+    SUMMARY_FILE_SUFFIX,
 };
 use anyhow::Result;
 use anyhow::{anyhow, Context};
-use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
 use object_store::DynObjectStore;
 use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
 use std::fs;
-use std::fs::{File, OpenOptions};
-use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use sui_config::object_storage_config::ObjectStoreConfig;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::util::{copy_file, path_to_filesystem};
-use sui_storage::{compress, FileCompression, StorageFormat};
+use sui_storage::object_store::{ObjectStoreDeleteExt, ObjectStoreGetExt, ObjectStorePutExt};
+use sui_storage::{
+    compress_with_level, compute_sha3_checksum, FileCompression, Sha3Writer, StorageFormat,
+    DEFAULT_ZSTD_COMPRESSION_LEVEL,
+};
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary as Checkpoint, CheckpointSequenceNumber,
     FullCheckpointContents as CheckpointContents,
@@ -62,6 +66,7 @@ struct CheckpointWriter {
     sender: Sender<CheckpointUpdates>,
     checkpoint_buf_offset: usize,
     file_compression: FileCompression,
+    compression_level: i32,
     storage_format: StorageFormat,
     manifest: Manifest,
     last_commit_instant: Instant,
@@ -73,6 +78,7 @@ impl CheckpointWriter {
     fn new(
         root_dir_path: PathBuf,
         file_compression: FileCompression,
+        compression_level: i32,
         storage_format: StorageFormat,
         sender: Sender<CheckpointUpdates>,
         manifest: Manifest,
@@ -111,6 +117,7 @@ impl CheckpointWriter {
             checkpoint_buf_offset: 0,
             sender,
             file_compression,
+            compression_level,
             storage_format,
             manifest,
             last_commit_instant: Instant::now(),
@@ -183,7 +190,7 @@ impl CheckpointWriter {
             .context("Checkpoint sequence num overflow")?;
         Ok(())
     }
-    fn finalize(&mut self) -> Result<FileMetadata> {
+    fn finalize(&mut self) -> Result<FileMetadataV2> {
         self.wbuf.flush()?;
         self.wbuf.get_ref().sync_data()?;
         let off = self.wbuf.get_ref().stream_position()?;
@@ -192,16 +199,16 @@ impl CheckpointWriter {
             "{}.{CHECKPOINT_FILE_SUFFIX}",
             self.checkpoint_range.start
         ));
-        self.compress(&file_path)?;
-        let file_metadata = create_file_metadata(
-            &file_path,
+        let sha3_digest = self.compress(&file_path)?;
+        let file_metadata = create_file_metadata_with_digest(
+            sha3_digest,
             FileType::CheckpointContent,
             self.epoch_num,
             self.checkpoint_range.clone(),
-        )?;
-        Ok(file_metadata)
+        );
+        Self::with_file_stats(file_metadata, &file_path)
     }
-    fn finalize_summary(&mut self) -> Result<FileMetadata> {
+    fn finalize_summary(&mut self) -> Result<FileMetadataV2> {
         self.summary_wbuf.flush()?;
         self.summary_wbuf.get_ref().sync_data()?;
         let off = self.summary_wbuf.get_ref().stream_position()?;
@@ -210,14 +217,23 @@ impl CheckpointWriter {
             "{}.{SUMMARY_FILE_SUFFIX}",
             self.checkpoint_range.start
         ));
-        self.compress(&file_path)?;
-        let file_metadata = create_file_metadata(
-            &file_path,
+        let sha3_digest = self.compress(&file_path)?;
+        let file_metadata = create_file_metadata_with_digest(
+            sha3_digest,
             FileType::CheckpointSummary,
             self.epoch_num,
             self.checkpoint_range.clone(),
-        )?;
-        Ok(file_metadata)
+        );
+        Self::with_file_stats(file_metadata, &file_path)
+    }
+    /// Records the size of the finalized file at `file_path`, and the current time as its creation
+    /// time, along with its metadata
+    fn with_file_stats(metadata: FileMetadata, file_path: &Path) -> Result<FileMetadataV2> {
+        Ok(FileMetadataV2 {
+            metadata,
+            file_size: fs::metadata(file_path)?.len(),
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        })
     }
     fn cut(&mut self) -> Result<()> {
         if !self.checkpoint_range.is_empty() {
@@ -235,16 +251,22 @@ impl CheckpointWriter {
         }
         Ok(())
     }
-    fn compress(&self, source: &Path) -> Result<()> {
+    /// Compresses the file in place (if enabled) and returns the sha3 digest of the final file.
+    /// When compressing, the digest is computed while the compressed bytes are written out so
+    /// the file doesn't have to be read back from disk a second time.
+    fn compress(&self, source: &Path) -> Result<[u8; 32]> {
         if self.file_compression == FileCompression::None {
-            return Ok(());
+            return compute_sha3_checksum(source);
         }
         let mut input = File::open(source)?;
         let tmp_file_name = source.with_extension("tmp");
-        let mut output = File::create(&tmp_file_name)?;
-        compress(&mut input, &mut output)?;
+        let mut output = Sha3Writer::new(File::create(&tmp_file_name)?);
+        compress_with_level(&mut input, &mut output, self.compression_level)?;
+        let (mut output, sha3_digest) = output.finalize();
+        output.flush()?;
+        drop(output);
         fs::rename(tmp_file_name, source)?;
-        Ok(())
+        Ok(sha3_digest)
     }
     fn next_file(
         dir_path: &Path,
@@ -255,15 +277,8 @@ impl CheckpointWriter {
         file_compression: FileCompression,
     ) -> Result<File> {
         let next_file_path = dir_path.join(format!("{checkpoint_sequence_num}.{suffix}"));
-        let mut f = File::create(next_file_path.clone())?;
-        let mut metab = [0u8; MAGIC_BYTES];
-        BigEndian::write_u32(&mut metab, magic_bytes);
-        let n = f.write(&metab)?;
-        drop(f);
-        f = OpenOptions::new().append(true).open(next_file_path)?;
-        f.seek(SeekFrom::Start(n as u64))?;
-        f.write_u8(storage_format.into())?;
-        f.write_u8(file_compression.into())?;
+        let mut f = File::create(next_file_path)?;
+        BlobFileHeader::new(magic_bytes, storage_format, file_compression).write(&mut f)?;
         Ok(f)
     }
     fn create_new_files(&mut self) -> Result<()> {
@@ -275,7 +290,7 @@ impl CheckpointWriter {
             self.storage_format,
             self.file_compression,
         )?;
-        self.checkpoint_buf_offset = MAGIC_BYTES;
+        self.checkpoint_buf_offset = BlobFileHeader::SIZE;
         self.wbuf = BufWriter::new(f);
         let f = Self::next_file(
             &self.epoch_dir(),
@@ -313,12 +328,14 @@ impl CheckpointWriter {
 /// simultaneously uploading them to a remote object store
 pub struct ArchiveWriter {
     file_compression: FileCompression,
+    compression_level: i32,
     storage_format: StorageFormat,
     local_staging_dir_root: PathBuf,
     local_object_store: Arc<DynObjectStore>,
     remote_object_store: Arc<DynObjectStore>,
     commit_duration: Duration,
     commit_file_size: usize,
+    write_manifest_v2: bool,
     archive_metrics: Arc<ArchiveMetrics>,
 }
 
@@ -334,16 +351,33 @@ impl ArchiveWriter {
     ) -> Result<Self> {
         Ok(ArchiveWriter {
             file_compression,
+            compression_level: DEFAULT_ZSTD_COMPRESSION_LEVEL,
             storage_format,
             remote_object_store: remote_store_config.make()?,
             local_object_store: local_store_config.make()?,
             local_staging_dir_root: local_store_config.directory.context("Missing local dir")?,
             commit_duration,
             commit_file_size,
+            write_manifest_v2: false,
             archive_metrics: ArchiveMetrics::new(registry),
         })
     }
 
+    /// Sets the zstd compression level of archived files, when zstd compression is enabled.
+    /// Defaults to `DEFAULT_ZSTD_COMPRESSION_LEVEL`.
+    pub fn with_compression_level(mut self, level: i32) -> Self {
+        self.compression_level = level;
+        self
+    }
+
+    /// Writes the manifest as V2, which records the size and creation time of every file, and
+    /// upgrades an existing V1 manifest. Off by default, as readers which are not upgraded can
+    /// only decode V1 manifests.
+    pub fn with_manifest_v2(mut self, write_manifest_v2: bool) -> Self {
+        self.write_manifest_v2 = write_manifest_v2;
+        self
+    }
+
     pub async fn start<S>(&self, store: S) -> Result<tokio::sync::broadcast::Sender<()>>
     where
         S: WriteStore + Send + Sync + 'static,
@@ -355,7 +389,7 @@ impl ArchiveWriter {
             .expect("Failed to read remote archive dir")
             .common_prefixes
             .is_empty();
-        let manifest = if remote_archive_is_empty {
+        let mut manifest = if remote_archive_is_empty {
             // Start from genesis
             Manifest::new(0, 0)
         } else {
@@ -363,11 +397,15 @@ impl ArchiveWriter {
                 .await
                 .expect("Failed to read manifest")
         };
+        if self.write_manifest_v2 {
+            manifest.upgrade_to_v2();
+        }
         let start_checkpoint_sequence_number = manifest.next_checkpoint_seq_num();
         let (sender, receiver) = mpsc::channel::<CheckpointUpdates>(100);
         let checkpoint_writer = CheckpointWriter::new(
             self.local_staging_dir_root.clone(),
             self.file_compression,
+            self.compression_level,
             self.storage_format,
             sender,
             manifest,
@@ -496,3 +534,34 @@ impl ArchiveWriter {
         Ok(())
     }
 }
+
+/// Prunes all epochs before `epoch` from the archive in the remote store, returning the metadata of
+/// the deleted files. The files are deleted first and the updated MANIFEST is written last, so an
+/// interrupted prune leaves the previous MANIFEST in place and can simply be retried. Files which
+/// are already missing from the store are skipped.
+pub async fn prune_archive_epochs_before<S>(
+    remote_store: S,
+    epoch: u64,
+) -> Result<Vec<FileMetadata>>
+where
+    S: ObjectStoreGetExt + ObjectStorePutExt + ObjectStoreDeleteExt + Clone,
+{
+    let mut manifest = read_manifest(remote_store.clone()).await?;
+    let pruned = manifest.prune_epochs_before(epoch)?;
+    if pruned.is_empty() {
+        return Ok(pruned);
+    }
+    for file_metadata in &pruned {
+        match remote_store.delete_object(&file_metadata.file_path()).await {
+            Err(err) if !is_not_found_error(&err) => return Err(err),
+            _ => {}
+        }
+    }
+    write_manifest(manifest, remote_store.clone()).await?;
+    info!(
+        "Pruned {} archive files from epochs before {}",
+        pruned.len(),
+        epoch
+    );
+    Ok(pruned)
+}
@@ -79,6 +79,11 @@ pub struct NodeConfig {
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub remove_deprecated_tables: bool,
 
+    /// Checks at startup that the `transaction_order` index has no gaps, logging a warning if it
+    /// does. This scans the whole table, so it is off by default.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub validate_index_transaction_order: bool,
+
     #[serde(default)]
     /// Determines the jsonrpc server type as either:
     /// - 'websocket' for a websocket based service (deprecated)
@@ -381,6 +386,8 @@ impl NodeConfig {
                         download_concurrency: NonZeroUsize::new(config.concurrency)
                             .unwrap_or(NonZeroUsize::new(5).unwrap()),
                         use_for_pruning_watermark: config.use_for_pruning_watermark,
+                        verify_checksums: config.verify_checksums.unwrap_or(true),
+                        retry_config: config.retry_config.unwrap_or_default(),
                     })
             })
             .collect()
@@ -712,6 +719,29 @@ pub struct ArchiveReaderConfig {
     pub remote_store_config: ObjectStoreConfig,
     pub download_concurrency: NonZeroUsize,
     pub use_for_pruning_watermark: bool,
+    /// Whether every downloaded file is checked against its manifest checksum before it is
+    /// decoded. Can be disabled for speed when the object store is trusted.
+    pub verify_checksums: bool,
+    pub retry_config: ArchiveRetryConfig,
+}
+
+/// Retries of transient errors while reading an archive from its remote store
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArchiveRetryConfig {
+    /// Maximum number of attempts of every read, including the first one
+    pub max_attempts: usize,
+    /// Delay in ms before the first retry, doubled after every failed attempt
+    pub base_delay_ms: u64,
+}
+
+impl Default for ArchiveRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_ms: 500,
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -721,6 +751,17 @@ pub struct StateArchiveConfig {
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
     pub use_for_pruning_watermark: bool,
+    /// Retries of transient errors when reading the archive, defaults to `ArchiveRetryConfig`'s
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_config: Option<ArchiveRetryConfig>,
+    /// Whether downloaded archive files are checked against the checksums recorded in the
+    /// manifest, defaults to true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_checksums: Option<bool>,
+    /// Whether the archive writer records the size and creation time of every file, in a V2
+    /// manifest. Only enable once all the readers of the archive can decode V2 manifests.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub write_manifest_v2: bool,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
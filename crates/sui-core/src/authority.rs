@@ -73,7 +73,7 @@ use sui_json_rpc_types::{
 };
 use sui_macros::{fail_point, fail_point_async, fail_point_if};
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
-use sui_storage::indexes::{CoinInfo, ObjectIndexChanges};
+use sui_storage::indexes::{CoinInfo, EndBound, EventSource, ObjectIndexChanges};
 use sui_storage::key_value_store::{TransactionKeyValueStore, TransactionKeyValueStoreTrait};
 use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
 use sui_storage::IndexStore;
@@ -2086,6 +2086,7 @@ impl AuthorityState {
                 digest,
                 timestamp_ms,
                 tx_coins,
+                cert.data().intent_message().value.is_system_tx(),
             )
             .await
     }
@@ -2628,6 +2629,7 @@ impl AuthorityState {
             store.perpetual_tables.clone(),
             checkpoint_store.clone(),
             rest_index.clone(),
+            indexes.clone(),
             store.objects_lock_table.clone(),
             config.authority_store_pruning_config.clone(),
             epoch_store.committee().authority_exists(&name),
@@ -3392,6 +3394,19 @@ impl AuthorityState {
         }
     }
 
+    #[instrument(level = "trace", skip_all)]
+    pub fn count_owned_objects(
+        &self,
+        owner: SuiAddress,
+        filter: Option<SuiObjectDataFilter>,
+    ) -> SuiResult<u64> {
+        if let Some(indexes) = &self.indexes {
+            indexes.count_owned_objects(owner, filter)
+        } else {
+            Err(SuiError::IndexStoreNotAvailable)
+        }
+    }
+
     #[instrument(level = "trace", skip_all)]
     pub async fn get_move_objects<T>(
         &self,
@@ -3605,6 +3620,11 @@ impl AuthorityState {
         reverse: bool,
     ) -> SuiResult<Vec<TransactionDigest>> {
         if let Some(TransactionFilter::Checkpoint(sequence_number)) = filter {
+            if let Some(indexes) = &self.indexes {
+                if indexes.is_checkpoint_indexed(sequence_number)? {
+                    return indexes.get_transactions(filter, cursor, limit, reverse);
+                }
+            }
             let checkpoint_contents = kv_store.get_checkpoint_contents(sequence_number).await?;
             let iter = checkpoint_contents.iter().map(|c| c.transaction);
             if reverse {
@@ -3825,8 +3845,16 @@ impl AuthorityState {
             EventFilter::TimeRange {
                 start_time,
                 end_time,
-            } => index_store
-                .event_iterator(start_time, end_time, tx_num, event_num, limit, descending)?,
+            } => index_store.event_iterator(
+                start_time,
+                end_time,
+                tx_num,
+                event_num,
+                limit,
+                descending,
+                EventSource::All,
+                EndBound::Inclusive,
+            )?,
             EventFilter::MoveEventModule { package, module } => index_store
                 .events_by_move_event_module(
                     &ModuleId::new(package.into(), module),
@@ -449,6 +449,17 @@ impl AuthorityStore {
         }
     }
 
+    /// Returns the latest marker for each of the given (object, epoch) pairs. Each lookup
+    /// requires a seek to the end of the object's marker range, so they are done one by one.
+    pub fn multi_get_latest_markers(
+        &self,
+        keys: &[(ObjectID, EpochId)],
+    ) -> SuiResult<Vec<Option<(SequenceNumber, MarkerValue)>>> {
+        keys.iter()
+            .map(|(object_id, epoch_id)| self.get_latest_marker(object_id, *epoch_id))
+            .collect()
+    }
+
     /// Returns future containing the state hash for the given epoch
     /// once available
     pub async fn notify_read_root_state_hash(
@@ -19,6 +19,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use std::{sync::Arc, time::Duration};
 use sui_archival::reader::ArchiveReaderBalancer;
 use sui_config::node::AuthorityStorePruningConfig;
+use sui_storage::indexes::IndexStore;
 use sui_storage::mutex_table::RwLockTable;
 use sui_types::base_types::SequenceNumber;
 use sui_types::committee::EpochId;
@@ -53,6 +54,7 @@ static PERIODIC_PRUNING_TABLES: Lazy<BTreeSet<String>> = Lazy::new(|| {
     .collect()
 });
 pub const EPOCH_DURATION_MS_FOR_TESTING: u64 = 24 * 60 * 60 * 1000;
+const INDEX_TABLE_SIZES_REPORT_INTERVAL: Duration = Duration::from_secs(60);
 pub struct AuthorityStorePruner {
     _objects_pruner_cancel_handle: oneshot::Sender<()>,
 }
@@ -622,6 +624,7 @@ impl AuthorityStorePruner {
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
         rest_index: Option<Arc<RestIndexStore>>,
+        indexes: Option<Arc<IndexStore>>,
         objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
         metrics: Arc<AuthorityStorePruningMetrics>,
         indirect_objects_threshold: usize,
@@ -644,6 +647,10 @@ impl AuthorityStorePruner {
             tokio::time::interval_at(Instant::now() + pruning_initial_delay, tick_duration);
         let mut checkpoints_prune_interval =
             tokio::time::interval_at(Instant::now() + pruning_initial_delay, tick_duration);
+        // Index table sizes change with indexing as well as pruning, so they are reported on their
+        // own schedule, starting right away.
+        let mut index_table_sizes_interval =
+            tokio::time::interval(INDEX_TABLE_SIZES_REPORT_INTERVAL);
 
         let perpetual_db_for_compaction = perpetual_db.clone();
         if let Some(delay_days) = config.periodic_compaction_threshold_days {
@@ -692,6 +699,11 @@ impl AuthorityStorePruner {
                             error!("Failed to prune checkpoints: {:?}", err);
                         }
                     },
+                    _ = index_table_sizes_interval.tick(), if indexes.is_some() => {
+                        if let Some(Err(err)) = indexes.as_ref().map(|indexes| indexes.report_table_sizes()) {
+                            error!("Failed to report index table sizes: {:?}", err);
+                        }
+                    },
                     _ = &mut recv => break,
                 }
             }
@@ -703,6 +715,7 @@ impl AuthorityStorePruner {
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
         rest_index: Option<Arc<RestIndexStore>>,
+        indexes: Option<Arc<IndexStore>>,
         objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
         mut pruning_config: AuthorityStorePruningConfig,
         is_validator: bool,
@@ -728,6 +741,7 @@ impl AuthorityStorePruner {
                 perpetual_db,
                 checkpoint_store,
                 rest_index,
+                indexes,
                 objects_lock_table,
                 AuthorityStorePruningMetrics::new(registry),
                 indirect_objects_threshold,
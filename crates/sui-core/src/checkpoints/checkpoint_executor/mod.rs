@@ -1322,6 +1322,10 @@ async fn finalize_checkpoint(
             checkpoint.sequence_number,
         )?;
 
+    if let Some(indexes) = &state.indexes {
+        indexes.index_checkpoint_transactions(checkpoint.sequence_number, tx_digests)?;
+    }
+
     let checkpoint_acc =
         accumulator.accumulate_checkpoint(effects, checkpoint.sequence_number, epoch_store)?;
 
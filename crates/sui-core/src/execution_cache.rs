@@ -46,7 +46,7 @@ pub mod writeback_cache;
 
 pub use passthrough_cache::PassthroughCache;
 pub use proxy_cache::ProxyCache;
-pub use writeback_cache::WritebackCache;
+pub use writeback_cache::{CacheStats, WritebackCache};
 
 use metrics::ExecutionCacheMetrics;
 
@@ -404,6 +404,13 @@ pub trait ObjectCacheRead: Send + Sync {
         epoch_id: EpochId,
     ) -> SuiResult<Option<(SequenceNumber, MarkerValue)>>;
 
+    /// Get the latest marker for each of the given objects. Results are in the same order as
+    /// the keys.
+    fn multi_get_latest_markers(
+        &self,
+        keys: &[(ObjectID, EpochId)],
+    ) -> SuiResult<Vec<Option<(SequenceNumber, MarkerValue)>>>;
+
     /// If the shared object was deleted, return deletion info for the current live version
     fn get_last_shared_object_deletion_info(
         &self,
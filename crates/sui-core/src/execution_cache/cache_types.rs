@@ -84,6 +84,15 @@ impl<V> CachedVersionMap<V> {
         self.values.front()
     }
 
+    // iterate mutably over all but the `keep` newest versions, oldest first
+    pub fn iter_mut_older_than_newest(
+        &mut self,
+        keep: usize,
+    ) -> impl Iterator<Item = &mut (SequenceNumber, V)> {
+        let older = self.values.len().saturating_sub(keep);
+        self.values.iter_mut().take(older)
+    }
+
     // pop items from the front of the collection until the size is <= limit
     pub fn truncate_to(&mut self, limit: usize) {
         while self.values.len() > limit {
@@ -276,6 +285,21 @@ mod tests {
         assert_eq!(map.values.front().unwrap().0, seq(3));
     }
 
+    #[test]
+    fn iter_mut_older_than_newest_skips_newest() {
+        let mut map = CachedVersionMap::default();
+        for i in 1..=4 {
+            map.insert(seq(i), i);
+        }
+        for (_, value) in map.iter_mut_older_than_newest(2) {
+            *value = 0;
+        }
+        let values: Vec<_> = map.values.iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, vec![0, 0, 3, 4]);
+
+        assert_eq!(map.iter_mut_older_than_newest(10).count(), 0);
+    }
+
     #[test]
     fn get_last_on_empty_map() {
         let map: CachedVersionMap<&str> = CachedVersionMap::default();
@@ -262,10 +262,12 @@ impl ObjectLocks {
 
 #[cfg(test)]
 mod tests {
+    use crate::authority::authority_store::{LockDetailsDeprecated, ObjectLockStatus};
     use crate::execution_cache::{
-        writeback_cache::writeback_cache_tests::Scenario, ExecutionCacheWrite,
+        writeback_cache::writeback_cache_tests::Scenario, ExecutionCacheWrite, ObjectCacheRead,
     };
     use futures::FutureExt;
+    use sui_types::error::{SuiError, UserInputError};
 
     #[tokio::test]
     async fn test_transaction_locks_are_exclusive() {
@@ -422,4 +424,126 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_transaction_locks_across_commit() {
+        telemetry_subscribers::init_for_testing();
+        Scenario::iterate(|mut s| async move {
+            s.with_created(&[1]);
+            let tx = s.do_tx().await;
+            s.commit(tx).await.unwrap();
+
+            let old1 = s.obj_ref(1);
+
+            s.with_mutated(&[1]); // begin forming a tx but never execute it
+            let tx1 = s.take_outputs().transaction.clone();
+            let tx1 = s.make_signed_transaction(&tx1);
+            s.with_created(&[2]);
+            let tx2 = s.take_outputs().transaction.clone();
+            let tx2 = s.make_signed_transaction(&tx2);
+
+            s.cache
+                .acquire_transaction_locks(&s.epoch_store, &[old1], tx1.clone())
+                .await
+                .expect("lock should be available");
+            let locked_to_tx1 = ObjectLockStatus::LockedToTx {
+                locked_by_tx: LockDetailsDeprecated {
+                    epoch: s.epoch_store.epoch(),
+                    tx_digest: *tx1.digest(),
+                },
+            };
+            assert_eq!(
+                s.cache.get_lock(old1, &s.epoch_store).unwrap(),
+                locked_to_tx1
+            );
+
+            // locking the same object for a different tx is an equivocation
+            let err = s
+                .cache
+                .acquire_transaction_locks(&s.epoch_store, &[old1], tx2.clone())
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                SuiError::ObjectLockConflict { pending_transaction, .. }
+                    if pending_transaction == *tx1.digest()
+            ));
+
+            // the lock is read back from the db by a fresh cache
+            s.reset_cache();
+            assert_eq!(
+                s.cache.get_lock(old1, &s.epoch_store).unwrap(),
+                locked_to_tx1
+            );
+
+            // once the object is mutated and committed, the old version can no longer be locked
+            s.with_mutated(&[1]);
+            let tx = s.do_tx().await;
+            s.commit(tx).await.unwrap();
+            let new1 = s.obj_ref(1);
+
+            assert_eq!(
+                s.cache.get_lock(old1, &s.epoch_store).unwrap(),
+                ObjectLockStatus::LockedAtDifferentVersion { locked_ref: new1 }
+            );
+            assert_eq!(
+                s.cache.get_lock(new1, &s.epoch_store).unwrap(),
+                ObjectLockStatus::Initialized
+            );
+            let err = s
+                .cache
+                .acquire_transaction_locks(&s.epoch_store, &[old1], tx2.clone())
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                SuiError::UserInputError {
+                    error: UserInputError::ObjectVersionUnavailableForConsumption { .. }
+                }
+            ));
+            s.cache
+                .acquire_transaction_locks(&s.epoch_store, &[new1], tx2)
+                .await
+                .expect("new1 should be unlocked");
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_transaction_locks_fall_back_to_store() {
+        telemetry_subscribers::init_for_testing();
+        Scenario::iterate(|mut s| async move {
+            s.with_created(&[1, 2]);
+            let tx = s.do_tx().await;
+            s.commit(tx).await.unwrap();
+
+            // the new cache has never seen the objects, so every read goes to the store
+            s.reset_cache();
+            let obj1 = s.obj_ref(1);
+            let obj2 = s.obj_ref(2);
+            s.cache.check_owned_objects_are_live(&[obj1, obj2]).unwrap();
+            assert_eq!(
+                s.cache.get_lock(obj1, &s.epoch_store).unwrap(),
+                ObjectLockStatus::Initialized
+            );
+
+            s.with_mutated(&[1, 2]); // begin forming a tx but never execute it
+            let tx1 = s.take_outputs().transaction.clone();
+            let tx1 = s.make_signed_transaction(&tx1);
+            s.cache
+                .acquire_transaction_locks(&s.epoch_store, &[obj1, obj2], tx1.clone())
+                .await
+                .expect("locks should be available");
+            assert_eq!(
+                s.cache.get_lock(obj2, &s.epoch_store).unwrap(),
+                ObjectLockStatus::LockedToTx {
+                    locked_by_tx: LockDetailsDeprecated {
+                        epoch: s.epoch_store.epoch(),
+                        tx_digest: *tx1.digest(),
+                    },
+                }
+            );
+        })
+        .await;
+    }
 }
@@ -183,6 +183,13 @@ impl ObjectCacheRead for PassthroughCache {
         self.store.get_latest_marker(object_id, epoch_id)
     }
 
+    fn multi_get_latest_markers(
+        &self,
+        keys: &[(ObjectID, EpochId)],
+    ) -> SuiResult<Vec<Option<(SequenceNumber, MarkerValue)>>> {
+        self.store.multi_get_latest_markers(keys)
+    }
+
     fn get_highest_pruned_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
         self.store.perpetual_tables.get_highest_pruned_checkpoint()
     }
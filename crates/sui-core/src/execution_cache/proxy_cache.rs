@@ -25,6 +25,7 @@ use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Object;
 use sui_types::storage::{MarkerValue, ObjectKey, ObjectOrTombstone, PackageObject};
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::SuiSystemState;
 use sui_types::transaction::{VerifiedSignedTransaction, VerifiedTransaction};
 
@@ -90,6 +91,9 @@ impl ProxyCache {
             // short sleep here just to be sure).
             tokio::time::sleep(Duration::from_nanos(100)).await;
             self.writeback_cache.clear_caches_and_assert_empty();
+        } else {
+            self.writeback_cache
+                .evict_markers_before_epoch(epoch_start_config.epoch_start_state().epoch());
         }
         *self.mode.write() = cache_type;
     }
@@ -194,6 +198,13 @@ impl ObjectCacheRead for ProxyCache {
         delegate_method!(self.get_latest_marker(object_id, epoch_id))
     }
 
+    fn multi_get_latest_markers(
+        &self,
+        keys: &[(ObjectID, EpochId)],
+    ) -> SuiResult<Vec<Option<(SequenceNumber, MarkerValue)>>> {
+        delegate_method!(self.multi_get_latest_markers(keys))
+    }
+
     fn get_highest_pruned_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
         delegate_method!(self.get_highest_pruned_checkpoint())
     }
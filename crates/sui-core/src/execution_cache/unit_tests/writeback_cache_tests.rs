@@ -369,10 +369,11 @@ impl Scenario {
     }
 
     pub fn reset_cache(&mut self) {
-        self.cache = Arc::new(WritebackCache::new(
-            self.store.clone(),
-            self.cache.metrics.clone(),
-        ));
+        let mut cache = WritebackCache::new(self.store.clone(), self.cache.metrics.clone());
+        cache.object_compression_age = self.cache.object_compression_age;
+        cache.event_referers_count_threshold = self.cache.event_referers_count_threshold;
+        cache.strict_invariants = self.cache.strict_invariants;
+        self.cache = Arc::new(cache);
 
         // reset the scenario state to match the db
         let reverse_id_map: BTreeMap<_, _> = self.id_map.iter().map(|(k, v)| (*v, *k)).collect();
@@ -390,6 +391,21 @@ impl Scenario {
         });
     }
 
+    // Must be called before any transactions are executed.
+    pub fn with_object_compression_age(&mut self, age: usize) {
+        self.cache = Arc::new(
+            WritebackCache::new(self.store.clone(), self.cache.metrics.clone())
+                .with_object_compression_age(age),
+        );
+    }
+
+    pub fn with_event_referers_count_threshold(&mut self, threshold: usize) {
+        self.cache = Arc::new(
+            WritebackCache::new(self.store.clone(), self.cache.metrics.clone())
+                .with_event_referers_count_threshold(threshold),
+        );
+    }
+
     pub fn assert_live(&self, short_ids: &[u32]) {
         for short_id in short_ids {
             let id = self.id_map.get(short_id).expect("no such object");
@@ -632,6 +648,355 @@ async fn test_received() {
     .await;
 }
 
+#[tokio::test]
+async fn test_latest_marker_caching() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_created(&[1, 2]);
+        let tx1 = s.do_tx().await;
+
+        s.with_mutated(&[1, 2]);
+        s.with_received(&[1]);
+        let tx2 = s.do_tx().await;
+        s.commit(tx1).await.unwrap();
+        s.commit(tx2).await.unwrap();
+
+        s.reset_cache();
+
+        let key = (1, s.obj_id(1));
+        let expected = Some((s.object(1).version(), MarkerValue::Received));
+
+        // latest marker not yet cached, the first read falls back to the store
+        assert!(!s.cache.cached.marker_cache.contains_key(&key));
+        assert_eq!(
+            s.cache().get_latest_marker(&s.obj_id(1), 1).unwrap(),
+            expected
+        );
+
+        // query above populates cache
+        assert_eq!(
+            s.cache
+                .cached
+                .marker_cache
+                .get(&key)
+                .unwrap()
+                .lock()
+                .get_highest()
+                .cloned(),
+            expected
+        );
+
+        // subsequent reads are served from the cache
+        assert_eq!(
+            s.cache().get_latest_marker(&s.obj_id(1), 1).unwrap(),
+            expected
+        );
+        assert!(s
+            .cache()
+            .have_received_object_at_version(&s.obj_id(1), s.object(1).version(), 1)
+            .unwrap());
+
+        // no marker exists for the other object, so nothing gets cached for it
+        assert!(s
+            .cache()
+            .get_latest_marker(&s.obj_id(2), 1)
+            .unwrap()
+            .is_none());
+        assert!(!s.cache.cached.marker_cache.contains_key(&(1, s.obj_id(2))));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_latest_marker_not_cached_after_commit() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_created(&[1]);
+        let tx1 = s.do_tx().await;
+        s.commit(tx1).await.unwrap();
+
+        // a marker is read from the db before a newer marker is committed and evicted
+        let ticket = s.cache.cached.marker_ticket();
+        let stale_version = s.object(1).version();
+
+        s.with_mutated(&[1]);
+        s.with_received(&[1]);
+        let tx2 = s.do_tx().await;
+        s.commit(tx2).await.unwrap();
+
+        let key = (1, s.obj_id(1));
+        s.cache.cached.marker_cache.invalidate(&key);
+
+        // the stale marker must not be cached in place of the committed one
+        s.cache
+            .cache_latest_marker(key, stale_version, MarkerValue::Received, ticket);
+        assert!(!s.cache.cached.marker_cache.contains_key(&key));
+
+        // a marker read after the commit is cached
+        s.cache.cache_latest_marker(
+            key,
+            s.object(1).version(),
+            MarkerValue::Received,
+            s.cache.cached.marker_ticket(),
+        );
+        assert!(s.cache.cached.marker_cache.contains_key(&key));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_multi_get_latest_markers() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_created(&[1, 2, 3, 4]);
+        let tx1 = s.do_tx().await;
+        s.with_mutated(&[1]);
+        s.with_received(&[1]);
+        let tx2 = s.do_tx().await;
+        s.commit(tx1).await.unwrap();
+        s.commit(tx2).await.unwrap();
+
+        // marker for 1 is now only in the store
+        s.reset_cache();
+
+        // marker for 2 is committed and moved to the cache
+        s.with_mutated(&[2]);
+        s.with_received(&[2]);
+        let tx3 = s.do_tx().await;
+        s.commit(tx3).await.unwrap();
+
+        // marker for 3 is uncommitted
+        s.with_mutated(&[3]);
+        s.with_received(&[3]);
+        s.do_tx().await;
+
+        // 4 has no marker at all
+        let keys: Vec<_> = [1, 4, 3, 2].iter().map(|id| (s.obj_id(*id), 1)).collect();
+        let expected: Vec<_> = [1, 4, 3, 2]
+            .iter()
+            .map(|id| (*id != 4).then(|| (s.object(*id).version(), MarkerValue::Received)))
+            .collect();
+        assert_eq!(s.cache().multi_get_latest_markers(&keys).unwrap(), expected);
+
+        // each result matches the single-object lookup
+        for ((object_id, epoch_id), marker) in keys.iter().zip(expected.iter()) {
+            assert_eq!(
+                s.cache().get_latest_marker(object_id, *epoch_id).unwrap(),
+                *marker
+            );
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_multi_get_objects_by_key_result_order() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_created(&[1, 2, 3, 4]);
+        let tx1 = s.do_tx().await;
+        s.commit(tx1).await.unwrap();
+
+        // the created objects are now only in the store
+        s.reset_cache();
+        let [obj1, obj2_v1, obj3, obj4] = [1, 2, 3, 4].map(|id| s.object(id));
+
+        // uncommitted mutation of 2 and deletion of 3
+        s.with_mutated(&[2]);
+        s.with_deleted(&[3]);
+        s.do_tx().await;
+        let obj2_v2 = s.object(2);
+        let mut deleted_version = obj3.version();
+        deleted_version.increment();
+
+        let missing_id = ObjectID::random();
+        let lookups = [
+            // cache hit
+            (
+                ObjectKey(obj2_v2.id(), obj2_v2.version()),
+                Some(obj2_v2.clone()),
+            ),
+            // store hit
+            (ObjectKey(obj1.id(), obj1.version()), Some(obj1.clone())),
+            // negative cache hit
+            (ObjectKey(obj3.id(), deleted_version), None),
+            // store hit for an older version of a cached object
+            (
+                ObjectKey(obj2_v1.id(), obj2_v1.version()),
+                Some(obj2_v1.clone()),
+            ),
+            // store miss
+            (ObjectKey(missing_id, obj1.version()), None),
+            // store hit
+            (ObjectKey(obj4.id(), obj4.version()), Some(obj4.clone())),
+            // cache hit
+            (
+                ObjectKey(obj2_v2.id(), obj2_v2.version()),
+                Some(obj2_v2.clone()),
+            ),
+        ];
+        let keys: Vec<_> = lookups.iter().map(|(key, _)| *key).collect();
+        let results = s.cache().multi_get_objects_by_key(&keys).unwrap();
+
+        assert_eq!(results.len(), lookups.len());
+        for (i, (result, (key, expected))) in results.iter().zip(lookups.iter()).enumerate() {
+            assert_eq!(result, expected, "wrong result in slot {i} for key {key:?}");
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_prefetch_objects() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_packages(&[1]);
+        s.with_created(&[2]);
+        let tx = s.do_tx().await;
+        s.commit(tx).await.unwrap();
+
+        // the objects are now only in the store
+        s.reset_cache();
+        let package = s.object(1);
+        let object = s.object(2);
+        assert!(matches!(
+            s.cache
+                .get_object_by_key_cache_only(&package.id(), package.version()),
+            CacheResult::Miss
+        ));
+
+        s.cache
+            .prefetch_objects(&[
+                ObjectKey(package.id(), package.version()),
+                ObjectKey(object.id(), object.version()),
+                ObjectKey(ObjectID::random(), package.version()),
+            ])
+            .unwrap();
+
+        // only the immutable package is cached
+        match s
+            .cache
+            .get_object_by_key_cache_only(&package.id(), package.version())
+        {
+            CacheResult::Hit(cached) => assert_eq!(cached, package),
+            _ => panic!("package should be cached"),
+        }
+        assert!(s
+            .cache
+            .cached
+            .object_by_id_cache
+            .contains_key(&package.id()));
+        assert!(!s.cache.cached.object_cache.contains_key(&object.id()));
+        s.cache.cached.object_cache.run_pending_tasks();
+        assert_eq!(s.cache.cached.object_cache.entry_count(), 1);
+
+        // reads are unaffected
+        s.assert_live(&[1, 2]);
+    })
+    .await;
+}
+
+#[test]
+fn test_move_missing_version_from_dirty_to_cache() {
+    telemetry_subscribers::init_for_testing();
+    let dirty: DashMap<MarkerKey, CachedVersionMap<MarkerValue>> = DashMap::new();
+    let cache: MokaCache<MarkerKey, Arc<Mutex<CachedVersionMap<MarkerValue>>>> =
+        MokaCache::builder().build();
+    let key = (1, ObjectID::random());
+    let cache_older_version = |cache: &MokaCache<_, Arc<Mutex<CachedVersionMap<_>>>>| {
+        let mut cache_map = CachedVersionMap::default();
+        cache_map.insert(SequenceNumber::MIN, MarkerValue::Received);
+        cache.insert(key, Arc::new(Mutex::new(cache_map)));
+    };
+
+    // the dirty entry is missing, e.g. because a racing commit already moved it. The cached
+    // versions may be older than the committed one, so they are invalidated
+    cache_older_version(&cache);
+    assert!(!WritebackCache::move_version_from_dirty_to_cache(
+        false,
+        &dirty,
+        &cache,
+        key,
+        OBJECT_START_VERSION,
+        &MarkerValue::Received,
+    ));
+    assert!(dirty.is_empty());
+    assert!(!cache.contains_key(&key));
+
+    // the oldest dirty version is not the committed one
+    cache_older_version(&cache);
+    dirty
+        .entry(key)
+        .or_default()
+        .insert(OBJECT_START_VERSION, MarkerValue::OwnedDeleted);
+    assert!(!WritebackCache::move_version_from_dirty_to_cache(
+        false,
+        &dirty,
+        &cache,
+        key,
+        OBJECT_START_VERSION,
+        &MarkerValue::Received,
+    ));
+    assert!(dirty.is_empty());
+    assert!(!cache.contains_key(&key));
+}
+
+#[test]
+#[should_panic(expected = "dirty map must exist")]
+fn test_move_missing_version_from_dirty_to_cache_strict() {
+    let dirty: DashMap<MarkerKey, CachedVersionMap<MarkerValue>> = DashMap::new();
+    let cache: MokaCache<MarkerKey, Arc<Mutex<CachedVersionMap<MarkerValue>>>> =
+        MokaCache::builder().build();
+
+    WritebackCache::move_version_from_dirty_to_cache(
+        true,
+        &dirty,
+        &cache,
+        (1, ObjectID::random()),
+        OBJECT_START_VERSION,
+        &MarkerValue::Received,
+    );
+}
+
+#[tokio::test]
+async fn test_evict_markers_before_epoch() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_created(&[1, 2]);
+        s.do_tx().await;
+        let version = s.object(1).version();
+
+        // uncommitted and cached markers for both epoch 1 and epoch 2
+        for epoch in [1, 2] {
+            s.cache
+                .write_marker_value(
+                    epoch,
+                    &ObjectKey(s.obj_id(1), version),
+                    MarkerValue::Received,
+                )
+                .await;
+            s.cache.cache_latest_marker(
+                (epoch, s.obj_id(2)),
+                version,
+                MarkerValue::Received,
+                s.cache.cached.marker_ticket(),
+            );
+        }
+
+        s.cache.evict_markers_before_epoch(2);
+        s.cache.cached.marker_cache.run_pending_tasks();
+
+        // uncommitted markers are never dropped, only cached ones are evicted
+        assert!(s.cache.dirty.markers.contains_key(&(1, s.obj_id(1))));
+        assert!(!s.cache.cached.marker_cache.contains_key(&(1, s.obj_id(2))));
+        assert!(s.cache.dirty.markers.contains_key(&(2, s.obj_id(1))));
+        assert!(s.cache.cached.marker_cache.contains_key(&(2, s.obj_id(2))));
+        assert_eq!(s.cache.dirty.markers.len(), 2);
+        assert_eq!(s.cache.cached.marker_cache.entry_count(), 1);
+    })
+    .await;
+}
+
 #[tokio::test]
 async fn test_extra_outputs() {
     telemetry_subscribers::init_for_testing();
@@ -849,6 +1214,187 @@ async fn test_lt_or_eq_with_cached_tombstone() {
     .await;
 }
 
+#[tokio::test]
+async fn test_compressed_cached_objects() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_object_compression_age(1);
+
+        // make 3 versions of the object: 1, 3, 5
+        s.with_created(&[1]);
+        let tx1 = s.do_tx().await;
+        let v1 = s.object(1);
+        s.with_mutated_version_delta(&[1], 2);
+        let tx2 = s.do_tx().await;
+        let v3 = s.object(1);
+        s.with_mutated_version_delta(&[1], 2);
+        let tx3 = s.do_tx().await;
+        let v5 = s.object(1);
+
+        s.commit(tx1).await.unwrap();
+        s.commit(tx2).await.unwrap();
+        s.commit(tx3).await.unwrap();
+
+        // compressed versions are reconstructed from the store
+        for expected in [&v1, &v3, &v5] {
+            assert_eq!(
+                s.cache()
+                    .get_object_by_key(&s.obj_id(1), expected.version())
+                    .unwrap()
+                    .unwrap(),
+                *expected
+            );
+        }
+
+        // missing versions between cached versions are still negative hits
+        assert!(s
+            .cache()
+            .get_object_by_key(&s.obj_id(1), SequenceNumber::from_u64(4))
+            .unwrap()
+            .is_none());
+
+        let check_version = |lookup_version: u64, expected: &Object| {
+            assert_eq!(
+                s.cache()
+                    .find_object_lt_or_eq_version(
+                        s.obj_id(1),
+                        SequenceNumber::from_u64(lookup_version)
+                    )
+                    .unwrap()
+                    .unwrap(),
+                *expected
+            );
+        };
+        check_version(1, &v1);
+        check_version(2, &v1);
+        check_version(3, &v3);
+        check_version(4, &v3);
+        check_version(5, &v5);
+
+        s.assert_live(&[1]);
+    })
+    .await;
+}
+
+// Not run through `Scenario::iterate`, as the cache entry checked here would be missing after
+// an eviction.
+#[tokio::test]
+async fn test_compressed_cached_object_entries() {
+    telemetry_subscribers::init_for_testing();
+    let mut s = Scenario::new(None, Arc::new(AtomicU32::new(0))).await;
+    s.with_object_compression_age(1);
+
+    // make 3 versions of the object: 1, 3, 5
+    s.with_created(&[1]);
+    let tx1 = s.do_tx().await;
+    s.with_mutated_version_delta(&[1], 2);
+    let tx2 = s.do_tx().await;
+    s.with_mutated_version_delta(&[1], 2);
+    let tx3 = s.do_tx().await;
+
+    s.commit(tx1).await.unwrap();
+    s.commit(tx2).await.unwrap();
+    s.commit(tx3).await.unwrap();
+
+    // only the newest cached version is stored in full
+    let entry = s
+        .cache
+        .cached
+        .object_cache
+        .get(&s.obj_id(1))
+        .expect("object should be cached");
+    let entry = entry.lock();
+    let (highest, _) = entry.get_highest().unwrap();
+    assert_eq!(*highest, SequenceNumber::from_u64(5));
+    for version in [1, 3, 5].map(SequenceNumber::from_u64) {
+        match entry.get(&version) {
+            Some(ObjectEntry::Compressed(obj_ref)) => {
+                assert!(version < *highest);
+                assert_eq!(obj_ref.1, version);
+            }
+            Some(ObjectEntry::Object(object)) => {
+                assert_eq!(version, *highest);
+                assert_eq!(object.version(), version);
+            }
+            other => panic!("unexpected cache entry {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_cache_stats() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_created(&[1, 2, 3]);
+        let tx1 = s.do_tx().await;
+        s.with_mutated(&[1]);
+        let tx2 = s.do_tx().await;
+
+        let stats = s.cache().cache_stats();
+        assert_eq!(stats.dirty_objects, 3);
+        assert_eq!(stats.pending_txns, 2);
+
+        s.commit(tx1).await.unwrap();
+        let stats = s.cache().cache_stats();
+        assert_eq!(stats.dirty_objects, 1);
+        assert_eq!(stats.pending_txns, 1);
+
+        s.commit(tx2).await.unwrap();
+        s.cache.cached.object_cache.run_pending_tasks();
+        s.cache.cached.marker_cache.run_pending_tasks();
+        let stats = s.cache().cache_stats();
+        assert_eq!(stats.dirty_objects, 0);
+        assert_eq!(stats.pending_txns, 0);
+        assert!(stats.object_cache_len <= 3);
+        assert_eq!(stats.marker_cache_len, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_shared_events_with_referer_count() {
+    telemetry_subscribers::init_for_testing();
+    Scenario::iterate(|mut s| async move {
+        s.with_event_referers_count_threshold(2);
+
+        // transactions without events all share the same, empty, TransactionEvents
+        let mut txns = Vec::new();
+        for i in 0..8 {
+            s.with_created(&[i]);
+            txns.push(s.do_tx().await);
+        }
+        let fx = s.cache.get_executed_effects(&txns[0]).unwrap().unwrap();
+        let events_digest = *fx.events_digest().unwrap();
+        assert!(matches!(
+            s.cache
+                .dirty
+                .transaction_events
+                .get(&events_digest)
+                .unwrap()
+                .0,
+            EventReferers::Count { count: 8, .. }
+        ));
+
+        let last = txns.pop().unwrap();
+        for tx in txns {
+            s.commit(tx).await.unwrap();
+            assert!(s
+                .cache
+                .dirty
+                .transaction_events
+                .contains_key(&events_digest));
+        }
+
+        s.commit(last).await.unwrap();
+        assert!(!s
+            .cache
+            .dirty
+            .transaction_events
+            .contains_key(&events_digest));
+    })
+    .await;
+}
+
 #[tokio::test]
 async fn test_write_transaction_outputs_is_sync() {
     telemetry_subscribers::init_for_testing();
@@ -60,6 +60,7 @@ use parking_lot::Mutex;
 use prometheus::Registry;
 use std::collections::{BTreeMap, BTreeSet};
 use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use sui_macros::fail_point_async;
 use sui_protocol_config::ProtocolVersion;
@@ -95,6 +96,10 @@ enum ObjectEntry {
     Object(Object),
     Deleted,
     Wrapped,
+    /// A committed live object for which only the reference is kept in memory. The full
+    /// object is read from the store on demand. Only ever present in `object_cache`, and
+    /// never for the highest cached version of an object.
+    Compressed(ObjectRef),
 }
 
 impl ObjectEntry {
@@ -109,7 +114,7 @@ impl ObjectEntry {
     fn is_tombstone(&self) -> bool {
         match self {
             ObjectEntry::Deleted | ObjectEntry::Wrapped => true,
-            ObjectEntry::Object(_) => false,
+            ObjectEntry::Object(_) | ObjectEntry::Compressed(_) => false,
         }
     }
 }
@@ -122,6 +127,7 @@ impl std::fmt::Debug for ObjectEntry {
             }
             ObjectEntry::Deleted => write!(f, "ObjectEntry::Deleted"),
             ObjectEntry::Wrapped => write!(f, "ObjectEntry::Wrapped"),
+            ObjectEntry::Compressed(obj_ref) => write!(f, "ObjectEntry::Compressed({:?})", obj_ref),
         }
     }
 }
@@ -212,11 +218,10 @@ struct UncommittedData {
     transaction_effects: DashMap<TransactionEffectsDigest, TransactionEffects>,
 
     // Because TransactionEvents are not unique to the transaction that created them, we must
-    // reference count them in order to know when we can remove them from the cache. For now
-    // we track all referers explicitly, but we can use a ref count when we are confident in
-    // the correctness of the code.
-    transaction_events:
-        DashMap<TransactionEventsDigest, (BTreeSet<TransactionDigest>, TransactionEvents)>,
+    // reference count them in order to know when we can remove them from the cache. Referers
+    // are tracked explicitly unless `event_referers_count_threshold` is set, in which case an
+    // entry with too many referers falls back to a plain count (see EventReferers).
+    transaction_events: DashMap<TransactionEventsDigest, (EventReferers, TransactionEvents)>,
 
     executed_effects_digests: DashMap<TransactionDigest, TransactionEffectsDigest>,
 
@@ -225,6 +230,80 @@ struct UncommittedData {
     pending_transaction_writes: DashMap<TransactionDigest, Arc<TransactionOutputs>>,
 }
 
+/// The set of uncommitted transactions that refer to a TransactionEvents entry.
+enum EventReferers {
+    Digests(BTreeSet<TransactionDigest>),
+    // Used once the number of referers exceeds the configured threshold. In debug builds the
+    // explicit set is still maintained so that the count can be checked against it.
+    Count {
+        count: usize,
+        #[cfg(debug_assertions)]
+        digests: BTreeSet<TransactionDigest>,
+    },
+}
+
+impl EventReferers {
+    fn new(tx_digest: TransactionDigest) -> Self {
+        Self::Digests(BTreeSet::from([tx_digest]))
+    }
+
+    /// Adds a referer. If `count_threshold` is set and the explicit set grows beyond it, the
+    /// set is replaced by a count.
+    fn insert(&mut self, tx_digest: TransactionDigest, count_threshold: Option<usize>) {
+        match self {
+            Self::Digests(txns) => {
+                txns.insert(tx_digest);
+                if count_threshold.is_some_and(|threshold| txns.len() > threshold) {
+                    let txns = std::mem::take(txns);
+                    *self = Self::Count {
+                        count: txns.len(),
+                        #[cfg(debug_assertions)]
+                        digests: txns,
+                    };
+                }
+            }
+            Self::Count {
+                count,
+                #[cfg(debug_assertions)]
+                digests,
+            } => {
+                // Without the explicit set we cannot tell whether this referer was already
+                // counted, so callers must never add the same transaction twice.
+                #[cfg(debug_assertions)]
+                assert!(
+                    digests.insert(tx_digest),
+                    "transaction referer already exists"
+                );
+                *count += 1;
+                #[cfg(debug_assertions)]
+                assert_eq!(*count, digests.len());
+            }
+        }
+    }
+
+    /// Removes a referer, returning true if no referers remain.
+    fn remove(&mut self, tx_digest: &TransactionDigest) -> bool {
+        match self {
+            Self::Digests(txns) => {
+                assert!(txns.remove(tx_digest), "transaction must exist");
+                txns.is_empty()
+            }
+            Self::Count {
+                count,
+                #[cfg(debug_assertions)]
+                digests,
+            } => {
+                #[cfg(debug_assertions)]
+                assert!(digests.remove(tx_digest), "transaction must exist");
+                *count = count.checked_sub(1).expect("transaction must exist");
+                #[cfg(debug_assertions)]
+                assert_eq!(*count, digests.len());
+                *count == 0
+            }
+        }
+    }
+}
+
 impl UncommittedData {
     fn new() -> Self {
         Self {
@@ -279,6 +358,11 @@ struct CachedCommittedData {
     // See module level comment for an explanation of caching strategy.
     marker_cache: MokaCache<MarkerKey, Arc<Mutex<CachedVersionMap<MarkerValue>>>>,
 
+    // Incremented whenever committed markers are moved into `marker_cache`. A marker read from
+    // the db is only cached if no markers were committed since the read, as the marker read may
+    // otherwise have been superseded by a committed marker that was since evicted.
+    marker_ticket: AtomicU64,
+
     transactions: MokaCache<TransactionDigest, Arc<VerifiedTransaction>>,
 
     transaction_effects: MokaCache<TransactionEffectsDigest, Arc<TransactionEffects>>,
@@ -331,6 +415,7 @@ impl CachedCommittedData {
             object_cache,
             object_by_id_cache,
             marker_cache,
+            marker_ticket: AtomicU64::new(0),
             transactions,
             transaction_effects,
             transaction_events,
@@ -358,6 +443,12 @@ impl CachedCommittedData {
         assert_empty(&self.executed_effects_digests);
         assert_empty(&self._transaction_objects);
     }
+
+    /// Returns the ticket to pass to `cache_latest_marker` for a marker about to be read from the
+    /// db.
+    fn marker_ticket(&self) -> u64 {
+        self.marker_ticket.load(Ordering::SeqCst)
+    }
 }
 
 fn assert_empty<K, V>(cache: &MokaCache<K, V>)
@@ -370,6 +461,22 @@ where
     }
 }
 
+/// A snapshot of how full the execution caches are. Counts of the committed data caches are
+/// approximate, as evictions and insertions may not be fully applied yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of objects with committed versions in the object cache.
+    pub object_cache_len: u64,
+    /// Number of packages in the package cache.
+    pub package_cache_len: u64,
+    /// Number of committed markers in the marker cache.
+    pub marker_cache_len: u64,
+    /// Number of objects with versions that are not yet written to the db.
+    pub dirty_objects: usize,
+    /// Number of executed transactions whose outputs are not yet written to the db.
+    pub pending_txns: usize,
+}
+
 pub struct WritebackCache {
     dirty: UncommittedData,
     cached: CachedCommittedData,
@@ -391,6 +498,19 @@ pub struct WritebackCache {
     executed_effects_digests_notify_read: NotifyRead<TransactionDigest, TransactionEffectsDigest>,
     store: Arc<AuthorityStore>,
     metrics: Arc<ExecutionCacheMetrics>,
+
+    // If set, committed live objects in `object_cache` that are not among the this many newest
+    // cached versions are stored as an object reference only, and re-read from the store when
+    // requested. This saves memory for hot objects that have many cached versions.
+    object_compression_age: Option<usize>,
+
+    // If set, uncommitted TransactionEvents shared by more than this many transactions track
+    // their referers with a count instead of an explicit set of transaction digests.
+    event_referers_count_threshold: Option<usize>,
+
+    // If set, a broken cache invariant when committing (such as a committed version missing from
+    // the dirty set) panics. Otherwise, it is logged and the offending entry is skipped.
+    strict_invariants: bool,
 }
 
 macro_rules! check_cache_entry_by_version {
@@ -444,9 +564,35 @@ impl WritebackCache {
             executed_effects_digests_notify_read: NotifyRead::new(),
             store,
             metrics,
+            object_compression_age: None,
+            event_referers_count_threshold: None,
+            strict_invariants: cfg!(debug_assertions),
         }
     }
 
+    /// Keep only the object reference for committed objects which are older than the `age`
+    /// newest cached versions of that object. `age` must be at least 1, so that the latest
+    /// cached version of an object is always stored in full.
+    pub fn with_object_compression_age(mut self, age: usize) -> Self {
+        assert!(age >= 1, "object compression age must be at least 1");
+        self.object_compression_age = Some(age);
+        self
+    }
+
+    /// Track the referers of uncommitted TransactionEvents with a plain count, rather than a set
+    /// of transaction digests, once more than `threshold` transactions share the same events.
+    pub fn with_event_referers_count_threshold(mut self, threshold: usize) -> Self {
+        self.event_referers_count_threshold = Some(threshold);
+        self
+    }
+
+    /// Whether a broken cache invariant when committing panics (the default in debug builds), or
+    /// is only logged so that the node can keep running.
+    pub fn with_strict_invariants(mut self, strict: bool) -> Self {
+        self.strict_invariants = strict;
+        self
+    }
+
     pub fn new_for_tests(store: Arc<AuthorityStore>, registry: &Registry) -> Self {
         Self::new(store, ExecutionCacheMetrics::new(registry).into())
     }
@@ -454,6 +600,9 @@ impl WritebackCache {
     #[cfg(test)]
     pub fn reset_for_test(&mut self) {
         let mut new = Self::new(self.store.clone(), self.metrics.clone());
+        new.object_compression_age = self.object_compression_age;
+        new.event_referers_count_threshold = self.event_referers_count_threshold;
+        new.strict_invariants = self.strict_invariants;
         std::mem::swap(self, &mut new);
     }
 
@@ -564,6 +713,8 @@ impl WritebackCache {
             CacheResult::Hit(entry) => match entry {
                 ObjectEntry::Object(object) => CacheResult::Hit(object),
                 ObjectEntry::Deleted | ObjectEntry::Wrapped => CacheResult::NegativeHit,
+                // The version is known to exist, but the full object must be read from the db.
+                ObjectEntry::Compressed(_) => CacheResult::Miss,
             },
             CacheResult::Miss => CacheResult::Miss,
             CacheResult::NegativeHit => CacheResult::NegativeHit,
@@ -659,6 +810,9 @@ impl WritebackCache {
             CacheResult::Hit((version, entry)) => match entry {
                 ObjectEntry::Object(object) => CacheResult::Hit((version, object)),
                 ObjectEntry::Deleted | ObjectEntry::Wrapped => CacheResult::NegativeHit,
+                ObjectEntry::Compressed(_) => {
+                    unreachable!("latest cached version is never compressed")
+                }
             },
             CacheResult::NegativeHit => CacheResult::NegativeHit,
             CacheResult::Miss => CacheResult::Miss,
@@ -821,12 +975,13 @@ impl WritebackCache {
         self.metrics.record_cache_write("transaction_events");
         match self.dirty.transaction_events.entry(events.digest()) {
             DashMapEntry::Occupied(mut occupied) => {
-                occupied.get_mut().0.insert(tx_digest);
+                occupied
+                    .get_mut()
+                    .0
+                    .insert(tx_digest, self.event_referers_count_threshold);
             }
             DashMapEntry::Vacant(entry) => {
-                let mut txns = BTreeSet::new();
-                txns.insert(tx_digest);
-                entry.insert((txns, events.clone()));
+                entry.insert((EventReferers::new(tx_digest), events.clone()));
             }
         }
 
@@ -943,9 +1098,7 @@ impl WritebackCache {
 
         match self.dirty.transaction_events.entry(events.digest()) {
             DashMapEntry::Occupied(mut occupied) => {
-                let txns = &mut occupied.get_mut().0;
-                assert!(txns.remove(&tx_digest), "transaction must exist");
-                if txns.is_empty() {
+                if occupied.get_mut().0.remove(&tx_digest) {
                     occupied.remove();
                 }
             }
@@ -959,9 +1112,14 @@ impl WritebackCache {
             .remove(&tx_digest)
             .expect("executed effects must exist");
 
-        // Move dirty markers to cache
+        // Move dirty markers to cache. The ticket is bumped before the dirty markers are removed,
+        // so that any marker read from the db before this commit is not cached afterwards.
+        if !markers.is_empty() {
+            self.cached.marker_ticket.fetch_add(1, Ordering::SeqCst);
+        }
         for (object_key, marker_value) in markers.iter() {
             Self::move_version_from_dirty_to_cache(
+                self.strict_invariants,
                 &self.dirty.markers,
                 &self.cached.marker_cache,
                 (epoch, object_key.0),
@@ -971,33 +1129,64 @@ impl WritebackCache {
         }
 
         for (object_id, object) in written.iter() {
-            Self::move_version_from_dirty_to_cache(
+            if !Self::move_version_from_dirty_to_cache(
+                self.strict_invariants,
                 &self.dirty.objects,
                 &self.cached.object_cache,
                 *object_id,
                 object.version(),
                 &ObjectEntry::Object(object.clone()),
-            );
+            ) {
+                self.cached.object_by_id_cache.invalidate(object_id);
+            }
+            self.compress_cached_object_versions(object_id);
         }
 
         for ObjectKey(object_id, version) in deleted.iter() {
-            Self::move_version_from_dirty_to_cache(
+            if !Self::move_version_from_dirty_to_cache(
+                self.strict_invariants,
                 &self.dirty.objects,
                 &self.cached.object_cache,
                 *object_id,
                 *version,
                 &ObjectEntry::Deleted,
-            );
+            ) {
+                self.cached.object_by_id_cache.invalidate(object_id);
+            }
+            self.compress_cached_object_versions(object_id);
         }
 
         for ObjectKey(object_id, version) in wrapped.iter() {
-            Self::move_version_from_dirty_to_cache(
+            if !Self::move_version_from_dirty_to_cache(
+                self.strict_invariants,
                 &self.dirty.objects,
                 &self.cached.object_cache,
                 *object_id,
                 *version,
                 &ObjectEntry::Wrapped,
-            );
+            ) {
+                self.cached.object_by_id_cache.invalidate(object_id);
+            }
+            self.compress_cached_object_versions(object_id);
+        }
+    }
+
+    // Replace all but the newest `object_compression_age` committed versions of a live object
+    // with their object reference. The versions themselves are kept, so the cached sequence of
+    // versions still has no gaps.
+    fn compress_cached_object_versions(&self, object_id: &ObjectID) {
+        let Some(age) = self.object_compression_age else {
+            return;
+        };
+        let Some(cache_entry) = self.cached.object_cache.get(object_id) else {
+            return;
+        };
+        let mut cache_map = cache_entry.lock();
+        for (_, entry) in cache_map.iter_mut_older_than_newest(age) {
+            if let ObjectEntry::Object(object) = entry {
+                let obj_ref = object.compute_object_reference();
+                *entry = ObjectEntry::Compressed(obj_ref);
+            }
         }
     }
 
@@ -1030,14 +1219,21 @@ impl WritebackCache {
 
     // Move the oldest/least entry from the dirty queue to the cache queue.
     // This is called after the entry is committed to the db.
+    //
+    // The dirty version must exist. If it does not, this panics when `strict_invariants` is set.
+    // Otherwise the cached versions of the key are invalidated, as they may be older than the
+    // version just committed to the db, and false is returned so that callers can invalidate any
+    // other cache entry derived from the key.
     fn move_version_from_dirty_to_cache<K, V>(
+        strict_invariants: bool,
         dirty: &DashMap<K, CachedVersionMap<V>>,
         cache: &MokaCache<K, Arc<Mutex<CachedVersionMap<V>>>>,
         key: K,
         version: SequenceNumber,
         value: &V,
-    ) where
-        K: Eq + std::hash::Hash + Clone + Send + Sync + Copy + 'static,
+    ) -> bool
+    where
+        K: Eq + std::hash::Hash + Clone + Send + Sync + Copy + std::fmt::Debug + 'static,
         V: Send + Sync + Clone + Eq + std::fmt::Debug + 'static,
     {
         static MAX_VERSIONS: usize = 3;
@@ -1045,26 +1241,54 @@ impl WritebackCache {
         // IMPORTANT: lock both the dirty set entry and the cache entry before modifying either.
         // this ensures that readers cannot see a value temporarily disappear.
         let dirty_entry = dirty.entry(key);
-        let cache_entry = cache.entry(key).or_default();
-        let mut cache_map = cache_entry.value().lock();
-
-        // insert into cache and drop old versions.
-        cache_map.insert(version, value.clone());
-        // TODO: make this automatic by giving CachedVersionMap an optional max capacity
-        cache_map.truncate_to(MAX_VERSIONS);
 
         let DashMapEntry::Occupied(mut occupied_dirty_entry) = dirty_entry else {
-            panic!("dirty map must exist");
+            if strict_invariants {
+                panic!("dirty map must exist");
+            }
+            tracing::error!(
+                ?key,
+                ?version,
+                ?value,
+                "dirty map must exist, invalidating cached versions"
+            );
+            cache.invalidate(&key);
+            return false;
         };
 
-        let removed = occupied_dirty_entry.get_mut().pop_oldest(&version);
+        let cache_entry = cache.entry(key).or_default();
+        let mut cache_map = cache_entry.value().lock();
 
-        assert_eq!(removed.as_ref(), Some(value), "dirty version must exist");
+        let removed = occupied_dirty_entry.get_mut().pop_oldest(&version);
+        let moved = removed.as_ref() == Some(value);
+        if moved {
+            // insert into cache and drop old versions.
+            cache_map.insert(version, value.clone());
+            // TODO: make this automatic by giving CachedVersionMap an optional max capacity
+            cache_map.truncate_to(MAX_VERSIONS);
+        } else {
+            if strict_invariants {
+                panic!(
+                    "dirty version must exist, found {:?} instead of {:?}",
+                    removed, value
+                );
+            }
+            tracing::error!(
+                ?key,
+                ?version,
+                ?value,
+                ?removed,
+                "dirty version must exist, invalidating cached versions"
+            );
+            drop(cache_map);
+            cache.invalidate(&key);
+        }
 
         // if there are no versions remaining, remove the map entry
         if occupied_dirty_entry.get().is_empty() {
             occupied_dirty_entry.remove();
         }
+        moved
     }
 
     // Updates the latest object id cache with an entry that was read from the db.
@@ -1111,6 +1335,68 @@ impl WritebackCache {
         }
     }
 
+    // Populates the marker cache with the latest marker for `key` after it was read from the db.
+    // Since nothing exists above the latest version, caching it alone cannot violate the
+    // no-missing-versions property.
+    //
+    // To avoid racing with writes, nothing is cached if the key has uncommitted markers (they
+    // will be moved into the cache once committed, and the marker we read may already be one of
+    // them), or if it already has a cache entry, which is then at least as recent as our read.
+    // Nor is anything cached if markers were committed since `ticket` was taken, before the
+    // read: a newer marker for the key may have been committed and evicted from the cache since.
+    fn cache_latest_marker(
+        &self,
+        key: MarkerKey,
+        version: SequenceNumber,
+        marker: MarkerValue,
+        ticket: u64,
+    ) {
+        // Lock the dirty entry before the cache entry, same as move_version_from_dirty_to_cache.
+        let dirty_entry = self.dirty.markers.entry(key);
+        if matches!(dirty_entry, DashMapEntry::Occupied(_)) {
+            return;
+        }
+        if self.cached.marker_ticket() != ticket {
+            trace!(
+                ?key,
+                ?version,
+                "markers committed since read, not caching marker"
+            );
+            return;
+        }
+        self.metrics.record_cache_write("marker");
+        self.cached.marker_cache.entry(key).or_insert_with(|| {
+            let mut cache_map = CachedVersionMap::default();
+            cache_map.insert(version, marker);
+            Arc::new(Mutex::new(cache_map))
+        });
+    }
+
+    // Populates the object cache with an immutable object that was read from the db. As with
+    // cache_latest_marker, nothing is cached if the object id has uncommitted versions or an
+    // existing cache entry.
+    fn cache_immutable_object(&self, object: Object) {
+        let object_id = object.id();
+        let version = object.version();
+        let dirty_entry = self.dirty.objects.entry(object_id);
+        if matches!(dirty_entry, DashMapEntry::Occupied(_)) {
+            return;
+        }
+        self.metrics.record_cache_write("object");
+        self.cached
+            .object_cache
+            .entry(object_id)
+            .or_insert_with(|| {
+                let mut cache_map = CachedVersionMap::default();
+                cache_map.insert(version, ObjectEntry::Object(object.clone()));
+                Arc::new(Mutex::new(cache_map))
+            });
+        self.cache_latest_object_by_id(
+            &object_id,
+            LatestObjectCacheEntry::Object(version, ObjectEntry::Object(object)),
+        );
+    }
+
     fn cache_object_not_found(&self, object_id: &ObjectID) {
         self.cache_latest_object_by_id(object_id, LatestObjectCacheEntry::NonExistent);
     }
@@ -1159,6 +1445,81 @@ impl WritebackCache {
         Ok(())
     }
 
+    /// Reports the number of entries in each of the caches, e.g. to decide whether they are
+    /// close to capacity. Does not change the contents of the caches.
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            object_cache_len: self.cached.object_cache.entry_count(),
+            package_cache_len: self.packages.entry_count(),
+            marker_cache_len: self.cached.marker_cache.entry_count(),
+            dirty_objects: self.dirty.objects.len(),
+            pending_txns: self.dirty.pending_transaction_writes.len(),
+        }
+    }
+
+    /// Removes the cached markers of all epochs older than `epoch`. Markers of past epochs are
+    /// never read again once the epoch has ended, but would otherwise linger until they are
+    /// evicted by the cache size limit. Uncommitted markers are left in place, as they have yet to
+    /// be written to the db; there should be none, since this must not be called while
+    /// transactions of older epochs are still pending commit.
+    pub fn evict_markers_before_epoch(&self, epoch: EpochId) {
+        let uncommitted = self
+            .dirty
+            .markers
+            .iter()
+            .filter(|entry| entry.key().0 < epoch && !entry.value().is_empty())
+            .count();
+        if uncommitted > 0 {
+            tracing::error!(
+                "{} objects still have uncommitted markers from epochs before {}",
+                uncommitted,
+                epoch
+            );
+        }
+        let stale_keys: Vec<MarkerKey> = self
+            .cached
+            .marker_cache
+            .iter()
+            .filter(|(key, _)| key.0 < epoch)
+            .map(|(key, _)| *key)
+            .collect();
+        debug!(
+            "evicting {} cached markers from epochs before {}",
+            stale_keys.len(),
+            epoch
+        );
+        for key in stale_keys {
+            self.cached.marker_cache.invalidate(&key);
+        }
+    }
+
+    /// Loads the objects at `keys` from the db into the cache ahead of their use, so that later
+    /// reads are served from memory. Only immutable objects are cached: they have no versions
+    /// above the one read, so caching that version alone cannot leave a gap in the cache. Keys
+    /// whose object id already has a cached or uncommitted entry are skipped.
+    pub fn prefetch_objects(&self, keys: &[ObjectKey]) -> SuiResult {
+        let uncached: Vec<ObjectKey> = keys
+            .iter()
+            .filter(|ObjectKey(id, _)| {
+                !self.dirty.objects.contains_key(id) && !self.cached.object_cache.contains_key(id)
+            })
+            .copied()
+            .collect();
+        if uncached.is_empty() {
+            return Ok(());
+        }
+
+        let objects = self
+            .record_db_multi_get("object_by_version", uncached.len())
+            .multi_get_objects_by_key(&uncached)?;
+        for object in objects.into_iter().flatten() {
+            if object.is_immutable() {
+                self.cache_immutable_object(object);
+            }
+        }
+        Ok(())
+    }
+
     pub fn clear_caches_and_assert_empty(&self) {
         info!("clearing caches");
         self.cached.clear_and_assert_empty();
@@ -1316,6 +1677,7 @@ impl ObjectCacheRead for WritebackCache {
         match self.get_object_entry_by_id_cache_only("latest_objref_or_tombstone", &object_id) {
             CacheResult::Hit((version, entry)) => Ok(Some(match entry {
                 ObjectEntry::Object(object) => object.compute_object_reference(),
+                ObjectEntry::Compressed(obj_ref) => obj_ref,
                 ObjectEntry::Deleted => (object_id, version, ObjectDigest::OBJECT_DIGEST_DELETED),
                 ObjectEntry::Wrapped => (object_id, version, ObjectDigest::OBJECT_DIGEST_WRAPPED),
             })),
@@ -1351,6 +1713,9 @@ impl ObjectCacheRead for WritebackCache {
                             ObjectDigest::OBJECT_DIGEST_WRAPPED,
                         )),
                     ),
+                    ObjectEntry::Compressed(_) => {
+                        unreachable!("latest cached version is never compressed")
+                    }
                 }))
             }
             CacheResult::NegativeHit => Ok(None),
@@ -1371,7 +1736,7 @@ impl ObjectCacheRead for WritebackCache {
                 self.metrics
                     .record_cache_request("object_lt_or_eq_version", $level);
                 if let Some(objects) = $objects {
-                    if let Some((_, object)) = objects
+                    if let Some((version, object)) = objects
                         .all_versions_lt_or_eq_descending(&version_bound)
                         .next()
                     {
@@ -1379,6 +1744,14 @@ impl ObjectCacheRead for WritebackCache {
                             self.metrics
                                 .record_cache_hit("object_lt_or_eq_version", $level);
                             return Ok(Some(object.clone()));
+                        } else if let ObjectEntry::Compressed(_) = object {
+                            // the cache tells us which version to return, but the object itself
+                            // must be read from the db
+                            self.metrics
+                                .record_cache_hit("object_lt_or_eq_version", $level);
+                            return Ok(self
+                                .record_db_get("object_lt_or_eq_version_compressed")
+                                .get_object_by_key(&object_id, *version)?);
                         } else {
                             // if we find a tombstone, the object does not exist
                             self.metrics
@@ -1479,6 +1852,9 @@ impl ObjectCacheRead for WritebackCache {
                         match obj_entry {
                             ObjectEntry::Object(object) => Ok(Some(object)),
                             ObjectEntry::Deleted | ObjectEntry::Wrapped => Ok(None),
+                            ObjectEntry::Compressed(_) => {
+                                unreachable!("compressed entries are never dirty or read from db")
+                            }
                         }
                     } else {
                         // The latest object exceeded the bound, so now we have to do a scan
@@ -1530,12 +1906,61 @@ impl ObjectCacheRead for WritebackCache {
             CacheResult::NegativeHit => {
                 panic!("cannot have negative hit when getting latest marker")
             }
-            CacheResult::Miss => self
-                .record_db_get("marker_latest")
-                .get_latest_marker(object_id, epoch_id),
+            CacheResult::Miss => {
+                let ticket = self.cached.marker_ticket();
+                let marker = self
+                    .record_db_get("marker_latest")
+                    .get_latest_marker(object_id, epoch_id)?;
+                if let Some((version, marker_value)) = &marker {
+                    self.cache_latest_marker(
+                        (epoch_id, *object_id),
+                        *version,
+                        *marker_value,
+                        ticket,
+                    );
+                }
+                Ok(marker)
+            }
         }
     }
 
+    fn multi_get_latest_markers(
+        &self,
+        keys: &[(ObjectID, EpochId)],
+    ) -> SuiResult<Vec<Option<(SequenceNumber, MarkerValue)>>> {
+        do_fallback_lookup(
+            keys,
+            |(object_id, epoch_id)| {
+                Ok(
+                    match self.get_latest_marker_value_cache_only(object_id, *epoch_id) {
+                        CacheResult::Hit(marker) => CacheResult::Hit(Some(marker)),
+                        CacheResult::NegativeHit => {
+                            panic!("cannot have negative hit when getting latest marker")
+                        }
+                        CacheResult::Miss => CacheResult::Miss,
+                    },
+                )
+            },
+            |remaining| {
+                let ticket = self.cached.marker_ticket();
+                let markers = self
+                    .record_db_multi_get("marker_latest", remaining.len())
+                    .multi_get_latest_markers(remaining)?;
+                for ((object_id, epoch_id), marker) in remaining.iter().zip(markers.iter()) {
+                    if let Some((version, marker_value)) = marker {
+                        self.cache_latest_marker(
+                            (*epoch_id, *object_id),
+                            *version,
+                            *marker_value,
+                            ticket,
+                        );
+                    }
+                }
+                Ok(markers)
+            },
+        )
+    }
+
     fn get_lock(&self, obj_ref: ObjectRef, epoch_store: &AuthorityPerEpochStore) -> SuiLockResult {
         let cur_epoch = epoch_store.epoch();
         match self.get_object_by_id_cache_only("lock", &obj_ref.0) {
@@ -1846,6 +2271,17 @@ fn do_fallback_lookup<K: Copy, V: Default + Clone>(
     let mut results = vec![V::default(); keys.len()];
     let mut fallback_keys = Vec::with_capacity(keys.len());
     let mut fallback_indices = Vec::with_capacity(keys.len());
+    // Tracks which result slots have been resolved, to check that each slot is resolved exactly
+    // once, by either the cache or the fallback.
+    #[cfg(debug_assertions)]
+    let mut resolved = vec![false; keys.len()];
+    #[cfg(debug_assertions)]
+    let mut resolve = |i: usize| {
+        assert!(!resolved[i], "result slot {i} resolved more than once");
+        resolved[i] = true;
+    };
+    #[cfg(not(debug_assertions))]
+    let mut resolve = |_: usize| {};
 
     for (i, key) in keys.iter().enumerate() {
         match get_cached_key(key)? {
@@ -1853,8 +2289,9 @@ fn do_fallback_lookup<K: Copy, V: Default + Clone>(
                 fallback_keys.push(*key);
                 fallback_indices.push(i);
             }
-            CacheResult::NegativeHit => (),
+            CacheResult::NegativeHit => resolve(i),
             CacheResult::Hit(value) => {
+                resolve(i);
                 results[i] = value;
             }
         }
@@ -1868,8 +2305,14 @@ fn do_fallback_lookup<K: Copy, V: Default + Clone>(
         .into_iter()
         .zip(fallback_results.into_iter())
     {
+        resolve(i);
         results[i] = result;
     }
+    #[cfg(debug_assertions)]
+    assert!(
+        resolved.iter().all(|resolved| *resolved),
+        "not every result slot was resolved"
+    );
     Ok(results)
 }
 
@@ -1893,6 +2336,10 @@ impl AccumulatorStore for WritebackCache {
                         assert_eq!(object.version(), version);
                         Some(object.compute_object_reference())
                     }
+                    ObjectEntry::Compressed(obj_ref) => {
+                        assert_eq!(obj_ref.1, version);
+                        Some(*obj_ref)
+                    }
                     ObjectEntry::Deleted => {
                         Some((*object_id, version, ObjectDigest::OBJECT_DIGEST_DELETED))
                     }
@@ -1999,6 +2446,9 @@ impl AccumulatorStore for WritebackCache {
                 (_, ObjectEntry::Deleted) => {
                     dirty_objects.remove(&id);
                 }
+                (_, ObjectEntry::Compressed(_)) => {
+                    unreachable!("compressed entries are never dirty")
+                }
             }
         }
 
@@ -14,8 +14,10 @@ pub struct TrafficControllerMetrics {
     pub requests_blocked_at_protocol: IntCounter,
     pub blocks_delegated_to_firewall: IntCounter,
     pub firewall_delegation_request_fail: IntCounter,
+    pub firewall_delegation_retries_exhausted: IntCounter,
     pub tally_channel_overflow: IntCounter,
     pub num_dry_run_blocked_requests: IntCounter,
+    pub num_dry_run_would_block_clients: IntCounter,
     pub tally_handled: IntCounter,
     pub error_tally_handled: IntCounter,
 }
@@ -61,6 +63,12 @@ impl TrafficControllerMetrics {
                 registry
             )
             .unwrap(),
+            firewall_delegation_retries_exhausted: register_int_counter_with_registry!(
+                "firewall_delegation_retries_exhausted",
+                "Number of blocklist delegation requests to firewall that failed after all retries",
+                registry
+            )
+            .unwrap(),
             tally_channel_overflow: register_int_counter_with_registry!(
                 "tally_channel_overflow",
                 "Traffic controller tally channel overflow count",
@@ -73,6 +81,13 @@ impl TrafficControllerMetrics {
                 registry
             )
             .unwrap(),
+            num_dry_run_would_block_clients: register_int_counter_with_registry!(
+                "traffic_control_num_dry_run_would_block_clients",
+                "Number of times a client would have been added to a blocklist in traffic \
+                    controller dry run mode",
+                registry
+            )
+            .unwrap(),
             tally_handled: register_int_counter_with_registry!(
                 "traffic_control_tally_handled",
                 "Number of tallies handled",
@@ -8,10 +8,12 @@ pub mod policies;
 
 use dashmap::DashMap;
 use fs::File;
+use ipnetwork::IpNetwork;
 use prometheus::IntGauge;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr};
 use std::ops::Add;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use self::metrics::TrafficControllerMetrics;
@@ -30,10 +32,14 @@ use tracing::{debug, error, info, warn};
 
 type Blocklist = Arc<DashMap<IpAddr, SystemTime>>;
 
+/// Source of the current time, used to set and check blocklist expirations.
+pub type Clock = Arc<dyn Fn() -> SystemTime + Send + Sync>;
+
 #[derive(Clone)]
 struct Blocklists {
     clients: Blocklist,
     proxied_clients: Blocklist,
+    clock: Clock,
 }
 
 #[derive(Clone)]
@@ -42,6 +48,10 @@ pub struct TrafficController {
     blocklists: Blocklists,
     metrics: Arc<TrafficControllerMetrics>,
     dry_run_mode: bool,
+    // When set, the tally loop keeps draining the tally channel but does not
+    // apply the tallies to the policies, so no new blocks are added.
+    paused: Arc<AtomicBool>,
+    allowlist: Arc<Vec<IpNetwork>>,
 }
 
 impl Debug for TrafficController {
@@ -69,6 +79,17 @@ impl TrafficController {
         policy_config: PolicyConfig,
         metrics: TrafficControllerMetrics,
         fw_config: Option<RemoteFirewallConfig>,
+    ) -> Self {
+        Self::spawn_with_clock(policy_config, metrics, fw_config, Arc::new(SystemTime::now))
+    }
+
+    /// Same as `spawn`, but blocklist expirations are based on the time given by `clock`
+    /// rather than the system time, e.g. to control the passing of time in tests.
+    pub fn spawn_with_clock(
+        policy_config: PolicyConfig,
+        metrics: TrafficControllerMetrics,
+        fw_config: Option<RemoteFirewallConfig>,
+        clock: Clock,
     ) -> Self {
         let metrics = Arc::new(metrics);
         let (tx, rx) = mpsc::channel(policy_config.channel_capacity);
@@ -86,9 +107,12 @@ impl TrafficController {
             blocklists: Blocklists {
                 clients: Arc::new(DashMap::new()),
                 proxied_clients: Arc::new(DashMap::new()),
+                clock,
             },
             metrics: metrics.clone(),
             dry_run_mode: policy_config.dry_run,
+            paused: Arc::new(AtomicBool::new(false)),
+            allowlist: Arc::new(policy_config.allowlisted_ips.clone()),
         };
         let blocklists = ret.blocklists.clone();
         spawn_monitored_task!(run_tally_loop(
@@ -98,6 +122,7 @@ impl TrafficController {
             blocklists,
             metrics,
             mem_drainfile_present,
+            ret.paused.clone(),
         ));
         ret
     }
@@ -178,6 +203,34 @@ impl TrafficController {
         self.dry_run_mode
     }
 
+    /// Stop applying tallies to the policies. Tallies received while paused are
+    /// discarded, so no new blocks are added, but existing blocks are still
+    /// honored by `check` until they expire.
+    pub fn pause(&self) {
+        info!("Pausing TrafficController tally processing");
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// Resume applying tallies to the policies after a call to `pause`.
+    pub fn resume(&self) {
+        info!("Resuming TrafficController tally processing");
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if `ip` is in one of the allowlisted IP ranges of the policy
+    /// config, in which case it is never blocked.
+    pub fn is_allowlisted(&self, ip: &IpAddr) -> bool {
+        is_allowlisted(&self.allowlist, ip)
+    }
+
+    pub fn allowlisted_ips(&self) -> &[IpNetwork] {
+        &self.allowlist
+    }
+
     async fn check_and_clear_blocklist(
         &self,
         client: &Option<IpAddr>,
@@ -185,10 +238,10 @@ impl TrafficController {
         blocklist_len_gauge: &IntGauge,
     ) -> bool {
         let client = match client {
-            Some(client) => client,
-            None => return true,
+            Some(client) if !self.is_allowlisted(client) => client,
+            _ => return true,
         };
-        let now = SystemTime::now();
+        let now = (self.blocklists.clock)();
         // the below two blocks cannot be nested, otherwise we will deadlock
         // due to aquiring the lock on get, then holding across the remove
         let (should_block, should_remove) = {
@@ -206,6 +259,10 @@ impl TrafficController {
     }
 }
 
+fn is_allowlisted(allowlist: &[IpNetwork], ip: &IpAddr) -> bool {
+    allowlist.iter().any(|network| network.contains(*ip))
+}
+
 async fn run_tally_loop(
     mut receiver: mpsc::Receiver<TrafficTally>,
     policy_config: PolicyConfig,
@@ -213,6 +270,7 @@ async fn run_tally_loop(
     blocklists: Blocklists,
     metrics: Arc<TrafficControllerMetrics>,
     mut mem_drainfile_present: bool,
+    paused: Arc<AtomicBool>,
 ) {
     let mut spam_policy = TrafficControlPolicy::from_spam_config(policy_config.clone()).await;
     let mut error_policy = TrafficControlPolicy::from_error_config(policy_config.clone()).await;
@@ -220,7 +278,7 @@ async fn run_tally_loop(
     let error_blocklists = Arc::new(blocklists);
     let node_fw_client = fw_config
         .as_ref()
-        .map(|fw_config| NodeFWClient::new(fw_config.remote_fw_url.clone()));
+        .map(|fw_config| Arc::new(NodeFWClient::new_from_config(fw_config, metrics.clone())));
 
     let timeout = fw_config
         .as_ref()
@@ -232,9 +290,23 @@ async fn run_tally_loop(
             received = receiver.recv() => {
                 metrics.tallies.inc();
                 match received {
-                    Some(tally) => {
+                    Some(_) if paused.load(Ordering::Relaxed) => {
+                        // drain the channel without applying the tally
+                    }
+                    Some(mut tally) => {
+                        // allowlisted clients are never tallied, so the policies never
+                        // block them
+                        tally.direct = tally
+                            .direct
+                            .filter(|ip| !is_allowlisted(&policy_config.allowlisted_ips, ip));
+                        tally.through_fullnode = tally
+                            .through_fullnode
+                            .filter(|ip| !is_allowlisted(&policy_config.allowlisted_ips, ip));
+                        if tally.direct.is_none() && tally.through_fullnode.is_none() {
+                            continue;
+                        }
                         // TODO: spawn a task to handle tallying concurrently
-                        if let Err(err) = handle_spam_tally(
+                        handle_spam_tally(
                             &mut spam_policy,
                             &policy_config,
                             &node_fw_client,
@@ -244,10 +316,8 @@ async fn run_tally_loop(
                             metrics.clone(),
                             mem_drainfile_present,
                         )
-                        .await {
-                            warn!("Error handling spam tally: {}", err);
-                        }
-                        if let Err(err) = handle_error_tally(
+                        .await;
+                        handle_error_tally(
                             &mut error_policy,
                             &policy_config,
                             &node_fw_client,
@@ -257,9 +327,7 @@ async fn run_tally_loop(
                             metrics.clone(),
                             mem_drainfile_present,
                         )
-                        .await {
-                            warn!("Error handling error tally: {}", err);
-                        }
+                        .await;
                     }
                     None => {
                         info!("TrafficController tally channel closed by all senders");
@@ -289,69 +357,69 @@ async fn run_tally_loop(
 async fn handle_error_tally(
     policy: &mut TrafficControlPolicy,
     policy_config: &PolicyConfig,
-    nodefw_client: &Option<NodeFWClient>,
+    nodefw_client: &Option<Arc<NodeFWClient>>,
     fw_config: &Option<RemoteFirewallConfig>,
     tally: TrafficTally,
     blocklists: Arc<Blocklists>,
     metrics: Arc<TrafficControllerMetrics>,
     mem_drainfile_present: bool,
-) -> Result<(), reqwest::Error> {
+) {
     if !tally.error_weight.is_sampled().await {
-        return Ok(());
+        return;
     }
     let resp = policy.handle_tally(tally.clone());
     metrics.error_tally_handled.inc();
     if let Some(fw_config) = fw_config {
-        if fw_config.delegate_error_blocking && !mem_drainfile_present {
+        // in dry-run mode the block is only reported by `handle_policy_response`
+        if fw_config.delegate_error_blocking && !mem_drainfile_present && !policy_config.dry_run {
             let client = nodefw_client
                 .as_ref()
                 .expect("Expected NodeFWClient for blocklist delegation");
-            return delegate_policy_response(
+            delegate_policy_response(
                 resp,
                 policy_config,
                 client,
                 fw_config.destination_port,
                 metrics.clone(),
-            )
-            .await;
+            );
+            return;
         }
     }
     handle_policy_response(resp, policy_config, blocklists, metrics).await;
-    Ok(())
 }
 
 async fn handle_spam_tally(
     policy: &mut TrafficControlPolicy,
     policy_config: &PolicyConfig,
-    nodefw_client: &Option<NodeFWClient>,
+    nodefw_client: &Option<Arc<NodeFWClient>>,
     fw_config: &Option<RemoteFirewallConfig>,
     tally: TrafficTally,
     blocklists: Arc<Blocklists>,
     metrics: Arc<TrafficControllerMetrics>,
     mem_drainfile_present: bool,
-) -> Result<(), reqwest::Error> {
+) {
     if !policy_config.spam_sample_rate.is_sampled().await {
-        return Ok(());
+        return;
     }
     let resp = policy.handle_tally(tally.clone());
     metrics.tally_handled.inc();
     if let Some(fw_config) = fw_config {
-        if fw_config.delegate_spam_blocking && !mem_drainfile_present {
+        // in dry-run mode the block is only reported by `handle_policy_response`
+        if fw_config.delegate_spam_blocking && !mem_drainfile_present && !policy_config.dry_run {
             let client = nodefw_client
                 .as_ref()
                 .expect("Expected NodeFWClient for blocklist delegation");
-            return delegate_policy_response(
+            delegate_policy_response(
                 resp,
                 policy_config,
                 client,
                 fw_config.destination_port,
                 metrics.clone(),
-            )
-            .await;
+            );
+            return;
         }
     }
     handle_policy_response(resp, policy_config, blocklists, metrics).await;
-    Ok(())
 }
 
 async fn handle_policy_response(
@@ -367,14 +435,28 @@ async fn handle_policy_response(
     let PolicyConfig {
         connection_blocklist_ttl_sec,
         proxy_blocklist_ttl_sec,
+        dry_run,
         ..
     } = policy_config;
+    if *dry_run {
+        // In dry-run mode we only report what would have been blocked, so that
+        // policies can be tuned against live traffic without affecting it.
+        if let Some(client) = block_client {
+            info!("Dry run mode: would block client: {:?}", client);
+            metrics.num_dry_run_would_block_clients.inc();
+        }
+        if let Some(client) = block_proxied_client {
+            info!("Dry run mode: would block proxied client: {:?}", client);
+            metrics.num_dry_run_would_block_clients.inc();
+        }
+        return;
+    }
     if let Some(client) = block_client {
         if blocklists
             .clients
             .insert(
                 client,
-                SystemTime::now() + Duration::from_secs(*connection_blocklist_ttl_sec),
+                (blocklists.clock)() + Duration::from_secs(*connection_blocklist_ttl_sec),
             )
             .is_none()
         {
@@ -388,7 +470,7 @@ async fn handle_policy_response(
             .proxied_clients
             .insert(
                 client,
-                SystemTime::now() + Duration::from_secs(*proxy_blocklist_ttl_sec),
+                (blocklists.clock)() + Duration::from_secs(*proxy_blocklist_ttl_sec),
             )
             .is_none()
         {
@@ -399,13 +481,16 @@ async fn handle_policy_response(
     }
 }
 
-async fn delegate_policy_response(
+/// Requests the remote firewall to block the clients of `response`. The request is sent from
+/// a separate task, as it may be retried for a while, which would otherwise hold up the tally
+/// loop.
+fn delegate_policy_response(
     response: PolicyResponse,
     policy_config: &PolicyConfig,
-    node_fw_client: &NodeFWClient,
+    node_fw_client: &Arc<NodeFWClient>,
     destination_port: u16,
     metrics: Arc<TrafficControllerMetrics>,
-) -> Result<(), reqwest::Error> {
+) {
     let PolicyResponse {
         block_client,
         block_proxied_client,
@@ -433,22 +518,21 @@ async fn delegate_policy_response(
         });
     }
     if addresses.is_empty() {
-        Ok(())
-    } else {
-        metrics
-            .blocks_delegated_to_firewall
-            .inc_by(addresses.len() as u64);
-        match node_fw_client
+        return;
+    }
+    metrics
+        .blocks_delegated_to_firewall
+        .inc_by(addresses.len() as u64);
+    let node_fw_client = node_fw_client.clone();
+    tokio::spawn(async move {
+        if let Err(err) = node_fw_client
             .block_addresses(BlockAddresses { addresses })
             .await
         {
-            Ok(()) => Ok(()),
-            Err(err) => {
-                metrics.firewall_delegation_request_fail.inc();
-                Err(err)
-            }
+            metrics.firewall_delegation_request_fail.inc();
+            warn!("Error delegating blocklist to firewall: {}", err);
         }
-    }
+    });
 }
 
 #[derive(Debug, Clone)]
@@ -690,3 +774,222 @@ impl TrafficSim {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use sui_types::traffic_control::PolicyType;
+
+    // Sends `count` tallies for `client` and waits for the tally loop to receive all of them.
+    async fn tally_and_wait(controller: &TrafficController, client: Option<IpAddr>, count: u64) {
+        let received = controller.metrics.tallies.get() + count;
+        for _ in 0..count {
+            controller.tally(TrafficTally::new(client, None, Weight::zero()));
+        }
+        let mut attempts = 0;
+        while controller.metrics.tallies.get() < received {
+            attempts += 1;
+            assert!(attempts < 100, "tallies were not received");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocklist_expires_with_clock() {
+        let policy_config = PolicyConfig {
+            connection_blocklist_ttl_sec: 5,
+            spam_policy_type: PolicyType::TestNConnIP(3),
+            spam_sample_rate: Weight::one(),
+            ..Default::default()
+        };
+        let now = Arc::new(Mutex::new(SystemTime::now()));
+        let clock_now = now.clone();
+        let controller = TrafficController::spawn_with_clock(
+            policy_config,
+            TrafficControllerMetrics::new_for_tests(),
+            None,
+            Arc::new(move || *clock_now.lock().unwrap()),
+        );
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+
+        tally_and_wait(&controller, client, 10).await;
+        assert!(!controller.check(&client, &None).await);
+
+        // the block holds until its ttl has elapsed
+        *now.lock().unwrap() += Duration::from_secs(4);
+        assert!(!controller.check(&client, &None).await);
+        *now.lock().unwrap() += Duration::from_secs(2);
+        assert!(controller.check(&client, &None).await);
+        assert!(controller.blocklists.clients.is_empty());
+        assert_eq!(controller.metrics.connection_ip_blocklist_len.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_block() {
+        let policy_config = PolicyConfig {
+            connection_blocklist_ttl_sec: 5,
+            spam_policy_type: PolicyType::TestNConnIP(3),
+            spam_sample_rate: Weight::one(),
+            dry_run: true,
+            ..Default::default()
+        };
+        let controller = TrafficController::spawn(
+            policy_config,
+            TrafficControllerMetrics::new_for_tests(),
+            None,
+        );
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+        for _ in 0..10 {
+            controller.tally(TrafficTally::new(client, None, Weight::zero()));
+        }
+
+        // wait for the tally loop to process the flood. The client would have been
+        // blocked on every tally from the third one on
+        let mut attempts = 0;
+        while controller.metrics.num_dry_run_would_block_clients.get() < 8 {
+            attempts += 1;
+            assert!(attempts < 100, "tallies were not handled");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(controller.metrics.tally_handled.get(), 10);
+        assert!(controller.blocklists.clients.is_empty());
+        assert_eq!(controller.metrics.connection_ip_blocklist_len.get(), 0);
+        assert!(controller.check(&client, &None).await);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_delegate() {
+        let policy_config = PolicyConfig {
+            connection_blocklist_ttl_sec: 5,
+            spam_policy_type: PolicyType::TestNConnIP(3),
+            spam_sample_rate: Weight::one(),
+            dry_run: true,
+            ..Default::default()
+        };
+        // no firewall listens there, but it must not be contacted anyway
+        let fw_config = RemoteFirewallConfig {
+            remote_fw_url: "http://127.0.0.1:1".to_string(),
+            destination_port: 8080,
+            delegate_spam_blocking: true,
+            delegate_error_blocking: true,
+            drain_path: std::env::temp_dir().join("test_dry_run_does_not_delegate_drain"),
+            drain_timeout_secs: 300,
+            max_block_attempts: 1,
+            block_retry_base_delay_ms: 10,
+            max_block_duration_ms: 1_000,
+        };
+        let controller = TrafficController::spawn(
+            policy_config,
+            TrafficControllerMetrics::new_for_tests(),
+            Some(fw_config),
+        );
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+        for _ in 0..10 {
+            controller.tally(TrafficTally::new(client, None, Weight::zero()));
+        }
+
+        let mut attempts = 0;
+        while controller.metrics.num_dry_run_would_block_clients.get() < 8 {
+            attempts += 1;
+            assert!(attempts < 100, "tallies were not handled");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(controller.metrics.blocks_delegated_to_firewall.get(), 0);
+        assert_eq!(controller.metrics.firewall_delegation_request_fail.get(), 0);
+        assert!(controller.check(&client, &None).await);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let policy_config = PolicyConfig {
+            connection_blocklist_ttl_sec: 60,
+            spam_policy_type: PolicyType::TestNConnIP(3),
+            spam_sample_rate: Weight::one(),
+            ..Default::default()
+        };
+        let controller = TrafficController::spawn(
+            policy_config,
+            TrafficControllerMetrics::new_for_tests(),
+            None,
+        );
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+
+        // while paused, the flood is drained from the channel but not handled
+        controller.pause();
+        assert!(controller.is_paused());
+        for _ in 0..10 {
+            controller.tally(TrafficTally::new(client, None, Weight::zero()));
+        }
+        let mut attempts = 0;
+        while controller.metrics.tallies.get() < 10 {
+            attempts += 1;
+            assert!(attempts < 100, "tallies were not drained");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(controller.metrics.tally_handled.get(), 0);
+        assert!(controller.blocklists.clients.is_empty());
+        assert!(controller.check(&client, &None).await);
+
+        // once resumed, the same flood blocks the client
+        controller.resume();
+        assert!(!controller.is_paused());
+        for _ in 0..10 {
+            controller.tally(TrafficTally::new(client, None, Weight::zero()));
+        }
+        let mut attempts = 0;
+        while controller.blocklists.clients.is_empty() {
+            attempts += 1;
+            assert!(attempts < 100, "client was not blocked");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!controller.check(&client, &None).await);
+
+        // pausing again does not lift the existing block
+        controller.pause();
+        assert!(!controller.check(&client, &None).await);
+    }
+    #[tokio::test]
+    async fn test_allowlisted_clients_are_not_blocked() {
+        let policy_config = PolicyConfig {
+            connection_blocklist_ttl_sec: 60,
+            spam_policy_type: PolicyType::TestNConnIP(3),
+            spam_sample_rate: Weight::one(),
+            allowlisted_ips: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Default::default()
+        };
+        let controller = TrafficController::spawn(
+            policy_config,
+            TrafficControllerMetrics::new_for_tests(),
+            None,
+        );
+        let allowlisted = Some(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)));
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+        assert!(controller.is_allowlisted(&allowlisted.unwrap()));
+        assert!(!controller.is_allowlisted(&client.unwrap()));
+
+        // tallies of the allowlisted client, whether direct or through a fullnode,
+        // are dropped before reaching the policies
+        for _ in 0..10 {
+            controller.tally(TrafficTally::new(allowlisted, allowlisted, Weight::zero()));
+        }
+        tally_and_wait(&controller, client, 10).await;
+        let mut attempts = 0;
+        while controller.blocklists.clients.is_empty() {
+            attempts += 1;
+            assert!(attempts < 100, "client was not blocked");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(controller.metrics.tally_handled.get(), 10);
+        assert!(!controller
+            .blocklists
+            .clients
+            .contains_key(&allowlisted.unwrap()));
+        assert!(controller.blocklists.proxied_clients.is_empty());
+        assert!(controller.check(&allowlisted, &allowlisted).await);
+        assert!(!controller.check(&client, &None).await);
+    }
+}
@@ -2,7 +2,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::traffic_controller::metrics::TrafficControllerMetrics;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_types::traffic_control::RemoteFirewallConfig;
+use tokio::time::Instant;
+use tracing::warn;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockAddresses {
@@ -19,6 +25,10 @@ pub struct BlockAddress {
 pub struct NodeFWClient {
     client: reqwest::Client,
     remote_fw_url: String,
+    max_block_attempts: u32,
+    block_retry_base_delay: Duration,
+    max_block_duration: Option<Duration>,
+    metrics: Option<Arc<TrafficControllerMetrics>>,
 }
 
 impl NodeFWClient {
@@ -26,20 +36,77 @@ impl NodeFWClient {
         Self {
             client: reqwest::Client::new(),
             remote_fw_url,
+            max_block_attempts: 1,
+            block_retry_base_delay: Duration::ZERO,
+            max_block_duration: None,
+            metrics: None,
         }
     }
 
+    pub fn new_from_config(
+        fw_config: &RemoteFirewallConfig,
+        metrics: Arc<TrafficControllerMetrics>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            remote_fw_url: fw_config.remote_fw_url.clone(),
+            max_block_attempts: fw_config.max_block_attempts.max(1),
+            block_retry_base_delay: Duration::from_millis(fw_config.block_retry_base_delay_ms),
+            max_block_duration: Some(Duration::from_millis(fw_config.max_block_duration_ms)),
+            metrics: Some(metrics),
+        }
+    }
+
+    /// Requests the remote firewall to block the given addresses. Transient
+    /// failures (connection errors, timeouts and server errors) are retried
+    /// with exponential backoff, up to `max_block_attempts` attempts and
+    /// within `max_block_duration` overall, if set.
     pub async fn block_addresses(&self, addresses: BlockAddresses) -> Result<(), reqwest::Error> {
-        let response = self
+        let deadline = self
+            .max_block_duration
+            .map(|duration| Instant::now() + duration);
+        let mut delay = self.block_retry_base_delay;
+        let mut attempt = 1;
+        loop {
+            let err = match self.try_block_addresses(&addresses, deadline).await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            if !is_transient(&err) {
+                return Err(err);
+            }
+            let past_deadline =
+                deadline.map_or(false, |deadline| Instant::now() + delay >= deadline);
+            if attempt >= self.max_block_attempts || past_deadline {
+                if let Some(metrics) = &self.metrics {
+                    metrics.firewall_delegation_retries_exhausted.inc();
+                }
+                return Err(err);
+            }
+            warn!(
+                "Blocklist delegation attempt {} failed, retrying in {:?}: {}",
+                attempt, delay, err
+            );
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+
+    async fn try_block_addresses(
+        &self,
+        addresses: &BlockAddresses,
+        deadline: Option<Instant>,
+    ) -> Result<(), reqwest::Error> {
+        let mut request = self
             .client
             .post(&format!("{}/block_addresses", self.remote_fw_url))
-            .json(&addresses)
-            .send()
-            .await?;
-        match response.error_for_status() {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e),
+            .json(addresses);
+        if let Some(deadline) = deadline {
+            request = request.timeout(deadline.saturating_duration_since(Instant::now()));
         }
+        request.send().await?.error_for_status()?;
+        Ok(())
     }
 
     pub async fn list_addresses(&self) -> Result<BlockAddresses, reqwest::Error> {
@@ -52,3 +119,65 @@ impl NodeFWClient {
             .await
     }
 }
+
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_connect()
+        || err.is_timeout()
+        || err
+            .status()
+            .map_or(false, |status| status.is_server_error())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traffic_controller::nodefw_test_server::NodeFwTestServer;
+
+    #[tokio::test]
+    async fn test_block_addresses_retries_transient_failures() {
+        let mut server = NodeFwTestServer::new();
+        let addr = server.start(0).await;
+
+        let fw_config = RemoteFirewallConfig {
+            remote_fw_url: format!("http://{}", addr),
+            destination_port: 8080,
+            delegate_spam_blocking: true,
+            delegate_error_blocking: false,
+            drain_path: std::env::temp_dir().join("drain"),
+            drain_timeout_secs: 10,
+            max_block_attempts: 3,
+            block_retry_base_delay_ms: 10,
+            max_block_duration_ms: 5_000,
+        };
+        let metrics = Arc::new(TrafficControllerMetrics::new_for_tests());
+        let client = NodeFWClient::new_from_config(&fw_config, metrics.clone());
+        let address = BlockAddress {
+            source_address: "127.0.0.2".to_string(),
+            destination_port: 8080,
+            ttl: 60,
+        };
+
+        // The first request fails, the retry should go through.
+        server.fail_next_block_requests(1);
+        client
+            .block_addresses(BlockAddresses {
+                addresses: vec![address.clone()],
+            })
+            .await
+            .expect("Expected request to succeed after a retry");
+        assert_eq!(server.list_addresses_rpc().await, vec![address.clone()]);
+        assert_eq!(metrics.firewall_delegation_retries_exhausted.get(), 0);
+
+        // All attempts fail, the request should give up.
+        server.fail_next_block_requests(3);
+        assert!(client
+            .block_addresses(BlockAddresses {
+                addresses: vec![address],
+            })
+            .await
+            .is_err());
+        assert_eq!(metrics.firewall_delegation_retries_exhausted.get(), 1);
+
+        server.stop().await;
+    }
+}
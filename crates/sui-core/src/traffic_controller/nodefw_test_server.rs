@@ -9,6 +9,7 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, SystemTime};
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 use tokio::sync::{Mutex, Notify};
@@ -18,6 +19,9 @@ use tokio::task::JoinHandle;
 struct AppState {
     /// BlockAddress -> expiry time
     blocklist: Arc<Mutex<HashMap<BlockAddress, SystemTime>>>,
+    /// Number of upcoming block requests to fail, to simulate
+    /// transient firewall errors
+    failing_block_requests: Arc<AtomicUsize>,
 }
 
 pub struct NodeFwTestServer {
@@ -33,11 +37,14 @@ impl NodeFwTestServer {
             shutdown_signal: Arc::new(Notify::new()),
             state: AppState {
                 blocklist: Arc::new(Mutex::new(HashMap::new())),
+                failing_block_requests: Arc::new(AtomicUsize::new(0)),
             },
         }
     }
 
-    pub async fn start(&mut self, port: u16) {
+    /// Starts serving on the given port, or on any free port if it is 0,
+    /// and returns the address the server listens on
+    pub async fn start(&mut self, port: u16) -> SocketAddr {
         let app_state = self.state.clone();
         let app = Router::new()
             .route("/list_addresses", get(Self::list_addresses))
@@ -46,11 +53,11 @@ impl NodeFwTestServer {
 
         let shutdown_signal = self.shutdown_signal.clone();
         let addr = SocketAddr::from(([127, 0, 0, 1], port));
-        let server = axum::Server::bind(&addr)
-            .serve(app.into_make_service())
-            .with_graceful_shutdown(async move {
-                shutdown_signal.notified().await;
-            });
+        let server = axum::Server::bind(&addr).serve(app.into_make_service());
+        let local_addr = server.local_addr();
+        let server = server.with_graceful_shutdown(async move {
+            shutdown_signal.notified().await;
+        });
 
         let handle = tokio::spawn(async move {
             if let Err(e) = server.await {
@@ -63,6 +70,7 @@ impl NodeFwTestServer {
         ));
 
         self.server_handle = Some(handle);
+        local_addr
     }
 
     /// Direct access api for test verification
@@ -71,6 +79,13 @@ impl NodeFwTestServer {
         blocklist.keys().cloned().collect()
     }
 
+    /// Makes the next `count` block requests fail with a server error
+    pub fn fail_next_block_requests(&self, count: usize) {
+        self.state
+            .failing_block_requests
+            .store(count, Ordering::SeqCst);
+    }
+
     /// Endpoint handler to list addresses
     async fn list_addresses(State(state): State<AppState>) -> impl IntoResponse {
         let blocklist = state.blocklist.lock().await;
@@ -96,6 +111,15 @@ impl NodeFwTestServer {
         State(state): State<AppState>,
         Json(addresses): Json<BlockAddresses>,
     ) -> impl IntoResponse {
+        if state
+            .failing_block_requests
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok()
+        {
+            return (StatusCode::SERVICE_UNAVAILABLE, "unavailable");
+        }
         let mut blocklist = state.blocklist.lock().await;
         for addr in addresses.addresses.iter() {
             blocklist.insert(
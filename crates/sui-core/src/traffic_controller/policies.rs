@@ -16,15 +16,16 @@ use sui_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType,
 use tracing::info;
 
 /// The type of request client.
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
 enum ClientType {
     Direct,
     ThroughFullnode,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
 struct SketchKey(IpAddr, ClientType);
 
+#[derive(Clone)]
 pub struct TrafficSketch {
     /// Circular buffer Count Min Sketches representing a sliding window
     /// of traffic data. Note that the 32 in CountMinSketch32 represents
@@ -205,6 +206,25 @@ impl TrafficControlPolicy {
     pub async fn from_error_config(policy_config: PolicyConfig) -> Self {
         Self::from_config(policy_config.clone().error_policy_type, policy_config).await
     }
+    /// Returns the response this policy would give if `tally` were handled now, without
+    /// updating the state of the policy. This clones the full policy state (e.g. all traffic
+    /// sketches of a `FreqThreshold` policy) on every call, so it is meant for tests and
+    /// operator tooling rather than for high frequency use.
+    pub fn simulate(&self, tally: &TrafficTally) -> PolicyResponse {
+        match self {
+            TrafficControlPolicy::NoOp(policy) => policy.clone().handle_tally(tally.clone()),
+            TrafficControlPolicy::FreqThreshold(policy) => {
+                policy.clone().handle_tally(tally.clone())
+            }
+            TrafficControlPolicy::TestNConnIP(policy) => {
+                policy.deep_clone().handle_tally(tally.clone())
+            }
+            TrafficControlPolicy::TestPanicOnInvocation(policy) => {
+                policy.clone().handle_tally(tally.clone())
+            }
+        }
+    }
+
     pub async fn from_config(policy_type: PolicyType, policy_config: PolicyConfig) -> Self {
         match policy_type {
             PolicyType::NoOp => Self::NoOp(NoOpPolicy::new(policy_config)),
@@ -223,6 +243,7 @@ impl TrafficControlPolicy {
 
 ////////////// *** Policy definitions *** //////////////
 
+#[derive(Clone)]
 pub struct FreqThresholdPolicy {
     config: PolicyConfig,
     sketch: TrafficSketch,
@@ -335,6 +356,15 @@ impl TestNConnIPPolicy {
         }
     }
 
+    // Unlike `clone`, does not share the frequency table with `self`.
+    fn deep_clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            frequencies: Arc::new(RwLock::new(self.frequencies.read().clone())),
+            threshold: self.threshold,
+        }
+    }
+
     fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
         let client = if let Some(client) = tally.direct {
             client
@@ -502,6 +532,58 @@ mod tests {
         }
     }
 
+    #[sim_test]
+    async fn test_simulate_does_not_update_policy_state() {
+        let mut policy = TrafficControlPolicy::FreqThreshold(FreqThresholdPolicy::new(
+            PolicyConfig::default(),
+            FreqThresholdConfig {
+                client_threshold: 3,
+                proxied_client_threshold: 3,
+                window_size_secs: 2,
+                update_interval_secs: 1,
+                ..Default::default()
+            },
+        ));
+        let alice = TrafficTally {
+            direct: Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5))),
+            through_fullnode: None,
+            error_weight: Weight::zero(),
+            timestamp: SystemTime::now(),
+        };
+
+        // 5 tallies in a 2 second window is below the threshold of 3 per second
+        for _ in 0..5 {
+            let response = policy.handle_tally(alice.clone());
+            assert_eq!(response.block_client, None);
+        }
+        // any number of simulations do not count towards the threshold
+        for _ in 0..10 {
+            let response = policy.simulate(&alice);
+            assert_eq!(response.block_client, alice.direct);
+        }
+        let response = policy.handle_tally(alice.clone());
+        assert_eq!(response.block_client, alice.direct);
+
+        let mut policy = TrafficControlPolicy::TestNConnIP(
+            TestNConnIPPolicy::new(
+                PolicyConfig {
+                    connection_blocklist_ttl_sec: 60,
+                    ..Default::default()
+                },
+                2,
+            )
+            .await,
+        );
+        assert_eq!(policy.simulate(&alice).block_client, None);
+        assert_eq!(policy.simulate(&alice).block_client, None);
+        assert_eq!(policy.handle_tally(alice.clone()).block_client, None);
+        assert_eq!(policy.simulate(&alice).block_client, alice.direct);
+        assert_eq!(
+            policy.handle_tally(alice.clone()).block_client,
+            alice.direct
+        );
+    }
+
     #[sim_test]
     async fn test_traffic_sketch_mem_estimate() {
         // Test for getting a rough estimate of memory usage for the traffic sketch
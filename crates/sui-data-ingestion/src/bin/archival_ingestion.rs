@@ -15,6 +15,8 @@ struct Config {
     commit_file_size: usize,
     #[serde(default = "default_commit_duration_seconds")]
     commit_duration_seconds: u64,
+    #[serde(default)]
+    write_manifest_v2: bool,
 }
 
 fn default_commit_file_size() -> usize {
@@ -36,6 +38,7 @@ async fn main() -> Result<()> {
         remote_store_options: config.archive_remote_store_options,
         commit_file_size: config.commit_file_size,
         commit_duration_seconds: config.commit_duration_seconds,
+        write_manifest_v2: config.write_manifest_v2,
     };
     let worker = ArchivalWorker::new(archival_config).await?;
     let initial_checkpoint_number = worker.initial_checkpoint_number().await;
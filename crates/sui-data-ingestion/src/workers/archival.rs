@@ -11,9 +11,10 @@ use object_store::ObjectStore;
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
 use sui_archival::{
-    create_file_metadata_from_bytes, finalize_manifest, read_manifest_from_bytes, FileType,
-    Manifest, CHECKPOINT_FILE_MAGIC, SUMMARY_FILE_MAGIC,
+    create_file_metadata_from_bytes, finalize_manifest, read_manifest_from_bytes, FileMetadataV2,
+    FileType, Manifest, CHECKPOINT_FILE_MAGIC, SUMMARY_FILE_MAGIC,
 };
 use sui_data_ingestion_core::{create_remote_store_client, Worker};
 use sui_storage::blob::{Blob, BlobEncoding};
@@ -29,6 +30,10 @@ pub struct ArchivalConfig {
     pub remote_store_options: Vec<(String, String)>,
     pub commit_file_size: usize,
     pub commit_duration_seconds: u64,
+    /// Whether the manifest is written as V2, which records the size and creation time of every
+    /// file. Only enable once all the readers of the archive can decode V2 manifests.
+    #[serde(default)]
+    pub write_manifest_v2: bool,
 }
 
 struct AccumulatedState {
@@ -45,6 +50,7 @@ pub struct ArchivalWorker {
     state: Mutex<AccumulatedState>,
     commit_file_size: usize,
     commit_duration_ms: u64,
+    write_manifest_v2: bool,
 }
 
 impl ArchivalWorker {
@@ -66,6 +72,7 @@ impl ArchivalWorker {
             state: Mutex::new(state),
             commit_file_size: config.commit_file_size,
             commit_duration_ms: config.commit_duration_seconds * 1000,
+            write_manifest_v2: config.write_manifest_v2,
         })
     }
 
@@ -97,23 +104,36 @@ impl ArchivalWorker {
             )
             .await?;
         let mut manifest = Self::read_manifest(&self.remote_store).await?;
-        let checkpoint_file_metadata = create_file_metadata_from_bytes(
-            chk_bytes,
-            FileType::CheckpointContent,
-            state.epoch,
-            state.checkpoint_range.clone(),
-        )?;
-        let summary_file_metadata = create_file_metadata_from_bytes(
-            sum_bytes,
-            FileType::CheckpointSummary,
-            state.epoch,
-            state.checkpoint_range.clone(),
-        )?;
+        if self.write_manifest_v2 {
+            manifest.upgrade_to_v2();
+        }
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let checkpoint_file_metadata = FileMetadataV2 {
+            file_size: chk_bytes.len() as u64,
+            created_at,
+            metadata: create_file_metadata_from_bytes(
+                chk_bytes,
+                FileType::CheckpointContent,
+                state.epoch,
+                state.checkpoint_range.clone(),
+            )?,
+        };
+        let summary_file_metadata = FileMetadataV2 {
+            file_size: sum_bytes.len() as u64,
+            created_at,
+            metadata: create_file_metadata_from_bytes(
+                sum_bytes,
+                FileType::CheckpointSummary,
+                state.epoch,
+                state.checkpoint_range.clone(),
+            )?,
+        };
         manifest.update(
             state.epoch,
             state.checkpoint_range.end,
             checkpoint_file_metadata,
             summary_file_metadata,
+            None,
         );
 
         let bytes = finalize_manifest(manifest)?;
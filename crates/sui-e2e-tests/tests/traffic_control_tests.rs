@@ -219,6 +219,9 @@ async fn test_validator_traffic_control_spam_delegated() -> Result<(), anyhow::E
         destination_port: 8080,
         drain_path: tempfile::tempdir().unwrap().into_path().join("drain"),
         drain_timeout_secs: 10,
+        max_block_attempts: 3,
+        block_retry_base_delay_ms: 100,
+        max_block_duration_ms: 5_000,
     };
     let network_config = ConfigBuilder::new_with_temp_dir()
         .with_policy_config(Some(policy_config))
@@ -252,6 +255,9 @@ async fn test_fullnode_traffic_control_spam_delegated() -> Result<(), anyhow::Er
         destination_port: 9000,
         drain_path: tempfile::tempdir().unwrap().into_path().join("drain"),
         drain_timeout_secs: 10,
+        max_block_attempts: 3,
+        block_retry_base_delay_ms: 100,
+        max_block_duration_ms: 5_000,
     };
     let test_cluster = TestClusterBuilder::new()
         .with_fullnode_policy_config(Some(policy_config))
@@ -282,6 +288,9 @@ async fn test_traffic_control_dead_mans_switch() -> Result<(), anyhow::Error> {
         destination_port: 9000,
         drain_path: drain_path.clone(),
         drain_timeout_secs: 10,
+        max_block_attempts: 3,
+        block_retry_base_delay_ms: 100,
+        max_block_duration_ms: 5_000,
     };
 
     // NOTE: we need to hold onto this tc handle to ensure we don't inadvertently close
@@ -15,7 +15,7 @@ use std::num::NonZeroUsize;
 use std::{collections::HashMap, time::Duration};
 use sui_archival::reader::ArchiveReaderBalancer;
 use sui_archival::writer::ArchiveWriter;
-use sui_config::node::ArchiveReaderConfig;
+use sui_config::node::{ArchiveReaderConfig, ArchiveRetryConfig};
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
 use sui_storage::{FileCompression, StorageFormat};
 use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
@@ -312,6 +312,8 @@ async fn test_state_sync_using_archive() -> anyhow::Result<()> {
         remote_store_config,
         download_concurrency: NonZeroUsize::new(1).unwrap(),
         use_for_pruning_watermark: false,
+        verify_checksums: true,
+        retry_config: ArchiveRetryConfig::default(),
     };
     // We will delete all checkpoints older than this checkpoint on Node 2
     let oldest_checkpoint_to_keep: u64 = 10;
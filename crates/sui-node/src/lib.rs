@@ -531,14 +531,21 @@ impl SuiNode {
 
         let index_store = if is_full_node && config.enable_index_processing {
             info!("creating index store");
-            Some(Arc::new(IndexStore::new(
+            let index_store = IndexStore::new(
                 config.db_path().join("indexes"),
                 &prometheus_registry,
                 epoch_store
                     .protocol_config()
                     .max_move_identifier_len_as_option(),
                 config.remove_deprecated_tables,
-            )))
+            );
+            let index_store = if config.validate_index_transaction_order {
+                info!("validating transaction order index");
+                index_store.validate_transaction_order(false)?
+            } else {
+                index_store
+            };
+            Some(Arc::new(index_store))
         } else {
             None
         };
@@ -878,7 +885,8 @@ impl SuiNode {
                 256 * 1024 * 1024,
                 prometheus_registry,
             )
-            .await?;
+            .await?
+            .with_manifest_v2(config.state_archive_write_config.write_manifest_v2);
             Ok(Some(archive_writer.start(state_sync_store).await?))
         } else {
             Ok(None)
@@ -808,6 +808,11 @@ pub struct ProtocolConfig {
     /// Maximum number of cached objects in the object runtime ObjectStore in system transaction. Enforced by object runtime during execution
     object_runtime_max_num_cached_objects_system_tx: Option<u64>,
 
+    /// Number of entries at which the object runtime ObjectStore evicts the cached lookups of child
+    /// objects that do not exist. Evicted lookups still count towards
+    /// `object_runtime_max_num_cached_objects`. Eviction is disabled if unset.
+    object_runtime_cached_objects_eviction_threshold: Option<u64>,
+
     /// Maximum number of stored objects accessed by object runtime ObjectStore. Enforced by object runtime during execution
     object_runtime_max_num_store_entries: Option<u64>,
 
@@ -1149,6 +1154,8 @@ pub struct ProtocolConfig {
     consensus_max_transaction_size_bytes: Option<u64>,
     /// The maximum size of transactions included in a consensus proposed block
     consensus_max_transactions_in_block_bytes: Option<u64>,
+    /// The maximum number of transactions included in a consensus proposed block
+    consensus_max_transactions_in_block: Option<u64>,
 
     /// The max accumulated txn execution cost per object in a checkpoint. Transactions
     /// in a checkpoint will be deferred once their touch shared objects hit this limit.
@@ -1652,6 +1659,7 @@ impl ProtocolConfig {
 
             object_runtime_max_num_cached_objects: Some(1000),
             object_runtime_max_num_cached_objects_system_tx: Some(1000 * 16),
+            object_runtime_cached_objects_eviction_threshold: None,
             object_runtime_max_num_store_entries: Some(1000),
             object_runtime_max_num_store_entries_system_tx: Some(1000 * 16),
             base_tx_cost_fixed: Some(110_000),
@@ -1913,6 +1921,8 @@ impl ProtocolConfig {
 
             consensus_max_transactions_in_block_bytes: None,
 
+            consensus_max_transactions_in_block: None,
+
             max_accumulated_txn_cost_per_object_in_checkpoint: None,
 
             max_deferral_rounds_for_congestion_control: None,
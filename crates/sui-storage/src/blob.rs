@@ -53,6 +53,36 @@ impl Blob {
         };
         Ok(blob)
     }
+    /// Advances `rbuf` past the next blob without materializing its data.
+    pub fn skip<R: Read>(rbuf: &mut R) -> Result<()> {
+        let len = rbuf.read_varint::<u64>()?;
+        if len == 0 {
+            return Err(anyhow!("Invalid object length of 0 in file"));
+        }
+        BlobEncoding::try_from(rbuf.read_u8()?)?;
+        let skipped = std::io::copy(&mut rbuf.take(len), &mut std::io::sink())?;
+        if skipped != len {
+            return Err(anyhow!("Unexpected end of file while skipping blob"));
+        }
+        Ok(())
+    }
+    /// Reads the length of the sequence the next blob's value starts with, e.g. the number of
+    /// transactions of a `FullCheckpointContents`, and advances `rbuf` past the blob. The rest
+    /// of the value is never decoded nor materialized.
+    pub fn read_sequence_len<R: Read>(rbuf: &mut R) -> Result<u64> {
+        let len = rbuf.read_varint::<u64>()?;
+        if len == 0 {
+            return Err(anyhow!("Invalid object length of 0 in file"));
+        }
+        let encoding = BlobEncoding::try_from(rbuf.read_u8()?)?;
+        let mut data = rbuf.take(len);
+        let sequence_len = match encoding {
+            // BCS prefixes sequences with their ULEB128 encoded length
+            BlobEncoding::Bcs => data.read_varint::<u64>()?,
+        };
+        std::io::copy(&mut data, &mut std::io::sink())?;
+        Ok(sequence_len)
+    }
     pub fn write<W: Write>(&self, wbuf: &mut W) -> Result<usize> {
         let mut buf = [0u8; MAX_VARINT_LENGTH];
         let mut counter = 0;
@@ -5,7 +5,7 @@
 //! The main user of this data is the explorer.
 
 use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -13,7 +13,10 @@ use std::sync::Arc;
 use itertools::Itertools;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
-use prometheus::{register_int_counter_with_registry, IntCounter, Registry};
+use prometheus::{
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry, IntCounter,
+    IntGaugeVec, Registry,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::sync::OwnedMutexGuard;
 use typed_store::TypedStoreError;
@@ -24,16 +27,18 @@ use sui_json_rpc_types::{SuiObjectDataFilter, TransactionFilter};
 use sui_types::base_types::{
     ObjectDigest, ObjectID, SequenceNumber, SuiAddress, TransactionDigest, TxSequenceNumber,
 };
-use sui_types::base_types::{ObjectInfo, ObjectRef};
+use sui_types::base_types::{ObjectInfo, ObjectRef, ObjectType};
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::dynamic_field::{self, DynamicFieldInfo};
 use sui_types::effects::TransactionEvents;
 use sui_types::error::{SuiError, SuiResult, UserInputError};
 use sui_types::inner_temporary_store::TxCoins;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::{Object, Owner};
 use sui_types::parse_sui_struct_tag;
 use tokio::task::spawn_blocking;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
+use typed_store::rocks::errors::typed_store_err_from_rocks_err;
 use typed_store::rocks::{
     default_db_options, read_size_from_env, DBBatch, DBMap, DBOptions, MetricConf,
 };
@@ -52,6 +57,17 @@ pub const MAX_TX_RANGE_SIZE: u64 = 4096;
 
 pub const MAX_GET_OWNED_OBJECT_SIZE: usize = 256;
 const ENV_VAR_COIN_INDEX_BLOCK_CACHE_SIZE_MB: &str = "COIN_INDEX_BLOCK_CACHE_MB";
+// Block cache size for all event index tables, unless overridden by the per-table env var below.
+const ENV_VAR_EVENT_INDEX_BLOCK_CACHE_SIZE_MB: &str = "EVENT_INDEX_BLOCK_CACHE_MB";
+const ENV_VAR_EVENT_ORDER_BLOCK_CACHE_SIZE_MB: &str = "EVENT_ORDER_BLOCK_CACHE_MB";
+const ENV_VAR_EVENT_BY_MOVE_MODULE_BLOCK_CACHE_SIZE_MB: &str =
+    "EVENT_BY_MOVE_MODULE_BLOCK_CACHE_MB";
+const ENV_VAR_EVENT_BY_MOVE_EVENT_BLOCK_CACHE_SIZE_MB: &str = "EVENT_BY_MOVE_EVENT_BLOCK_CACHE_MB";
+const ENV_VAR_EVENT_BY_EVENT_MODULE_BLOCK_CACHE_SIZE_MB: &str =
+    "EVENT_BY_EVENT_MODULE_BLOCK_CACHE_MB";
+const ENV_VAR_EVENT_BY_SENDER_BLOCK_CACHE_SIZE_MB: &str = "EVENT_BY_SENDER_BLOCK_CACHE_MB";
+const ENV_VAR_EVENT_BY_TIME_BLOCK_CACHE_SIZE_MB: &str = "EVENT_BY_TIME_BLOCK_CACHE_MB";
+const ENV_VAR_EVENT_BY_TIME_USER_BLOCK_CACHE_SIZE_MB: &str = "EVENT_BY_TIME_USER_BLOCK_CACHE_MB";
 const ENV_VAR_DISABLE_INDEX_CACHE: &str = "DISABLE_INDEX_CACHE";
 const ENV_VAR_INVALIDATE_INSTEAD_OF_UPDATE: &str = "INVALIDATE_INSTEAD_OF_UPDATE";
 
@@ -69,6 +85,44 @@ pub struct ObjectIndexChanges {
     pub new_dynamic_fields: Vec<(DynamicFieldKey, DynamicFieldInfo)>,
 }
 
+impl ObjectIndexChanges {
+    /// Number of keys listed per change kind in `summary`.
+    const SUMMARY_SAMPLE_SIZE: usize = 3;
+
+    /// Returns a short description of the changes for logging: the number of entries of each
+    /// kind, along with the first few affected keys.
+    pub fn summary(&self) -> String {
+        fn describe<'a, K: std::fmt::Debug + 'a>(
+            name: &str,
+            keys: impl ExactSizeIterator<Item = &'a K>,
+        ) -> String {
+            let count = keys.len();
+            let sample: Vec<_> = keys.take(ObjectIndexChanges::SUMMARY_SAMPLE_SIZE).collect();
+            if count > sample.len() {
+                format!("{name}: {count} {sample:?}..")
+            } else {
+                format!("{name}: {count} {sample:?}")
+            }
+        }
+        [
+            describe("deleted_owners", self.deleted_owners.iter()),
+            describe("deleted_dynamic_fields", self.deleted_dynamic_fields.iter()),
+            describe("new_owners", self.new_owners.iter().map(|(key, _)| key)),
+            describe(
+                "new_dynamic_fields",
+                self.new_dynamic_fields.iter().map(|(key, _)| key),
+            ),
+        ]
+        .join(", ")
+    }
+}
+
+// Shared and immutable objects have no owner to be indexed under, so they are skipped before
+// doing any per-object work for the owner and coin indexes.
+fn has_indexed_owner(object_info: &ObjectInfo) -> bool {
+    !matches!(object_info.owner, Owner::Shared { .. } | Owner::Immutable)
+}
+
 #[derive(Clone, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Debug)]
 pub struct CoinInfo {
     pub version: SequenceNumber,
@@ -93,6 +147,7 @@ pub struct IndexStoreMetrics {
     balance_lookup_from_total: IntCounter,
     all_balance_lookup_from_db: IntCounter,
     all_balance_lookup_from_total: IntCounter,
+    table_estimated_num_keys: IntGaugeVec,
 }
 
 impl IndexStoreMetrics {
@@ -122,6 +177,13 @@ impl IndexStoreMetrics {
                 registry,
             )
             .unwrap(),
+            table_estimated_num_keys: register_int_gauge_vec_with_registry!(
+                "index_table_estimated_num_keys",
+                "Estimated number of keys in each of the main index tables",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
@@ -145,6 +207,13 @@ pub struct IndexStoreTables {
     #[default_options_override_fn = "transactions_from_addr_table_default_config"]
     transactions_from_addr: DBMap<(SuiAddress, TxSequenceNumber), TransactionDigest>,
 
+    /// Number of transactions initiated by each sui address. This table is lazily initialized:
+    /// it only counts transactions indexed after it was introduced, so counts for addresses
+    /// with older transactions are too low until `IndexStore::recompute_tx_count` is called
+    /// for that address.
+    #[default_options_override_fn = "tx_count_from_addr_table_default_config"]
+    tx_count_from_addr: DBMap<SuiAddress, u64>,
+
     /// Index from sui address to transactions that were sent to that address.
     #[default_options_override_fn = "transactions_to_addr_table_default_config"]
     transactions_to_addr: DBMap<(SuiAddress, TxSequenceNumber), TransactionDigest>,
@@ -181,6 +250,12 @@ pub struct IndexStoreTables {
     #[default_options_override_fn = "transactions_seq_table_default_config"]
     transactions_seq: DBMap<TransactionDigest, TxSequenceNumber>,
 
+    /// Index from checkpoint sequence number to the digests of the transactions of that
+    /// checkpoint, in checkpoint order. This is populated when checkpoints are executed, so
+    /// checkpoints executed before this table existed have no entry.
+    #[default_options_override_fn = "transactions_by_checkpoint_table_default_config"]
+    transactions_by_checkpoint: DBMap<CheckpointSequenceNumber, Vec<TransactionDigest>>,
+
     /// This is an index of object references to currently existing objects, indexed by the
     /// composite key of the SuiAddress of their owner and the object ID of the object.
     /// This composite index allows an efficient iterator to list all objected currently owned
@@ -202,18 +277,22 @@ pub struct IndexStoreTables {
     #[deprecated]
     loaded_child_object_versions: DBMap<TransactionDigest, Vec<(ObjectID, SequenceNumber)>>,
 
-    #[default_options_override_fn = "index_table_default_config"]
+    #[default_options_override_fn = "event_order_table_default_config"]
     event_order: DBMap<EventId, EventIndex>,
-    #[default_options_override_fn = "index_table_default_config"]
+    #[default_options_override_fn = "event_by_move_module_table_default_config"]
     event_by_move_module: DBMap<(ModuleId, EventId), EventIndex>,
-    #[default_options_override_fn = "index_table_default_config"]
+    #[default_options_override_fn = "event_by_move_event_table_default_config"]
     event_by_move_event: DBMap<(StructTag, EventId), EventIndex>,
-    #[default_options_override_fn = "index_table_default_config"]
+    #[default_options_override_fn = "event_by_event_module_table_default_config"]
     event_by_event_module: DBMap<(ModuleId, EventId), EventIndex>,
-    #[default_options_override_fn = "index_table_default_config"]
+    #[default_options_override_fn = "event_by_sender_table_default_config"]
     event_by_sender: DBMap<(SuiAddress, EventId), EventIndex>,
-    #[default_options_override_fn = "index_table_default_config"]
+    #[default_options_override_fn = "event_by_time_table_default_config"]
     event_by_time: DBMap<(u64, EventId), EventIndex>,
+    /// Same as `event_by_time`, but only for events emitted by user (i.e. non-system)
+    /// transactions. Events indexed before this table was introduced are missing from it.
+    #[default_options_override_fn = "event_by_time_user_table_default_config"]
+    event_by_time_user: DBMap<(u64, EventId), EventIndex>,
 }
 
 impl IndexStoreTables {
@@ -226,6 +305,53 @@ impl IndexStoreTables {
     }
 }
 
+/// The event indexes maintained by `IndexStore::index_tx`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventIndexType {
+    /// `event_order`, used by `all_events` and `events_by_transaction`.
+    Order,
+    /// `event_by_move_module`, used by `events_by_module_id`.
+    MoveModule,
+    /// `event_by_move_event`, used by `events_by_move_event_struct_name`.
+    MoveEvent,
+    /// `event_by_event_module`, used by `events_by_move_event_module`.
+    EventModule,
+    /// `event_by_sender`, used by `events_by_sender`.
+    Sender,
+    /// `event_by_time` and `event_by_time_user`, used by `event_iterator`.
+    Time,
+}
+
+impl EventIndexType {
+    pub fn all() -> BTreeSet<EventIndexType> {
+        BTreeSet::from([
+            EventIndexType::Order,
+            EventIndexType::MoveModule,
+            EventIndexType::MoveEvent,
+            EventIndexType::EventModule,
+            EventIndexType::Sender,
+            EventIndexType::Time,
+        ])
+    }
+}
+
+/// The transactions whose events are returned by `IndexStore::event_iterator`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EventSource {
+    /// Events of all transactions, read from `event_by_time`.
+    All,
+    /// Events of user transactions only, read from `event_by_time_user`.
+    UserOnly,
+}
+
+/// Whether the end time of `IndexStore::event_iterator` is part of the range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EndBound {
+    Inclusive,
+    /// Lets consecutive half-open ranges be paged through without overlap.
+    Exclusive,
+}
+
 pub struct IndexStore {
     next_sequence_number: AtomicU64,
     tables: IndexStoreTables,
@@ -233,6 +359,10 @@ pub struct IndexStore {
     metrics: Arc<IndexStoreMetrics>,
     max_type_length: u64,
     remove_deprecated_tables: bool,
+    /// The largest `limit` accepted by `get_owner_objects`.
+    max_get_owned_object_size: usize,
+    /// Event indexes written by `index_tx`. Queries against a disabled index return an error.
+    enabled_event_indexes: BTreeSet<EventIndexType>,
 }
 
 // These functions are used to initialize the DB tables
@@ -242,9 +372,15 @@ fn transactions_order_table_default_config() -> DBOptions {
 fn transactions_seq_table_default_config() -> DBOptions {
     default_db_options()
 }
+fn transactions_by_checkpoint_table_default_config() -> DBOptions {
+    default_db_options()
+}
 fn transactions_from_addr_table_default_config() -> DBOptions {
     default_db_options()
 }
+fn tx_count_from_addr_table_default_config() -> DBOptions {
+    default_db_options().optimize_for_point_lookup(64)
+}
 fn transactions_to_addr_table_default_config() -> DBOptions {
     default_db_options()
 }
@@ -260,8 +396,35 @@ fn owner_index_table_default_config() -> DBOptions {
 fn dynamic_field_index_table_default_config() -> DBOptions {
     default_db_options()
 }
-fn index_table_default_config() -> DBOptions {
-    default_db_options()
+// Event index tables use the default options unless a block cache size is configured for them.
+fn event_index_table_config(table_env_var: &str) -> DBOptions {
+    match read_size_from_env(table_env_var)
+        .or_else(|| read_size_from_env(ENV_VAR_EVENT_INDEX_BLOCK_CACHE_SIZE_MB))
+    {
+        Some(block_cache_size_mb) => default_db_options().optimize_for_read(block_cache_size_mb),
+        None => default_db_options(),
+    }
+}
+fn event_order_table_default_config() -> DBOptions {
+    event_index_table_config(ENV_VAR_EVENT_ORDER_BLOCK_CACHE_SIZE_MB)
+}
+fn event_by_move_module_table_default_config() -> DBOptions {
+    event_index_table_config(ENV_VAR_EVENT_BY_MOVE_MODULE_BLOCK_CACHE_SIZE_MB)
+}
+fn event_by_move_event_table_default_config() -> DBOptions {
+    event_index_table_config(ENV_VAR_EVENT_BY_MOVE_EVENT_BLOCK_CACHE_SIZE_MB)
+}
+fn event_by_event_module_table_default_config() -> DBOptions {
+    event_index_table_config(ENV_VAR_EVENT_BY_EVENT_MODULE_BLOCK_CACHE_SIZE_MB)
+}
+fn event_by_sender_table_default_config() -> DBOptions {
+    event_index_table_config(ENV_VAR_EVENT_BY_SENDER_BLOCK_CACHE_SIZE_MB)
+}
+fn event_by_time_table_default_config() -> DBOptions {
+    event_index_table_config(ENV_VAR_EVENT_BY_TIME_BLOCK_CACHE_SIZE_MB)
+}
+fn event_by_time_user_table_default_config() -> DBOptions {
+    event_index_table_config(ENV_VAR_EVENT_BY_TIME_USER_BLOCK_CACHE_SIZE_MB)
 }
 fn coin_index_table_default_config() -> DBOptions {
     default_db_options()
@@ -307,15 +470,84 @@ impl IndexStore {
             metrics: Arc::new(metrics),
             max_type_length: max_type_length.unwrap_or(128),
             remove_deprecated_tables,
+            max_get_owned_object_size: MAX_GET_OWNED_OBJECT_SIZE,
+            enabled_event_indexes: EventIndexType::all(),
+        }
+    }
+
+    /// Checks that the keys of `transaction_order` form a contiguous range starting at 0, which
+    /// `new` assumes when it derives the next sequence number from the largest key. A gap is
+    /// logged, and also returned as an error if `fail_on_gap` is set. This scans the whole table,
+    /// so it is only meant to be run at startup.
+    pub fn validate_transaction_order(self, fail_on_gap: bool) -> SuiResult<Self> {
+        if let Some(missing) = self.find_transaction_order_gap()? {
+            warn!(
+                "transaction_order index is not contiguous, sequence number {missing} is missing"
+            );
+            if fail_on_gap {
+                return Err(SuiError::Storage(format!(
+                    "transaction_order index is not contiguous, sequence number {missing} is missing"
+                )));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns the first sequence number missing from `transaction_order` below its largest key.
+    pub fn find_transaction_order_gap(&self) -> SuiResult<Option<TxSequenceNumber>> {
+        let mut expected: TxSequenceNumber = 0;
+        for item in self.tables.transaction_order.safe_iter() {
+            let (seq, _) = item?;
+            if seq != expected {
+                return Ok(Some(expected));
+            }
+            expected += 1;
+        }
+        Ok(None)
+    }
+
+    /// Restricts the event indexes written by `index_tx` to `enabled_event_indexes`. All event
+    /// indexes are enabled by default. Events indexed while an index was disabled are missing
+    /// from it, even if the index is enabled again later.
+    pub fn with_enabled_event_indexes(
+        mut self,
+        enabled_event_indexes: impl IntoIterator<Item = EventIndexType>,
+    ) -> Self {
+        self.enabled_event_indexes = enabled_event_indexes.into_iter().collect();
+        self
+    }
+
+    pub fn is_event_index_enabled(&self, index: EventIndexType) -> bool {
+        self.enabled_event_indexes.contains(&index)
+    }
+
+    fn ensure_event_index_enabled(&self, index: EventIndexType) -> SuiResult {
+        if self.is_event_index_enabled(index) {
+            Ok(())
+        } else {
+            Err(SuiError::UnsupportedFeatureError {
+                error: format!("Event index {:?} is not enabled on this node", index),
+            })
         }
     }
 
+    /// Overrides the largest page size accepted by `get_owner_objects`, which defaults to
+    /// `MAX_GET_OWNED_OBJECT_SIZE`. One extra object is allowed on top of it as a look-ahead.
+    pub fn with_max_get_owned_object_size(mut self, max_get_owned_object_size: usize) -> Self {
+        self.max_get_owned_object_size = max_get_owned_object_size;
+        self
+    }
+
     pub fn tables(&self) -> &IndexStoreTables {
         &self.tables
     }
 
+    /// Indexes the coins changed by a transaction. The locks of the changed coin owners and of
+    /// `sender` are held by the returned `IndexStoreCacheUpdates`, so that the caller can update
+    /// the per-address indexes of the sender under the same locks.
     pub async fn index_coin(
         &self,
+        sender: SuiAddress,
         digest: &TransactionDigest,
         batch: &mut DBBatch,
         object_index_changes: &ObjectIndexChanges,
@@ -325,10 +557,18 @@ impl IndexStore {
         // However, in many tests today we do not distinguish validator and/or fullnode, so
         // we gracefully exist here.
         if tx_coins.is_none() {
-            return Ok(IndexStoreCacheUpdates::default());
+            let _locks = self
+                .caches
+                .locks
+                .acquire_locks(std::iter::once(sender))
+                .await;
+            return Ok(IndexStoreCacheUpdates {
+                _locks,
+                ..Default::default()
+            });
         }
-        // Acquire locks on changed coin owners
-        let mut addresses: HashSet<SuiAddress> = HashSet::new();
+        // Acquire locks on changed coin owners and the sender
+        let mut addresses: HashSet<SuiAddress> = HashSet::from([sender]);
         addresses.extend(
             object_index_changes
                 .deleted_owners
@@ -339,6 +579,7 @@ impl IndexStore {
             object_index_changes
                 .new_owners
                 .iter()
+                .filter(|(_, obj_info)| has_indexed_owner(obj_info))
                 .map(|((owner, _), _)| *owner),
         );
         let _locks = self.caches.locks.acquire_locks(addresses.into_iter()).await;
@@ -390,6 +631,7 @@ impl IndexStore {
         let coin_add_keys = object_index_changes
         .new_owners
         .iter()
+        .filter(|(_, obj_info)| has_indexed_owner(obj_info))
         .filter_map(|((owner, obj_id), obj_info)| {
             // If it's in written_coins, then it's not a coin. Skip it.
             let obj = written_coins.get(obj_id)?;
@@ -467,8 +709,15 @@ impl IndexStore {
         digest: &TransactionDigest,
         timestamp_ms: u64,
         tx_coins: Option<TxCoins>,
+        is_system_tx: bool,
     ) -> SuiResult<u64> {
         let sequence = self.next_sequence_number.fetch_add(1, Ordering::SeqCst);
+        trace!(
+            tx_digest=?digest,
+            sequence,
+            "object_index_changes: {}",
+            object_index_changes.summary(),
+        );
         let mut batch = self.tables.transactions_from_addr.batch();
 
         batch.insert_batch(
@@ -523,9 +772,17 @@ impl IndexStore {
 
         // Coin Index
         let cache_updates = self
-            .index_coin(digest, &mut batch, &object_index_changes, tx_coins)
+            .index_coin(sender, digest, &mut batch, &object_index_changes, tx_coins)
             .await?;
 
+        // The sender lock is held by `cache_updates` until the batch is written, so concurrent
+        // transactions of the same sender cannot lose an increment of its count.
+        let tx_count = self.tables.tx_count_from_addr.get(&sender)?.unwrap_or(0);
+        batch.insert_batch(
+            &self.tables.tx_count_from_addr,
+            std::iter::once((sender, tx_count + 1)),
+        )?;
+
         // Owner index
         batch.delete_batch(
             &self.tables.owner_index,
@@ -538,7 +795,10 @@ impl IndexStore {
 
         batch.insert_batch(
             &self.tables.owner_index,
-            object_index_changes.new_owners.into_iter(),
+            object_index_changes
+                .new_owners
+                .into_iter()
+                .filter(|(_, obj_info)| has_indexed_owner(obj_info)),
         )?;
 
         batch.insert_batch(
@@ -548,69 +808,92 @@ impl IndexStore {
 
         // events
         let event_digest = events.digest();
-        batch.insert_batch(
-            &self.tables.event_order,
-            events
-                .data
-                .iter()
-                .enumerate()
-                .map(|(i, _)| ((sequence, i), (event_digest, *digest, timestamp_ms))),
-        )?;
-        batch.insert_batch(
-            &self.tables.event_by_move_module,
-            events
-                .data
-                .iter()
-                .enumerate()
-                .map(|(i, e)| {
+        if self.is_event_index_enabled(EventIndexType::Order) {
+            batch.insert_batch(
+                &self.tables.event_order,
+                events
+                    .data
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| ((sequence, i), (event_digest, *digest, timestamp_ms))),
+            )?;
+        }
+        if self.is_event_index_enabled(EventIndexType::MoveModule) {
+            batch.insert_batch(
+                &self.tables.event_by_move_module,
+                events
+                    .data
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| {
+                        (
+                            i,
+                            ModuleId::new(e.package_id.into(), e.transaction_module.clone()),
+                        )
+                    })
+                    .map(|(i, m)| ((m, (sequence, i)), (event_digest, *digest, timestamp_ms))),
+            )?;
+        }
+        if self.is_event_index_enabled(EventIndexType::Sender) {
+            batch.insert_batch(
+                &self.tables.event_by_sender,
+                events.data.iter().enumerate().map(|(i, e)| {
                     (
-                        i,
-                        ModuleId::new(e.package_id.into(), e.transaction_module.clone()),
+                        (e.sender, (sequence, i)),
+                        (event_digest, *digest, timestamp_ms),
                     )
-                })
-                .map(|(i, m)| ((m, (sequence, i)), (event_digest, *digest, timestamp_ms))),
-        )?;
-        batch.insert_batch(
-            &self.tables.event_by_sender,
-            events.data.iter().enumerate().map(|(i, e)| {
-                (
-                    (e.sender, (sequence, i)),
-                    (event_digest, *digest, timestamp_ms),
-                )
-            }),
-        )?;
-        batch.insert_batch(
-            &self.tables.event_by_move_event,
-            events.data.iter().enumerate().map(|(i, e)| {
-                (
-                    (e.type_.clone(), (sequence, i)),
-                    (event_digest, *digest, timestamp_ms),
-                )
-            }),
-        )?;
+                }),
+            )?;
+        }
+        if self.is_event_index_enabled(EventIndexType::MoveEvent) {
+            batch.insert_batch(
+                &self.tables.event_by_move_event,
+                events.data.iter().enumerate().map(|(i, e)| {
+                    (
+                        (e.type_.clone(), (sequence, i)),
+                        (event_digest, *digest, timestamp_ms),
+                    )
+                }),
+            )?;
+        }
 
-        batch.insert_batch(
-            &self.tables.event_by_time,
-            events.data.iter().enumerate().map(|(i, _)| {
-                (
-                    (timestamp_ms, (sequence, i)),
-                    (event_digest, *digest, timestamp_ms),
-                )
-            }),
-        )?;
+        if self.is_event_index_enabled(EventIndexType::Time) {
+            batch.insert_batch(
+                &self.tables.event_by_time,
+                events.data.iter().enumerate().map(|(i, _)| {
+                    (
+                        (timestamp_ms, (sequence, i)),
+                        (event_digest, *digest, timestamp_ms),
+                    )
+                }),
+            )?;
+            if !is_system_tx {
+                batch.insert_batch(
+                    &self.tables.event_by_time_user,
+                    events.data.iter().enumerate().map(|(i, _)| {
+                        (
+                            (timestamp_ms, (sequence, i)),
+                            (event_digest, *digest, timestamp_ms),
+                        )
+                    }),
+                )?;
+            }
+        }
 
-        batch.insert_batch(
-            &self.tables.event_by_event_module,
-            events.data.iter().enumerate().map(|(i, e)| {
-                (
+        if self.is_event_index_enabled(EventIndexType::EventModule) {
+            batch.insert_batch(
+                &self.tables.event_by_event_module,
+                events.data.iter().enumerate().map(|(i, e)| {
                     (
-                        ModuleId::new(e.type_.address, e.type_.module.clone()),
-                        (sequence, i),
-                    ),
-                    (event_digest, *digest, timestamp_ms),
-                )
-            }),
-        )?;
+                        (
+                            ModuleId::new(e.type_.address, e.type_.module.clone()),
+                            (sequence, i),
+                        ),
+                        (event_digest, *digest, timestamp_ms),
+                    )
+                }),
+            )?;
+        }
 
         let invalidate_caches =
             read_size_from_env(ENV_VAR_INVALIDATE_INSTEAD_OF_UPDATE).unwrap_or(0) > 0;
@@ -649,6 +932,19 @@ impl IndexStore {
         self.next_sequence_number.load(Ordering::SeqCst) + 1
     }
 
+    /// Returns the sequence number that the next call to `index_tx` will assign, i.e. the
+    /// number of transactions indexed so far. Unlike `next_sequence_number`, this is exactly
+    /// the value `index_tx` will return.
+    pub fn peek_next_sequence_number(&self) -> TxSequenceNumber {
+        self.next_sequence_number.load(Ordering::SeqCst)
+    }
+
+    /// Returns the sequence number assigned to the most recently indexed transaction, or `None`
+    /// if no transaction has been indexed yet.
+    pub fn last_assigned_sequence_number(&self) -> Option<TxSequenceNumber> {
+        self.peek_next_sequence_number().checked_sub(1)
+    }
+
     pub fn get_transactions(
         &self,
         filter: Option<TransactionFilter>,
@@ -656,6 +952,23 @@ impl IndexStore {
         limit: Option<usize>,
         reverse: bool,
     ) -> SuiResult<Vec<TransactionDigest>> {
+        // NOTE: checkpoints which were not indexed by this store are served from the
+        // checkpoint contents in `get_transactions` of authority.rs.
+        if let Some(TransactionFilter::Checkpoint(checkpoint)) = filter {
+            if let Some(digests) = self.get_transactions_in_checkpoint(checkpoint)? {
+                let iter: Box<dyn Iterator<Item = TransactionDigest>> = if reverse {
+                    Box::new(digests.into_iter().rev())
+                } else {
+                    Box::new(digests.into_iter())
+                };
+                // cursor is exclusive
+                return Ok(iter
+                    .skip_while(|d| cursor.is_some() && Some(*d) != cursor)
+                    .skip(usize::from(cursor.is_some()))
+                    .take(limit.unwrap_or(usize::MAX))
+                    .collect());
+            }
+        }
         // Lookup TransactionDigest sequence number,
         let cursor = if let Some(cursor) = cursor {
             Some(
@@ -685,8 +998,6 @@ impl IndexStore {
             Some(TransactionFilter::ToAddress(address)) => {
                 Ok(self.get_transactions_to_addr(address, cursor, limit, reverse)?)
             }
-            // NOTE: filter via checkpoint sequence number is implemented in
-            // `get_transactions` of authority.rs.
             Some(_) => Err(SuiError::UserInputError {
                 error: UserInputError::Unsupported(format!("{:?}", filter)),
             }),
@@ -719,6 +1030,35 @@ impl IndexStore {
         }
     }
 
+    pub fn is_checkpoint_indexed(&self, checkpoint: CheckpointSequenceNumber) -> SuiResult<bool> {
+        Ok(self
+            .tables
+            .transactions_by_checkpoint
+            .contains_key(&checkpoint)?)
+    }
+
+    /// Indexes the transactions of an executed checkpoint, so that the checkpoint filter can be
+    /// served by this store. `tx_digests` must be in checkpoint order.
+    pub fn index_checkpoint_transactions(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+        tx_digests: &[TransactionDigest],
+    ) -> SuiResult {
+        self.tables
+            .transactions_by_checkpoint
+            .insert(&checkpoint, &tx_digests.to_vec())?;
+        Ok(())
+    }
+
+    /// Returns the transactions indexed for `checkpoint` in checkpoint order, or `None` if the
+    /// checkpoint was never indexed by this store.
+    pub fn get_transactions_in_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<Vec<TransactionDigest>>> {
+        Ok(self.tables.transactions_by_checkpoint.get(&checkpoint)?)
+    }
+
     fn get_transactions_from_index<KeyT: Clone + Serialize + DeserializeOwned + PartialEq>(
         index: &DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
         key: KeyT,
@@ -812,6 +1152,28 @@ impl IndexStore {
         )
     }
 
+    /// Returns the number of transactions initiated by `addr`. The count is lazily initialized,
+    /// and only includes transactions indexed since the count index was introduced, unless it
+    /// has been backfilled for this address with `recompute_tx_count`.
+    pub fn get_transaction_count_from(&self, addr: SuiAddress) -> SuiResult<u64> {
+        Ok(self.tables.tx_count_from_addr.get(&addr)?.unwrap_or(0))
+    }
+
+    /// Backfills the transaction count of `addr` by iterating over all of its transactions in
+    /// `transactions_from_addr`, and returns the new count. This must not run concurrently with
+    /// `index_tx` for a transaction sent by `addr`, or the count may be lost.
+    pub fn recompute_tx_count(&self, addr: SuiAddress) -> SuiResult<u64> {
+        let count = self
+            .tables
+            .transactions_from_addr
+            .unbounded_iter()
+            .skip_to(&(addr, TxSequenceNumber::MIN))?
+            .take_while(|((sender, _), _)| *sender == addr)
+            .count() as u64;
+        self.tables.tx_count_from_addr.insert(&addr, &count)?;
+        Ok(count)
+    }
+
     pub fn get_transactions_by_move_function(
         &self,
         package: ObjectID,
@@ -925,6 +1287,7 @@ impl IndexStore {
         limit: usize,
         descending: bool,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.ensure_event_index_enabled(EventIndexType::Order)?;
         Ok(if descending {
             self.tables
                 .event_order
@@ -957,6 +1320,7 @@ impl IndexStore {
         limit: usize,
         descending: bool,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.ensure_event_index_enabled(EventIndexType::Order)?;
         let seq = self
             .get_transaction_seq(digest)?
             .ok_or(SuiError::TransactionNotFound { digest: *digest })?;
@@ -1026,6 +1390,7 @@ impl IndexStore {
         limit: usize,
         descending: bool,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.ensure_event_index_enabled(EventIndexType::MoveModule)?;
         Self::get_event_from_index(
             &self.tables.event_by_move_module,
             module,
@@ -1044,6 +1409,7 @@ impl IndexStore {
         limit: usize,
         descending: bool,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.ensure_event_index_enabled(EventIndexType::MoveEvent)?;
         Self::get_event_from_index(
             &self.tables.event_by_move_event,
             struct_name,
@@ -1062,6 +1428,7 @@ impl IndexStore {
         limit: usize,
         descending: bool,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.ensure_event_index_enabled(EventIndexType::EventModule)?;
         Self::get_event_from_index(
             &self.tables.event_by_event_module,
             module_id,
@@ -1080,6 +1447,7 @@ impl IndexStore {
         limit: usize,
         descending: bool,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.ensure_event_index_enabled(EventIndexType::Sender)?;
         Self::get_event_from_index(
             &self.tables.event_by_sender,
             sender,
@@ -1090,6 +1458,29 @@ impl IndexStore {
         )
     }
 
+    /// Returns up to `limit` distinct senders of indexed events, in ascending order. Each sender's
+    /// events are skipped over with a seek, so the scan does not grow with the number of events.
+    pub fn distinct_event_senders(&self, limit: usize) -> SuiResult<Vec<SuiAddress>> {
+        self.ensure_event_index_enabled(EventIndexType::Sender)?;
+        let mut senders: Vec<SuiAddress> = Vec::new();
+        let mut iter = self.tables.event_by_sender.unbounded_iter();
+        while senders.len() < limit {
+            let Some(((sender, _), _)) = iter.next() else {
+                break;
+            };
+            if senders.last() == Some(&sender) {
+                continue;
+            }
+            senders.push(sender);
+            iter = iter.skip_to(&(sender, (TxSequenceNumber::MAX, usize::MAX)))?;
+        }
+        Ok(senders)
+    }
+
+    /// Returns the events emitted between `start_time` and `end_time`. With
+    /// `EventSource::UserOnly`, events emitted by system transactions (e.g. the consensus commit
+    /// prologue) are skipped. `start_time` is always inclusive, while `end_bound` decides whether
+    /// `end_time` is.
     pub fn event_iterator(
         &self,
         start_time: u64,
@@ -1098,13 +1489,24 @@ impl IndexStore {
         event_seq: usize,
         limit: usize,
         descending: bool,
+        source: EventSource,
+        end_bound: EndBound,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        self.ensure_event_index_enabled(EventIndexType::Time)?;
+        let index = match source {
+            EventSource::All => &self.tables.event_by_time,
+            EventSource::UserOnly => &self.tables.event_by_time_user,
+        };
+        let before_end = |m: &u64| match end_bound {
+            EndBound::Inclusive => m <= &end_time,
+            EndBound::Exclusive => m < &end_time,
+        };
         Ok(if descending {
-            self.tables
-                .event_by_time
+            index
                 .unbounded_iter()
                 .skip_prior_to(&(end_time, (tx_seq, event_seq)))?
                 .reverse()
+                .skip_while(|((m, _), _)| !before_end(m))
                 .take_while(|((m, _), _)| m >= &start_time)
                 .take(limit)
                 .map(|((_, (_, event_seq)), (digest, tx_digest, time))| {
@@ -1112,11 +1514,10 @@ impl IndexStore {
                 })
                 .collect()
         } else {
-            self.tables
-                .event_by_time
+            index
                 .unbounded_iter()
                 .skip_to(&(start_time, (tx_seq, event_seq)))?
-                .take_while(|((m, _), _)| m <= &end_time)
+                .take_while(|((m, _), _)| before_end(m))
                 .take(limit)
                 .map(|((_, (_, event_seq)), (digest, tx_digest, time))| {
                     (digest, tx_digest, event_seq, time)
@@ -1205,6 +1606,16 @@ impl IndexStore {
         limit: usize,
         filter: Option<SuiObjectDataFilter>,
     ) -> SuiResult<Vec<ObjectInfo>> {
+        // Paginating callers such as `IndexerApi::get_owned_objects` fetch one object beyond the
+        // page size to find out whether there is a next page, so allow for that look-ahead.
+        if limit > self.max_get_owned_object_size + 1 {
+            return Err(SuiError::UserInputError {
+                error: UserInputError::SizeLimitExceeded {
+                    limit: "maximum number of owned objects per request".to_string(),
+                    value: self.max_get_owned_object_size.to_string(),
+                },
+            });
+        }
         let cursor = match cursor {
             Some(cursor) => cursor,
             None => ObjectID::ZERO,
@@ -1238,6 +1649,27 @@ impl IndexStore {
             .map(|((_, coin_type, obj_id), coin)| (coin_type, obj_id, coin)))
     }
 
+    /// Returns every coin held by `owner`, across all coin types, e.g. for wallet backups. The
+    /// result is deterministic: coins are grouped by coin type in the same order as the coin index,
+    /// and within a type are sorted from the greatest to the least balance, with ties broken by
+    /// object id. Note that this differs from the coin index itself, which orders coins of a type
+    /// by object id only.
+    pub fn export_owner_coins(
+        &self,
+        owner: SuiAddress,
+    ) -> SuiResult<Vec<(String, ObjectID, CoinInfo)>> {
+        let mut coins: Vec<_> =
+            Self::get_owned_coins_iterator(&self.tables.coin_index, owner, None)?.collect();
+        coins.sort_by(|(type_a, id_a, coin_a), (type_b, id_b, coin_b)| {
+            (type_a, std::cmp::Reverse(coin_a.balance), id_a).cmp(&(
+                type_b,
+                std::cmp::Reverse(coin_b.balance),
+                id_b,
+            ))
+        });
+        Ok(coins)
+    }
+
     pub fn get_owned_coins_iterator_with_cursor(
         &self,
         owner: SuiAddress,
@@ -1294,6 +1726,29 @@ impl IndexStore {
             .map(|(_, object_info)| object_info))
     }
 
+    /// Returns the number of objects owned by `owner` matching `filter`, without collecting them.
+    pub fn count_owned_objects(
+        &self,
+        owner: SuiAddress,
+        filter: Option<SuiObjectDataFilter>,
+    ) -> SuiResult<u64> {
+        Ok(self
+            .get_owner_objects_iterator(owner, ObjectID::ZERO, filter)?
+            .count() as u64)
+    }
+
+    /// Returns the number of objects owned by `owner` for each object type, including packages.
+    pub fn owner_object_type_breakdown(
+        &self,
+        owner: SuiAddress,
+    ) -> SuiResult<BTreeMap<ObjectType, u64>> {
+        let mut breakdown = BTreeMap::new();
+        for info in self.get_owner_objects_iterator(owner, ObjectID::ZERO, None)? {
+            *breakdown.entry(info.type_).or_insert(0) += 1;
+        }
+        Ok(breakdown)
+    }
+
     pub fn insert_genesis_objects(&self, object_index_changes: ObjectIndexChanges) -> SuiResult {
         let mut batch = self.tables.owner_index.batch();
         batch.insert_batch(
@@ -1308,10 +1763,74 @@ impl IndexStore {
         Ok(())
     }
 
+    /// Like `insert_genesis_objects`, but writes the owner and dynamic field entries in batches
+    /// of at most `chunk_size` entries instead of a single batch, so that a large genesis does
+    /// not build one huge write batch. After each batch is written, `progress` is called with
+    /// the number of entries written so far and the total number of entries.
+    pub fn insert_genesis_objects_in_chunks(
+        &self,
+        object_index_changes: ObjectIndexChanges,
+        chunk_size: usize,
+        mut progress: impl FnMut(usize, usize),
+    ) -> SuiResult {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        let ObjectIndexChanges {
+            new_owners,
+            new_dynamic_fields,
+            ..
+        } = object_index_changes;
+        let total = new_owners.len() + new_dynamic_fields.len();
+        let mut written = 0;
+        for chunk in new_owners.chunks(chunk_size) {
+            let mut batch = self.tables.owner_index.batch();
+            batch.insert_batch(&self.tables.owner_index, chunk.iter().cloned())?;
+            batch.write()?;
+            written += chunk.len();
+            progress(written, total);
+        }
+        for chunk in new_dynamic_fields.chunks(chunk_size) {
+            let mut batch = self.tables.dynamic_field_index.batch();
+            batch.insert_batch(&self.tables.dynamic_field_index, chunk.iter().cloned())?;
+            batch.write()?;
+            written += chunk.len();
+            progress(written, total);
+        }
+        Ok(())
+    }
+
     pub fn is_empty(&self) -> bool {
         self.tables.owner_index.is_empty()
     }
 
+    /// Flushes the memtables of all index tables to disk and waits for the flushes to complete.
+    /// This should be called before `checkpoint_db` when a consistent on-disk state is required,
+    /// e.g. for coordinated backups.
+    pub fn flush(&self) -> SuiResult {
+        self.tables.transactions_from_addr.flush()?;
+        self.tables.tx_count_from_addr.flush()?;
+        self.tables.transactions_to_addr.flush()?;
+        self.tables.transactions_by_move_function.flush()?;
+        self.tables.transaction_order.flush()?;
+        self.tables.transactions_seq.flush()?;
+        self.tables.transactions_by_checkpoint.flush()?;
+        self.tables.owner_index.flush()?;
+        self.tables.coin_index.flush()?;
+        self.tables.dynamic_field_index.flush()?;
+        self.tables.event_order.flush()?;
+        self.tables.event_by_move_module.flush()?;
+        self.tables.event_by_move_event.flush()?;
+        self.tables.event_by_event_module.flush()?;
+        self.tables.event_by_sender.flush()?;
+        self.tables.event_by_time.flush()?;
+        self.tables.event_by_time_user.flush()?;
+        #[allow(deprecated)]
+        if !self.remove_deprecated_tables {
+            self.tables.transactions_by_input_object_id.flush()?;
+            self.tables.transactions_by_mutated_object_id.flush()?;
+        }
+        Ok(())
+    }
+
     pub fn checkpoint_db(&self, path: &Path) -> SuiResult {
         // We are checkpointing the whole db
         self.tables
@@ -1460,7 +1979,30 @@ impl IndexStore {
     ) -> SuiResult<Arc<HashMap<TypeTag, TotalBalance>>> {
         metrics.all_balance_lookup_from_db.inc();
         let mut balances: HashMap<TypeTag, TotalBalance> = HashMap::new();
-        let coins = Self::get_owned_coins_iterator(&coin_index, owner, None)?
+        Self::for_each_balance_in_db(&coin_index, owner, |coin_type, balance| {
+            balances.insert(coin_type, balance);
+        })?;
+        Ok(Arc::new(balances))
+    }
+
+    /// Reads the balance of every coin type held by `owner` from the backend database, passing
+    /// them to `f` one coin type at a time. Unlike `get_all_balance`, the balances are neither
+    /// cached nor collected, so memory use does not grow with the number of coin types.
+    pub fn for_each_balance(
+        &self,
+        owner: SuiAddress,
+        f: impl FnMut(TypeTag, TotalBalance),
+    ) -> SuiResult {
+        self.metrics.all_balance_lookup_from_db.inc();
+        Self::for_each_balance_in_db(&self.tables.coin_index, owner, f)
+    }
+
+    fn for_each_balance_in_db(
+        coin_index: &DBMap<CoinIndexKey, CoinInfo>,
+        owner: SuiAddress,
+        mut f: impl FnMut(TypeTag, TotalBalance),
+    ) -> SuiResult {
+        let coins = Self::get_owned_coins_iterator(coin_index, owner, None)?
             .group_by(|(coin_type, _obj_id, _coin)| coin_type.clone());
         for (coin_type, coins) in &coins {
             let mut total_balance = 0i128;
@@ -1476,7 +2018,7 @@ impl IndexStore {
                         e
                     ))
                 })?));
-            balances.insert(
+            f(
                 coin_type,
                 TotalBalance {
                     num_coins: coin_object_count,
@@ -1484,7 +2026,83 @@ impl IndexStore {
                 },
             );
         }
-        Ok(Arc::new(balances))
+        Ok(())
+    }
+
+    /// Updates the `index_table_estimated_num_keys` gauges of the main index tables, from the
+    /// estimates kept by rocksdb. These are cheap to read, as no table is scanned, but can be off,
+    /// especially right after large deletions.
+    pub fn report_table_sizes(&self) -> SuiResult {
+        fn estimated_num_keys<K, V>(table: &DBMap<K, V>) -> SuiResult<i64> {
+            let num_keys = table
+                .rocksdb
+                .property_int_value_cf(&table.cf(), "rocksdb.estimate-num-keys")
+                .map_err(typed_store_err_from_rocks_err)?;
+            Ok(num_keys.unwrap_or(0) as i64)
+        }
+        let tables = [
+            ("owner_index", estimated_num_keys(&self.tables.owner_index)?),
+            ("coin_index", estimated_num_keys(&self.tables.coin_index)?),
+            (
+                "transaction_order",
+                estimated_num_keys(&self.tables.transaction_order)?,
+            ),
+            ("event_order", estimated_num_keys(&self.tables.event_order)?),
+        ];
+        for (table, num_keys) in tables {
+            self.metrics
+                .table_estimated_num_keys
+                .with_label_values(&[table])
+                .set(num_keys);
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the cached balances of `owner` from the coin index. Unlike the merge-based
+    /// updates done when indexing transactions, this replaces the cached values with the
+    /// authoritative ones read from the db, so it can be used to repair inconsistent caches.
+    pub async fn rebuild_all_balances(&self, owner: SuiAddress) -> SuiResult<()> {
+        // Hold the owner lock so that no transaction can update the cached balances while
+        // they are being rebuilt.
+        let _locks = self
+            .caches
+            .locks
+            .acquire_locks(std::iter::once(owner))
+            .await;
+
+        let metrics_cloned = self.metrics.clone();
+        let coin_index_cloned = self.tables.coin_index.clone();
+        let balances = spawn_blocking(move || {
+            Self::get_all_balances_from_db(metrics_cloned, coin_index_cloned, owner)
+        })
+        .await
+        .unwrap()?;
+
+        // Invalidate every coin type we may have cached a balance for, including those the
+        // owner no longer holds any coins of.
+        let mut coin_types: HashSet<TypeTag> = balances.keys().cloned().collect();
+        if let Some(Ok(cached)) = self.caches.all_balances.get(&owner).await {
+            coin_types.extend(cached.keys().cloned());
+        }
+        self.invalidate_per_coin_type_cache(
+            coin_types.into_iter().map(|coin_type| (owner, coin_type)),
+        )
+        .await?;
+        self.invalidate_all_balance_cache(std::iter::once(owner))
+            .await?;
+
+        for (coin_type, balance) in balances.iter() {
+            let balance = *balance;
+            self.caches
+                .per_coin_type_balance
+                .get_with((owner, coin_type.clone()), async move { Ok(balance) })
+                .await?;
+        }
+        self.caches
+            .all_balances
+            .get_with(owner, async move { Ok(balances) })
+            .await?;
+        Ok(())
     }
 
     async fn invalidate_per_coin_type_cache(
@@ -1579,29 +2197,166 @@ impl IndexStore {
 
 #[cfg(test)]
 mod tests {
-    use crate::indexes::ObjectIndexChanges;
+    use crate::indexes::{
+        CoinInfo, EndBound, EventIndexType, EventSource, ObjectIndexChanges, TotalBalance,
+        ENV_VAR_EVENT_BY_MOVE_EVENT_BLOCK_CACHE_SIZE_MB, ENV_VAR_EVENT_INDEX_BLOCK_CACHE_SIZE_MB,
+        MAX_GET_OWNED_OBJECT_SIZE,
+    };
     use crate::IndexStore;
     use move_core_types::account_address::AccountAddress;
     use prometheus::Registry;
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, HashMap};
     use std::env::temp_dir;
-    use sui_types::base_types::{ObjectInfo, ObjectType, SuiAddress};
-    use sui_types::digests::TransactionDigest;
+    use sui_json_rpc_types::{SuiObjectDataFilter, TransactionFilter};
+    use sui_types::base_types::{
+        MoveObjectType, ObjectID, ObjectInfo, ObjectType, SequenceNumber, SuiAddress,
+        TxSequenceNumber,
+    };
+    use sui_types::digests::{ObjectDigest, TransactionDigest};
     use sui_types::effects::TransactionEvents;
+    use sui_types::error::{SuiError, UserInputError};
+    use sui_types::event::Event;
     use sui_types::gas_coin::GAS;
     use sui_types::object;
     use sui_types::object::Owner;
+    use sui_types::parse_sui_type_tag;
+    use tempfile::tempdir;
+    use typed_store::rocks::DBMap;
+    use typed_store::traits::Map;
+
+    #[test]
+    fn test_object_index_changes_summary() {
+        let address: SuiAddress = AccountAddress::random().into();
+        let object = object::Object::new_gas_with_balance_and_owner_for_testing(100, address);
+        let object_info = ObjectInfo {
+            object_id: object.id(),
+            version: object.version(),
+            digest: object.digest(),
+            type_: ObjectType::Struct(object.type_().unwrap().clone()),
+            owner: Owner::AddressOwner(address),
+            previous_transaction: object.previous_transaction,
+        };
+        let deleted_owners: Vec<_> = (0..5).map(|_| (address, ObjectID::random())).collect();
+        let deleted_dynamic_field = (ObjectID::random(), ObjectID::random());
+        let changes = ObjectIndexChanges {
+            deleted_owners: deleted_owners.clone(),
+            deleted_dynamic_fields: vec![deleted_dynamic_field],
+            new_owners: vec![((address, object.id()), object_info)],
+            new_dynamic_fields: vec![],
+        };
+
+        let summary = changes.summary();
+        assert!(summary.contains(&format!(
+            "deleted_owners: 5 {:?}..",
+            &deleted_owners[..ObjectIndexChanges::SUMMARY_SAMPLE_SIZE]
+        )));
+        assert!(!summary.contains(&format!("{:?}", deleted_owners[3])));
+        assert!(summary.contains(&format!(
+            "deleted_dynamic_fields: 1 {:?}",
+            [deleted_dynamic_field]
+        )));
+        assert!(summary.contains(&format!("new_owners: 1 {:?}", [(address, object.id())])));
+        assert!(summary.contains("new_dynamic_fields: 0 []"));
+    }
 
     #[tokio::test]
-    async fn test_index_cache() -> anyhow::Result<()> {
-        // This test is going to invoke `index_tx()`where 10 coins each with balance 100
-        // are going to be added to an address. The balance is then going to be read from the db
-        // and the cache. It should be 1000. Then, we are going to delete 3 of those coins from
-        // the address and invoke `index_tx()` again and read balance. The balance should be 700
-        // and verified from both db and cache.
-        // This tests make sure we are invalidating entries in the cache and always reading latest
-        // balance.
-        let index_store = IndexStore::new(temp_dir(), &Registry::default(), Some(128), false);
+    async fn test_index_skips_shared_and_immutable_objects() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+        let mut written_objects = BTreeMap::new();
+        let mut new_objects = vec![];
+        let owners = [
+            Owner::AddressOwner(address),
+            Owner::Shared {
+                initial_shared_version: SequenceNumber::from_u64(1),
+            },
+            Owner::Immutable,
+            Owner::AddressOwner(address),
+        ];
+        for owner in owners {
+            let object = object::Object::new_gas_with_balance_and_owner_for_testing(100, address);
+            new_objects.push((
+                (address, object.id()),
+                ObjectInfo {
+                    object_id: object.id(),
+                    version: object.version(),
+                    digest: object.digest(),
+                    type_: ObjectType::Struct(object.type_().unwrap().clone()),
+                    owner,
+                    previous_transaction: object.previous_transaction,
+                },
+            ));
+            written_objects.insert(object.id(), object);
+        }
+        let owned_ids: Vec<_> = new_objects
+            .iter()
+            .filter(|(_, info)| matches!(info.owner, Owner::AddressOwner(_)))
+            .map(|(_, info)| info.object_id)
+            .collect();
+
+        index_store
+            .index_tx(
+                address,
+                vec![].into_iter(),
+                vec![].into_iter(),
+                vec![].into_iter(),
+                &TransactionEvents { data: vec![] },
+                ObjectIndexChanges {
+                    deleted_owners: vec![],
+                    deleted_dynamic_fields: vec![],
+                    new_owners: new_objects,
+                    new_dynamic_fields: vec![],
+                },
+                &TransactionDigest::random(),
+                1234,
+                Some((BTreeMap::new(), written_objects)),
+                false,
+            )
+            .await?;
+
+        let mut owner_index_ids: Vec<_> = index_store
+            .tables
+            .owner_index
+            .unbounded_iter()
+            .map(|((_, object_id), _)| object_id)
+            .collect();
+        owner_index_ids.sort();
+        let mut coin_index_ids: Vec<_> = index_store
+            .tables
+            .coin_index
+            .unbounded_iter()
+            .map(|((_, _, object_id), _)| object_id)
+            .collect();
+        coin_index_ids.sort();
+        let mut expected = owned_ids;
+        expected.sort();
+        assert_eq!(owner_index_ids, expected);
+        assert_eq!(coin_index_ids, expected);
+        assert_eq!(
+            index_store.get_balance(address, GAS::type_tag()).await?,
+            TotalBalance {
+                balance: 200,
+                num_coins: 2,
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_cache() -> anyhow::Result<()> {
+        // This test is going to invoke `index_tx()`where 10 coins each with balance 100
+        // are going to be added to an address. The balance is then going to be read from the db
+        // and the cache. It should be 1000. Then, we are going to delete 3 of those coins from
+        // the address and invoke `index_tx()` again and read balance. The balance should be 700
+        // and verified from both db and cache.
+        // This tests make sure we are invalidating entries in the cache and always reading latest
+        // balance.
+        let index_store = IndexStore::new(temp_dir(), &Registry::default(), Some(128), false);
         let address: SuiAddress = AccountAddress::random().into();
         let mut written_objects = BTreeMap::new();
         let mut object_map = BTreeMap::new();
@@ -1642,6 +2397,7 @@ mod tests {
                 &TransactionDigest::random(),
                 1234,
                 Some(tx_coins),
+                false,
             )
             .await?;
 
@@ -1686,6 +2442,7 @@ mod tests {
                 &TransactionDigest::random(),
                 1234,
                 Some(tx_coins),
+                false,
             )
             .await?;
         let balance_from_db = IndexStore::get_balance_from_db(
@@ -1716,4 +2473,994 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_event_index_block_cache_size_from_env() -> anyhow::Result<()> {
+        fn block_cache_capacity<K, V>(table: &DBMap<K, V>) -> Option<u64> {
+            table
+                .rocksdb
+                .property_int_value_cf(&table.cf(), "rocksdb.block-cache-capacity")
+                .unwrap()
+        }
+
+        std::env::set_var(ENV_VAR_EVENT_INDEX_BLOCK_CACHE_SIZE_MB, "3");
+        std::env::set_var(ENV_VAR_EVENT_BY_MOVE_EVENT_BLOCK_CACHE_SIZE_MB, "7");
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        std::env::remove_var(ENV_VAR_EVENT_INDEX_BLOCK_CACHE_SIZE_MB);
+        std::env::remove_var(ENV_VAR_EVENT_BY_MOVE_EVENT_BLOCK_CACHE_SIZE_MB);
+
+        let tables = &index_store.tables;
+        // the per-table size takes precedence over the size for all event tables
+        assert_eq!(
+            block_cache_capacity(&tables.event_by_move_event),
+            Some(7 << 20)
+        );
+        for capacity in [
+            block_cache_capacity(&tables.event_order),
+            block_cache_capacity(&tables.event_by_move_module),
+            block_cache_capacity(&tables.event_by_event_module),
+            block_cache_capacity(&tables.event_by_sender),
+            block_cache_capacity(&tables.event_by_time),
+            block_cache_capacity(&tables.event_by_time_user),
+        ] {
+            assert_eq!(capacity, Some(3 << 20));
+        }
+        // tables other than the event indexes are unaffected
+        assert_ne!(
+            block_cache_capacity(&tables.transactions_from_addr),
+            Some(3 << 20)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_order_gap() -> anyhow::Result<()> {
+        let path = tempdir()?.into_path();
+        let index_store = IndexStore::new(path.clone(), &Registry::default(), Some(128), false);
+        assert_eq!(index_store.find_transaction_order_gap()?, None);
+        for seq in [0, 1, 3] {
+            index_store
+                .tables
+                .transaction_order
+                .insert(&seq, &TransactionDigest::random())?;
+        }
+        assert_eq!(index_store.find_transaction_order_gap()?, Some(2));
+
+        // a gap is only reported as an error when asked to
+        let index_store = index_store.validate_transaction_order(false)?;
+        assert!(matches!(
+            index_store.validate_transaction_order(true),
+            Err(SuiError::Storage(_))
+        ));
+
+        // filling the gap makes the index valid again
+        let index_store = IndexStore::new(path, &Registry::default(), Some(128), false);
+        index_store
+            .tables
+            .transaction_order
+            .insert(&2, &TransactionDigest::random())?;
+        assert_eq!(index_store.find_transaction_order_gap()?, None);
+        index_store.validate_transaction_order(true)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flush_and_reopen() -> anyhow::Result<()> {
+        let path = tempdir()?.into_path();
+        let index_store = IndexStore::new(path.clone(), &Registry::default(), Some(128), false);
+        let address: SuiAddress = AccountAddress::random().into();
+        let digest = TransactionDigest::random();
+        let sequence = index_store
+            .index_tx(
+                address,
+                vec![].into_iter(),
+                vec![].into_iter(),
+                vec![].into_iter(),
+                &TransactionEvents { data: vec![] },
+                ObjectIndexChanges {
+                    deleted_owners: vec![],
+                    deleted_dynamic_fields: vec![],
+                    new_owners: vec![],
+                    new_dynamic_fields: vec![],
+                },
+                &digest,
+                1234,
+                None,
+                false,
+            )
+            .await?;
+        index_store.flush()?;
+        drop(index_store);
+
+        let index_store = IndexStore::new(path, &Registry::default(), Some(128), false);
+        assert_eq!(index_store.get_transaction_seq(&digest)?, Some(sequence));
+        assert_eq!(
+            index_store.get_transactions_from_addr(address, None, None, false)?,
+            vec![digest]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transactions_by_checkpoint() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+        let mut digests = vec![];
+        for _ in 0..4 {
+            let digest = TransactionDigest::random();
+            index_store
+                .index_tx(
+                    address,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &TransactionEvents { data: vec![] },
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &digest,
+                    1234,
+                    None,
+                    false,
+                )
+                .await?;
+            digests.push(digest);
+        }
+        // Checkpoints list their transactions independently of the order they were indexed in
+        index_store.index_checkpoint_transactions(5, &[digests[1], digests[0]])?;
+        index_store.index_checkpoint_transactions(6, &digests[2..3])?;
+
+        assert_eq!(
+            index_store.get_transactions_in_checkpoint(5)?,
+            Some(vec![digests[1], digests[0]])
+        );
+        assert_eq!(
+            index_store.get_transactions_in_checkpoint(6)?,
+            Some(digests[2..3].to_vec())
+        );
+        assert_eq!(index_store.get_transactions_in_checkpoint(7)?, None);
+
+        let filter = Some(TransactionFilter::Checkpoint(5));
+        assert_eq!(
+            index_store.get_transactions(filter.clone(), None, None, true)?,
+            vec![digests[0], digests[1]]
+        );
+        assert_eq!(
+            index_store.get_transactions(filter, Some(digests[1]), Some(1), false)?,
+            vec![digests[0]]
+        );
+        // Checkpoints that were never indexed are not served by the index store
+        assert!(index_store
+            .get_transactions(Some(TransactionFilter::Checkpoint(7)), None, None, false)
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_count_owned_objects() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+        let other_address: SuiAddress = AccountAddress::random().into();
+
+        let gas_object_info = |owner: SuiAddress| {
+            let object = object::Object::new_gas_with_balance_and_owner_for_testing(100, owner);
+            (
+                (owner, object.id()),
+                ObjectInfo {
+                    object_id: object.id(),
+                    version: object.version(),
+                    digest: object.digest(),
+                    type_: ObjectType::Struct(object.type_().unwrap().clone()),
+                    owner: Owner::AddressOwner(owner),
+                    previous_transaction: object.previous_transaction,
+                },
+            )
+        };
+        let other_object_info = |owner: SuiAddress| {
+            let object_id = ObjectID::random();
+            (
+                (owner, object_id),
+                ObjectInfo {
+                    object_id,
+                    version: SequenceNumber::new(),
+                    digest: ObjectDigest::random(),
+                    type_: ObjectType::Package,
+                    owner: Owner::AddressOwner(owner),
+                    previous_transaction: TransactionDigest::random(),
+                },
+            )
+        };
+        let new_owners = vec![
+            gas_object_info(address),
+            gas_object_info(address),
+            gas_object_info(address),
+            other_object_info(address),
+            other_object_info(address),
+            gas_object_info(other_address),
+        ];
+        index_store
+            .index_tx(
+                address,
+                vec![].into_iter(),
+                vec![].into_iter(),
+                vec![].into_iter(),
+                &TransactionEvents { data: vec![] },
+                ObjectIndexChanges {
+                    deleted_owners: vec![],
+                    deleted_dynamic_fields: vec![],
+                    new_owners,
+                    new_dynamic_fields: vec![],
+                },
+                &TransactionDigest::random(),
+                1234,
+                None,
+                false,
+            )
+            .await?;
+
+        assert_eq!(index_store.count_owned_objects(address, None)?, 5);
+        assert_eq!(
+            index_store.count_owned_objects(address, Some(SuiObjectDataFilter::gas_coin()))?,
+            3
+        );
+        assert_eq!(
+            index_store
+                .count_owned_objects(other_address, Some(SuiObjectDataFilter::gas_coin()))?,
+            1
+        );
+        assert_eq!(
+            index_store.count_owned_objects(AccountAddress::random().into(), None)?,
+            0
+        );
+
+        let gas_type = ObjectType::Struct(MoveObjectType::gas_coin());
+        assert_eq!(
+            index_store.owner_object_type_breakdown(address)?,
+            BTreeMap::from([(ObjectType::Package, 2), (gas_type.clone(), 3)])
+        );
+        assert_eq!(
+            index_store.owner_object_type_breakdown(other_address)?,
+            BTreeMap::from([(gas_type, 1)])
+        );
+        assert!(index_store
+            .owner_object_type_breakdown(AccountAddress::random().into())?
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_genesis_objects_in_chunks() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+        let new_owners: Vec<_> = (0..25)
+            .map(|_| {
+                let object_id = ObjectID::random();
+                (
+                    (address, object_id),
+                    ObjectInfo {
+                        object_id,
+                        version: SequenceNumber::new(),
+                        digest: ObjectDigest::random(),
+                        type_: ObjectType::Package,
+                        owner: Owner::AddressOwner(address),
+                        previous_transaction: TransactionDigest::random(),
+                    },
+                )
+            })
+            .collect();
+
+        let mut progress = vec![];
+        index_store.insert_genesis_objects_in_chunks(
+            ObjectIndexChanges {
+                deleted_owners: vec![],
+                deleted_dynamic_fields: vec![],
+                new_owners: new_owners.clone(),
+                new_dynamic_fields: vec![],
+            },
+            10,
+            |written, total| progress.push((written, total)),
+        )?;
+
+        assert_eq!(progress, vec![(10, 25), (20, 25), (25, 25)]);
+        for ((owner, object_id), info) in new_owners {
+            assert_eq!(
+                index_store.tables.owner_index.get(&(owner, object_id))?,
+                Some(info)
+            );
+        }
+        assert_eq!(index_store.count_owned_objects(address, None)?, 25);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_all_balances() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+        for _ in 0..3 {
+            let object = object::Object::new_gas_with_balance_and_owner_for_testing(100, address);
+            index_store.tables.coin_index.insert(
+                &(address, GAS::type_tag().to_string(), object.id()),
+                &CoinInfo::from_object(&object).unwrap(),
+            )?;
+        }
+        let expected = TotalBalance {
+            balance: 300,
+            num_coins: 3,
+        };
+
+        // pollute the per coin type cache with a wrong balance
+        let wrong = TotalBalance {
+            balance: 12345,
+            num_coins: 1,
+        };
+        index_store
+            .caches
+            .per_coin_type_balance
+            .get_with((address, GAS::type_tag()), async move { Ok(wrong) })
+            .await?;
+        assert_eq!(
+            index_store.get_balance(address, GAS::type_tag()).await?,
+            wrong
+        );
+
+        index_store.rebuild_all_balances(address).await?;
+
+        assert_eq!(
+            index_store.get_balance(address, GAS::type_tag()).await?,
+            expected
+        );
+        assert_eq!(
+            index_store
+                .get_all_balance(address)
+                .await?
+                .get(&GAS::type_tag()),
+            Some(&expected)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_each_balance() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+        let coin_types = [
+            "0x2::coin_a::COIN_A",
+            "0x2::coin_b::COIN_B",
+            "0x2::coin_c::COIN_C",
+        ];
+        for (i, coin_type) in coin_types.iter().enumerate() {
+            for _ in 0..=i {
+                let object =
+                    object::Object::new_gas_with_balance_and_owner_for_testing(100, address);
+                index_store.tables.coin_index.insert(
+                    &(address, coin_type.to_string(), object.id()),
+                    &CoinInfo::from_object(&object).unwrap(),
+                )?;
+            }
+        }
+        // coins of another owner are not included
+        let other: SuiAddress = AccountAddress::random().into();
+        let object = object::Object::new_gas_with_balance_and_owner_for_testing(100, other);
+        index_store.tables.coin_index.insert(
+            &(other, coin_types[0].to_string(), object.id()),
+            &CoinInfo::from_object(&object).unwrap(),
+        )?;
+
+        let mut streamed = vec![];
+        index_store.for_each_balance(address, |coin_type, balance| {
+            streamed.push((coin_type, balance))
+        })?;
+        assert_eq!(streamed.len(), coin_types.len());
+        for (i, (coin_type, balance)) in streamed.iter().enumerate() {
+            assert_eq!(*coin_type, parse_sui_type_tag(coin_types[i])?);
+            assert_eq!(
+                *balance,
+                TotalBalance {
+                    balance: 100 * (i as i128 + 1),
+                    num_coins: i as i64 + 1,
+                }
+            );
+        }
+
+        let all_balances = index_store.get_all_balance(address).await?;
+        assert_eq!(
+            streamed.into_iter().collect::<HashMap<_, _>>(),
+            *all_balances
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_report_table_sizes() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let tables = [
+            "owner_index",
+            "coin_index",
+            "transaction_order",
+            "event_order",
+        ];
+        let estimated_num_keys = |table: &str| {
+            index_store
+                .metrics
+                .table_estimated_num_keys
+                .with_label_values(&[table])
+                .get()
+        };
+        index_store.report_table_sizes()?;
+        for table in tables {
+            assert_eq!(estimated_num_keys(table), 0, "{table}");
+        }
+
+        let address: SuiAddress = AccountAddress::random().into();
+        let mut written_objects = BTreeMap::new();
+        let mut object_map = BTreeMap::new();
+        let mut new_objects = vec![];
+        for _ in 0..3 {
+            let object = object::Object::new_gas_with_balance_and_owner_for_testing(100, address);
+            new_objects.push((
+                (address, object.id()),
+                ObjectInfo {
+                    object_id: object.id(),
+                    version: object.version(),
+                    digest: object.digest(),
+                    type_: ObjectType::Struct(object.type_().unwrap().clone()),
+                    owner: Owner::AddressOwner(address),
+                    previous_transaction: object.previous_transaction,
+                },
+            ));
+            object_map.insert(object.id(), object.clone());
+            written_objects.insert(object.data.id(), object);
+        }
+        index_store
+            .index_tx(
+                address,
+                vec![].into_iter(),
+                vec![].into_iter(),
+                vec![].into_iter(),
+                &TransactionEvents {
+                    data: vec![Event::random_for_testing()],
+                },
+                ObjectIndexChanges {
+                    deleted_owners: vec![],
+                    deleted_dynamic_fields: vec![],
+                    new_owners: new_objects,
+                    new_dynamic_fields: vec![],
+                },
+                &TransactionDigest::random(),
+                1234,
+                Some((object_map, written_objects)),
+                false,
+            )
+            .await?;
+
+        index_store.report_table_sizes()?;
+        for table in tables {
+            assert!(estimated_num_keys(table) > 0, "{table}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disabled_event_index() -> anyhow::Result<()> {
+        let enabled = EventIndexType::all()
+            .into_iter()
+            .filter(|index| *index != EventIndexType::Time);
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        )
+        .with_enabled_event_indexes(enabled);
+        assert!(!index_store.is_event_index_enabled(EventIndexType::Time));
+
+        let event = Event::random_for_testing();
+        index_store
+            .index_tx(
+                event.sender,
+                vec![].into_iter(),
+                vec![].into_iter(),
+                vec![].into_iter(),
+                &TransactionEvents {
+                    data: vec![event.clone()],
+                },
+                ObjectIndexChanges {
+                    deleted_owners: vec![],
+                    deleted_dynamic_fields: vec![],
+                    new_owners: vec![],
+                    new_dynamic_fields: vec![],
+                },
+                &TransactionDigest::random(),
+                1234,
+                None,
+                false,
+            )
+            .await?;
+
+        // the disabled index is not written to, and cannot be queried
+        assert!(index_store.tables.event_by_time.is_empty());
+        assert!(matches!(
+            index_store.event_iterator(
+                0,
+                u64::MAX,
+                0,
+                0,
+                10,
+                false,
+                EventSource::All,
+                EndBound::Inclusive,
+            ),
+            Err(SuiError::UnsupportedFeatureError { .. })
+        ));
+
+        // other event indexes are still written
+        assert_eq!(index_store.all_events(0, 0, 10, false)?.len(), 1);
+        assert_eq!(
+            index_store
+                .events_by_sender(&event.sender, 0, 0, 10, false)?
+                .len(),
+            1
+        );
+        assert_eq!(
+            index_store
+                .events_by_move_event_struct_name(&event.type_, 0, 0, 10, false)?
+                .len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_iterator_excluding_system_txs() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+
+        let system_tx_digest = TransactionDigest::random();
+        let user_tx_digest = TransactionDigest::random();
+        for (digest, timestamp_ms, is_system_tx) in [
+            (system_tx_digest, 1000, true),
+            (user_tx_digest, 2000, false),
+        ] {
+            let event = Event::random_for_testing();
+            index_store
+                .index_tx(
+                    event.sender,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &TransactionEvents { data: vec![event] },
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &digest,
+                    timestamp_ms,
+                    None,
+                    is_system_tx,
+                )
+                .await?;
+        }
+
+        // all events are returned by default
+        let all_events = index_store.event_iterator(
+            0,
+            u64::MAX,
+            0,
+            0,
+            10,
+            false,
+            EventSource::All,
+            EndBound::Inclusive,
+        )?;
+        assert_eq!(
+            all_events
+                .iter()
+                .map(|(_, tx_digest, _, _)| *tx_digest)
+                .collect::<Vec<_>>(),
+            vec![system_tx_digest, user_tx_digest]
+        );
+
+        // events of system transactions are filtered out on request, in both directions
+        for (tx_seq, descending) in [(0, false), (TxSequenceNumber::MAX, true)] {
+            let user_events = index_store.event_iterator(
+                0,
+                u64::MAX,
+                tx_seq,
+                0,
+                10,
+                descending,
+                EventSource::UserOnly,
+                EndBound::Inclusive,
+            )?;
+            assert_eq!(user_events.len(), 1);
+            assert_eq!(user_events[0].1, user_tx_digest);
+            assert_eq!(user_events[0].3, 2000);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_iterator_end_time_bound() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+
+        for timestamp_ms in [1000, 2000, 3000] {
+            let event = Event::random_for_testing();
+            index_store
+                .index_tx(
+                    event.sender,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &TransactionEvents { data: vec![event] },
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &TransactionDigest::random(),
+                    timestamp_ms,
+                    None,
+                    false,
+                )
+                .await?;
+        }
+
+        let timestamps = |tx_seq, descending, end_bound| -> anyhow::Result<Vec<u64>> {
+            Ok(index_store
+                .event_iterator(
+                    1000,
+                    2000,
+                    tx_seq,
+                    0,
+                    10,
+                    descending,
+                    EventSource::All,
+                    end_bound,
+                )?
+                .into_iter()
+                .map(|(_, _, _, timestamp_ms)| timestamp_ms)
+                .collect())
+        };
+        // the event exactly at the end time is only returned when the end is inclusive
+        assert_eq!(timestamps(0, false, EndBound::Inclusive)?, vec![1000, 2000]);
+        assert_eq!(timestamps(0, false, EndBound::Exclusive)?, vec![1000]);
+        assert_eq!(
+            timestamps(TxSequenceNumber::MAX, true, EndBound::Inclusive)?,
+            vec![2000, 1000]
+        );
+        assert_eq!(
+            timestamps(TxSequenceNumber::MAX, true, EndBound::Exclusive)?,
+            vec![1000]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_owner_coins() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let owner: SuiAddress = AccountAddress::random().into();
+        let other: SuiAddress = AccountAddress::random().into();
+        let coin = |balance| CoinInfo {
+            version: SequenceNumber::new(),
+            digest: ObjectDigest::random(),
+            balance,
+            previous_transaction: TransactionDigest::random(),
+        };
+
+        let gas_type = "0x2::sui::SUI".to_string();
+        let other_type = "0x2::test::TEST".to_string();
+        let mut expected = vec![];
+        for (coin_type, balance) in [
+            (&gas_type, 10),
+            (&gas_type, 30),
+            (&gas_type, 20),
+            (&other_type, 5),
+            (&other_type, 5),
+            (&other_type, 50),
+        ] {
+            let id = ObjectID::random();
+            let info = coin(balance);
+            index_store
+                .tables
+                .coin_index
+                .insert(&(owner, coin_type.clone(), id), &info)?;
+            expected.push((coin_type.clone(), id, info));
+        }
+        // coins of other owners are not exported
+        index_store
+            .tables
+            .coin_index
+            .insert(&(other, gas_type.clone(), ObjectID::random()), &coin(100))?;
+
+        let exported = index_store.export_owner_coins(owner)?;
+        expected.sort_by_key(|(coin_type, id, info)| {
+            (coin_type.clone(), std::cmp::Reverse(info.balance), *id)
+        });
+        assert_eq!(exported, expected);
+        let balances: Vec<_> = exported.iter().map(|(_, _, info)| info.balance).collect();
+        assert_eq!(balances, vec![30, 20, 10, 50, 5, 5]);
+        assert_eq!(index_store.export_owner_coins(other)?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_distinct_event_senders() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        assert!(index_store.distinct_event_senders(10)?.is_empty());
+
+        let mut senders: Vec<SuiAddress> =
+            (0..3).map(|_| AccountAddress::random().into()).collect();
+        for (i, sender) in senders.iter().cycle().take(9).enumerate() {
+            let event = Event {
+                sender: *sender,
+                ..Event::random_for_testing()
+            };
+            index_store
+                .index_tx(
+                    *sender,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &TransactionEvents {
+                        data: vec![event.clone(), event],
+                    },
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &TransactionDigest::random(),
+                    i as u64,
+                    None,
+                    false,
+                )
+                .await?;
+        }
+
+        senders.sort();
+        assert_eq!(index_store.distinct_event_senders(10)?, senders);
+        assert_eq!(index_store.distinct_event_senders(2)?, senders[..2]);
+        assert!(index_store.distinct_event_senders(0)?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_owner_objects_limit() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+
+        assert!(index_store
+            .get_owner_objects(address, None, MAX_GET_OWNED_OBJECT_SIZE + 1, None)?
+            .is_empty());
+        assert!(matches!(
+            index_store.get_owner_objects(address, None, MAX_GET_OWNED_OBJECT_SIZE + 2, None),
+            Err(SuiError::UserInputError {
+                error: UserInputError::SizeLimitExceeded { .. }
+            })
+        ));
+
+        let index_store = index_store.with_max_get_owned_object_size(10);
+        assert!(index_store
+            .get_owner_objects(address, None, 11, None)?
+            .is_empty());
+        assert!(index_store
+            .get_owner_objects(address, None, 12, None)
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_owner_objects_max_page_with_look_ahead() -> anyhow::Result<()> {
+        let page_size = 4;
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        )
+        .with_max_get_owned_object_size(page_size);
+        let address: SuiAddress = AccountAddress::random().into();
+        let mut new_owners: Vec<_> = (0..page_size + 2)
+            .map(|_| {
+                let object_id = ObjectID::random();
+                (
+                    (address, object_id),
+                    ObjectInfo {
+                        object_id,
+                        version: SequenceNumber::new(),
+                        digest: ObjectDigest::random(),
+                        type_: ObjectType::Package,
+                        owner: Owner::AddressOwner(address),
+                        previous_transaction: TransactionDigest::random(),
+                    },
+                )
+            })
+            .collect();
+        new_owners.sort_by_key(|((_, object_id), _)| *object_id);
+        index_store.insert_genesis_objects(ObjectIndexChanges {
+            deleted_owners: vec![],
+            deleted_dynamic_fields: vec![],
+            new_owners: new_owners.clone(),
+            new_dynamic_fields: vec![],
+        })?;
+
+        // Mirrors `IndexerApi::get_owned_objects`, which requests a maximum-size page plus one
+        // object to tell whether there is a next page.
+        let first_page = index_store.get_owner_objects(address, None, page_size + 1, None)?;
+        assert_eq!(first_page.len(), page_size + 1);
+        assert_eq!(
+            first_page[page_size - 1].object_id,
+            new_owners[page_size - 1].0 .1
+        );
+
+        let cursor = first_page[page_size - 1].object_id;
+        let second_page =
+            index_store.get_owner_objects(address, Some(cursor), page_size + 1, None)?;
+        let ids: Vec<_> = second_page.iter().map(|info| info.object_id).collect();
+        assert_eq!(
+            ids,
+            new_owners[page_size..]
+                .iter()
+                .map(|((_, object_id), _)| *object_id)
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sequence_number_accessors() -> anyhow::Result<()> {
+        let path = tempdir()?.into_path();
+        let index_store = IndexStore::new(path.clone(), &Registry::default(), Some(128), false);
+        let address: SuiAddress = AccountAddress::random().into();
+
+        assert_eq!(index_store.peek_next_sequence_number(), 0);
+        assert_eq!(index_store.last_assigned_sequence_number(), None);
+
+        for expected in 0..3 {
+            let sequence = index_store
+                .index_tx(
+                    address,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &TransactionEvents { data: vec![] },
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &TransactionDigest::random(),
+                    1234,
+                    None,
+                    false,
+                )
+                .await?;
+            assert_eq!(sequence, expected);
+            assert_eq!(index_store.last_assigned_sequence_number(), Some(expected));
+            assert_eq!(index_store.peek_next_sequence_number(), expected + 1);
+        }
+        index_store.flush()?;
+        drop(index_store);
+
+        // the values are restored from the db on reopen
+        let index_store = IndexStore::new(path, &Registry::default(), Some(128), false);
+        assert_eq!(index_store.last_assigned_sequence_number(), Some(2));
+        assert_eq!(index_store.peek_next_sequence_number(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_count_from_addr() -> anyhow::Result<()> {
+        let index_store = IndexStore::new(
+            tempdir()?.into_path(),
+            &Registry::default(),
+            Some(128),
+            false,
+        );
+        let address: SuiAddress = AccountAddress::random().into();
+        let other_address: SuiAddress = AccountAddress::random().into();
+        for sender in [address, address, other_address, address] {
+            index_store
+                .index_tx(
+                    sender,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &TransactionEvents { data: vec![] },
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &TransactionDigest::random(),
+                    1234,
+                    None,
+                    false,
+                )
+                .await?;
+        }
+
+        assert_eq!(index_store.get_transaction_count_from(address)?, 3);
+        assert_eq!(index_store.get_transaction_count_from(other_address)?, 1);
+        assert_eq!(
+            index_store.get_transaction_count_from(AccountAddress::random().into())?,
+            0
+        );
+
+        // Simulate transactions indexed before the count index existed
+        index_store.tables.tx_count_from_addr.remove(&address)?;
+        assert_eq!(index_store.get_transaction_count_from(address)?, 0);
+        assert_eq!(index_store.recompute_tx_count(address)?, 3);
+        assert_eq!(index_store.get_transaction_count_from(address)?, 3);
+        assert_eq!(index_store.recompute_tx_count(other_address)?, 1);
+        Ok(())
+    }
 }
@@ -56,12 +56,29 @@ pub enum StorageFormat {
 pub enum FileCompression {
     None = 0,
     Zstd,
+    /// Compresses less than zstd, but is much faster to decompress
+    Lz4,
 }
 
+/// The zstd compression level used unless another one is configured
+pub const DEFAULT_ZSTD_COMPRESSION_LEVEL: i32 = 1;
+
 impl FileCompression {
     pub fn zstd_compress<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<()> {
-        // TODO: Add zstd compression level as function argument
-        let mut encoder = zstd::Encoder::new(writer, 1)?;
+        Self::zstd_compress_with_level(reader, writer, DEFAULT_ZSTD_COMPRESSION_LEVEL)
+    }
+    pub fn zstd_compress_with_level<R: Read, W: Write>(
+        reader: &mut R,
+        writer: &mut W,
+        level: i32,
+    ) -> io::Result<()> {
+        let mut encoder = zstd::Encoder::new(writer, level)?;
+        io::copy(reader, &mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+    pub fn lz4_compress<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<()> {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(writer);
         io::copy(reader, &mut encoder)?;
         encoder.finish()?;
         Ok(())
@@ -75,6 +92,13 @@ impl FileCompression {
                 Self::zstd_compress(&mut input, &mut output)?;
                 fs::rename(tmp_file_name, source)?;
             }
+            FileCompression::Lz4 => {
+                let mut input = File::open(source)?;
+                let tmp_file_name = source.with_extension("tmp");
+                let mut output = File::create(&tmp_file_name)?;
+                Self::lz4_compress(&mut input, &mut output)?;
+                fs::rename(tmp_file_name, source)?;
+            }
             FileCompression::None => {}
         }
         Ok(())
@@ -83,6 +107,7 @@ impl FileCompression {
         let file = File::open(source)?;
         let res: Box<dyn Read> = match self {
             FileCompression::Zstd => Box::new(zstd::stream::Decoder::new(file)?),
+            FileCompression::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(file)),
             FileCompression::None => Box::new(BufReader::new(file)),
         };
         Ok(res)
@@ -90,6 +115,7 @@ impl FileCompression {
     pub fn bytes_decompress(&self, bytes: Bytes) -> Result<Box<dyn Read>> {
         let res: Box<dyn Read> = match self {
             FileCompression::Zstd => Box::new(zstd::stream::Decoder::new(bytes.reader())?),
+            FileCompression::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(bytes.reader())),
             FileCompression::None => Box::new(BufReader::new(bytes.reader())),
         };
         Ok(res)
@@ -102,6 +128,38 @@ pub fn compute_sha3_checksum_for_bytes(bytes: Bytes) -> Result<[u8; 32]> {
     Ok(hasher.finalize().digest)
 }
 
+/// Sha3Writer wraps a writer and feeds every byte successfully written to it into a sha3 hasher,
+/// so the digest of a file can be computed during the write pass instead of reading it back.
+pub struct Sha3Writer<W: Write> {
+    inner: W,
+    hasher: Sha3_256,
+}
+
+impl<W: Write> Sha3Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Sha3Writer {
+            inner,
+            hasher: Sha3_256::default(),
+        }
+    }
+    /// Consumes the writer returning the inner writer and the sha3 digest of all bytes written.
+    /// The inner writer is not flushed.
+    pub fn finalize(self) -> (W, [u8; 32]) {
+        (self.inner, self.hasher.finalize().digest)
+    }
+}
+
+impl<W: Write> Write for Sha3Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 pub fn compute_sha3_checksum_for_file(file: &mut File) -> Result<[u8; 32]> {
     let mut hasher = Sha3_256::default();
     io::copy(file, &mut hasher)?;
@@ -114,6 +172,16 @@ pub fn compute_sha3_checksum(source: &std::path::Path) -> Result<[u8; 32]> {
 }
 
 pub fn compress<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> Result<()> {
+    compress_with_level(reader, writer, DEFAULT_ZSTD_COMPRESSION_LEVEL)
+}
+
+/// Compresses the blob file in `reader` into `writer` with the compression named in its header,
+/// using the given zstd compression `level`. The level is ignored by other compressions
+pub fn compress_with_level<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    level: i32,
+) -> Result<()> {
     let magic = reader.read_u32::<BigEndian>()?;
     writer.write_u32::<BigEndian>(magic)?;
     let storage_format = reader.read_u8()?;
@@ -122,7 +190,10 @@ pub fn compress<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> Result<()>
     writer.write_u8(file_compression.into())?;
     match file_compression {
         FileCompression::Zstd => {
-            FileCompression::zstd_compress(reader, writer)?;
+            FileCompression::zstd_compress_with_level(reader, writer, level)?;
+        }
+        FileCompression::Lz4 => {
+            FileCompression::lz4_compress(reader, writer)?;
         }
         FileCompression::None => {}
     }
@@ -145,6 +216,7 @@ pub fn read<R: Read + 'static>(
         let file_compression = FileCompression::try_from(reader.read_u8()?)?;
         let reader: Box<dyn Read> = match file_compression {
             FileCompression::Zstd => Box::new(zstd::stream::Decoder::new(reader)?),
+            FileCompression::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(reader)),
             FileCompression::None => Box::new(BufReader::new(reader)),
         };
         Ok((reader, storage_format))
@@ -198,6 +198,7 @@ impl ValidatorConfigBuilder {
                 .unwrap(),
             consensus_config: Some(consensus_config),
             remove_deprecated_tables: false,
+            validate_index_transaction_order: false,
             enable_index_processing: default_enable_index_processing(),
             genesis: sui_config::node::Genesis::new(genesis),
             grpc_load_shed: None,
@@ -467,6 +468,7 @@ impl FullnodeConfigBuilder {
             json_rpc_address: self.json_rpc_address.unwrap_or(json_rpc_address),
             consensus_config: None,
             remove_deprecated_tables: false,
+            validate_index_transaction_order: false,
             enable_index_processing: default_enable_index_processing(),
             genesis: self.genesis.unwrap_or(sui_config::node::Genesis::new(
                 network_config.genesis.clone(),
@@ -6,8 +6,9 @@ use crate::{
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     download_db_snapshot, download_formal_snapshot, dump_checkpoints_from_archive,
     get_latest_available_epoch, get_object, get_transaction_block, make_clients, pkg_dump,
-    restore_from_db_checkpoint, verify_archive, verify_archive_by_checksum, ConciseObjectOutput,
-    GroupedObjectOutput, SnapshotVerifyMode, VerboseObjectOutput,
+    restore_from_db_checkpoint, verify_archive, verify_archive_by_checksum,
+    verify_archive_by_integrity, ConciseObjectOutput, GroupedObjectOutput, SnapshotVerifyMode,
+    VerboseObjectOutput,
 };
 use anyhow::Result;
 use futures::{future::join_all, StreamExt};
@@ -165,6 +166,16 @@ pub enum ToolCommand {
         download_concurrency: usize,
     },
 
+    /// Tool to verify every file in the archive store against its checksum in the MANIFEST, and
+    /// report all mismatched, missing and invalid files
+    #[command(name = "verify-archive-integrity")]
+    VerifyArchiveIntegrity {
+        #[command(flatten)]
+        object_store_config: ObjectStoreConfig,
+        #[arg(default_value_t = 5)]
+        download_concurrency: usize,
+    },
+
     /// Tool to print archive contents in checkpoint range
     #[command(name = "dump-archive")]
     DumpArchiveByChecksum {
@@ -1115,6 +1126,12 @@ impl ToolCommand {
             } => {
                 verify_archive_by_checksum(object_store_config, download_concurrency).await?;
             }
+            ToolCommand::VerifyArchiveIntegrity {
+                object_store_config,
+                download_concurrency,
+            } => {
+                verify_archive_by_integrity(object_store_config, download_concurrency).await?;
+            }
             ToolCommand::DumpArchiveByChecksum {
                 object_store_config,
                 start,
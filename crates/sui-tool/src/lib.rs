@@ -46,8 +46,10 @@ use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
-use sui_archival::{verify_archive_with_checksums, verify_archive_with_genesis_config};
-use sui_config::node::ArchiveReaderConfig;
+use sui_archival::{
+    verify_archive_integrity, verify_archive_with_checksums, verify_archive_with_genesis_config,
+};
+use sui_config::node::{ArchiveReaderConfig, ArchiveRetryConfig};
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::AuthorityStore;
@@ -689,6 +691,8 @@ fn start_summary_sync(
             remote_store_config: archive_store_config,
             download_concurrency: NonZeroUsize::new(num_parallel_downloads).unwrap(),
             use_for_pruning_watermark: false,
+            verify_checksums: true,
+            retry_config: ArchiveRetryConfig::default(),
         };
         let metrics = ArchiveReaderMetrics::new(&Registry::default());
         let archive_reader = ArchiveReader::new(config, &metrics)?;
@@ -696,8 +700,8 @@ fn start_summary_sync(
         let manifest = archive_reader.get_manifest().await?;
 
         let end_of_epoch_checkpoint_seq_nums = (0..=epoch)
-            .map(|e| manifest.next_checkpoint_after_epoch(e) - 1)
-            .collect::<Vec<_>>();
+            .map(|e| manifest.next_checkpoint_after_epoch(e).map(|c| c - 1))
+            .collect::<Result<Vec<_>>>()?;
         let last_checkpoint = end_of_epoch_checkpoint_seq_nums
             .last()
             .expect("Expected at least one checkpoint");
@@ -1214,6 +1218,8 @@ pub async fn dump_checkpoints_from_archive(
         remote_store_config,
         download_concurrency: NonZeroUsize::new(1).unwrap(),
         use_for_pruning_watermark: false,
+        verify_checksums: false,
+        retry_config: ArchiveRetryConfig::default(),
     };
     let store = SharedInMemoryStore::default();
     let archive_reader = ArchiveReader::new(config, &metrics)?;
@@ -1258,3 +1264,28 @@ pub async fn verify_archive_by_checksum(
 ) -> Result<()> {
     verify_archive_with_checksums(remote_store_config, concurrency).await
 }
+
+pub async fn verify_archive_by_integrity(
+    remote_store_config: ObjectStoreConfig,
+    concurrency: usize,
+) -> Result<()> {
+    let report = verify_archive_integrity(remote_store_config.make()?, concurrency).await?;
+    for (kind, files) in [
+        ("Mismatched checksum", &report.mismatched_files),
+        ("Missing", &report.missing_files),
+        ("Invalid contents", &report.invalid_files),
+    ] {
+        for file in files {
+            println!("{}: {}", kind, file.file_path());
+        }
+    }
+    if !report.is_valid() {
+        return Err(anyhow!(
+            "{} of {} archive files failed verification",
+            report.mismatched_files.len() + report.missing_files.len() + report.invalid_files.len(),
+            report.num_files
+        ));
+    }
+    println!("All {} files are valid", report.num_files);
+    Ok(())
+}
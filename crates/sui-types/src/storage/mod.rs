@@ -175,6 +175,22 @@ pub trait ChildObjectResolver {
         child_version_upper_bound: SequenceNumber,
     ) -> SuiResult<Option<Object>>;
 
+    /// Reads several children of `parent` at once, with the same requirements as
+    /// `read_child_object`. Returns one result per child, in the order of `children`. Resolvers
+    /// which can fetch objects in batches should override this, by default the children are read
+    /// one at a time.
+    fn read_child_objects(
+        &self,
+        parent: &ObjectID,
+        children: &[ObjectID],
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Vec<Option<Object>>> {
+        children
+            .iter()
+            .map(|child| self.read_child_object(parent, child, child_version_upper_bound))
+            .collect()
+    }
+
     /// `receiving_object_id` must have an `AddressOwner` ownership equal to `owner`.
     /// `get_object_received_at_version` must be the exact version at which the object will be received,
     /// and it cannot have been previously received at that version. NB: An object not existing at
@@ -377,6 +393,19 @@ impl<S: ChildObjectResolver> ChildObjectResolver for std::sync::Arc<S> {
             child_version_upper_bound,
         )
     }
+    fn read_child_objects(
+        &self,
+        parent: &ObjectID,
+        children: &[ObjectID],
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Vec<Option<Object>>> {
+        ChildObjectResolver::read_child_objects(
+            self.as_ref(),
+            parent,
+            children,
+            child_version_upper_bound,
+        )
+    }
     fn get_object_received_at_version(
         &self,
         owner: &ObjectID,
@@ -403,6 +432,14 @@ impl<S: ChildObjectResolver> ChildObjectResolver for &S {
     ) -> SuiResult<Option<Object>> {
         ChildObjectResolver::read_child_object(*self, parent, child, child_version_upper_bound)
     }
+    fn read_child_objects(
+        &self,
+        parent: &ObjectID,
+        children: &[ObjectID],
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Vec<Option<Object>>> {
+        ChildObjectResolver::read_child_objects(*self, parent, children, child_version_upper_bound)
+    }
     fn get_object_received_at_version(
         &self,
         owner: &ObjectID,
@@ -429,6 +466,14 @@ impl<S: ChildObjectResolver> ChildObjectResolver for &mut S {
     ) -> SuiResult<Option<Object>> {
         ChildObjectResolver::read_child_object(*self, parent, child, child_version_upper_bound)
     }
+    fn read_child_objects(
+        &self,
+        parent: &ObjectID,
+        children: &[ObjectID],
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Vec<Option<Object>>> {
+        ChildObjectResolver::read_child_objects(*self, parent, children, child_version_upper_bound)
+    }
     fn get_object_received_at_version(
         &self,
         owner: &ObjectID,
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use ipnetwork::IpNetwork;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::path::PathBuf;
@@ -82,6 +83,18 @@ pub struct RemoteFirewallConfig {
     /// will trigger dead mans switch to drain any firewalls
     #[serde(default = "default_drain_timeout")]
     pub drain_timeout_secs: u64,
+    /// Maximum number of attempts made for a single blocklist
+    /// delegation request before giving up on transient failures
+    #[serde(default = "default_max_block_attempts")]
+    pub max_block_attempts: u32,
+    /// Delay in ms before retrying a failed blocklist delegation
+    /// request, doubled after each failed attempt
+    #[serde(default = "default_block_retry_base_delay_ms")]
+    pub block_retry_base_delay_ms: u64,
+    /// Upper bound in ms on the time spent on a single blocklist
+    /// delegation request, retries included
+    #[serde(default = "default_max_block_duration_ms")]
+    pub max_block_duration_ms: u64,
 }
 
 fn default_drain_path() -> PathBuf {
@@ -92,6 +105,18 @@ fn default_drain_timeout() -> u64 {
     TRAFFIC_SINK_TIMEOUT_SEC
 }
 
+fn default_max_block_attempts() -> u32 {
+    3
+}
+
+fn default_block_retry_base_delay_ms() -> u64 {
+    100
+}
+
+fn default_max_block_duration_ms() -> u64 {
+    5_000
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -204,6 +229,10 @@ pub struct PolicyConfig {
     pub spam_sample_rate: Weight,
     #[serde(default = "default_dry_run")]
     pub dry_run: bool,
+    /// IPs or CIDR ranges of trusted clients, e.g. internal proxies and health
+    /// checkers. They are never blocked, and their traffic is not tallied.
+    #[serde(default)]
+    pub allowlisted_ips: Vec<IpNetwork>,
 }
 
 impl Default for PolicyConfig {
@@ -217,6 +246,7 @@ impl Default for PolicyConfig {
             channel_capacity: 100,
             spam_sample_rate: default_spam_sample_rate(),
             dry_run: default_dry_run(),
+            allowlisted_ips: vec![],
         }
     }
 }
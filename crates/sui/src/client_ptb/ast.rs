@@ -444,6 +444,7 @@ impl<'a> fmt::Display for TyDisplay<'a> {
             Bool => write!(f, "bool"),
             Signer => write!(f, "signer"),
             Vector(ty) => write!(f, "vector<{}>", TyDisplay(ty)),
+            TypeParameter(name) => write!(f, "{name}"),
             Struct(ParsedStructType {
                 fq_name:
                     ParsedFqName {
@@ -9,6 +9,7 @@ use crate::{
 use anyhow::{anyhow, bail, Result};
 use move_core_types::{
     account_address::AccountAddress,
+    identifier,
     u256::{U256FromStrError, U256},
 };
 use num_bigint::BigUint;
@@ -74,6 +75,28 @@ impl<Extra: ParsableValue> ParsedValue<Extra> {
     pub fn parse(s: &str) -> Result<ParsedValue<Extra>> {
         parse(s, |parser| parser.parse_value())
     }
+
+    /// Like `parse`, but rejects numbers without an explicit width suffix (e.g. `0` instead of
+    /// `0u8`), anywhere in the value, rather than leaving their width to be inferred.
+    pub fn parse_strict(s: &str) -> Result<ParsedValue<Extra>> {
+        let value = Self::parse(s)?;
+        if let Some(u) = value.find_inferred_num() {
+            bail!(
+                "Number {u} is missing an explicit type suffix, e.g. {u}u8 or {u}u64, in value: {s}"
+            )
+        }
+        Ok(value)
+    }
+
+    fn find_inferred_num(&self) -> Option<&move_core_types::u256::U256> {
+        match self {
+            ParsedValue::InferredNum(u) => Some(u),
+            ParsedValue::Vector(values) | ParsedValue::Struct(values) => {
+                values.iter().find_map(|value| value.find_inferred_num())
+            }
+            _ => None,
+        }
+    }
 }
 
 fn parse<'a, Tok: Token, R>(
@@ -213,6 +236,15 @@ impl<'a, I: Iterator<Item = (TypeToken, &'a str)>> Parser<'a, TypeToken, I> {
                 ParsedType::Vector(Box::new(ty))
             }
 
+            (TypeToken::Ident, contents)
+                if !matches!(self.peek_tok(), Some(TypeToken::ColonColon)) =>
+            {
+                if !identifier::is_valid(contents) {
+                    bail!("Invalid type parameter name: {contents}")
+                }
+                ParsedType::TypeParameter(contents.to_owned())
+            }
+
             (tok @ (TypeToken::Ident | TypeToken::AddressIdent), contents) => {
                 let fq_name = self.parse_fq_name_impl(tok, contents)?;
                 let type_args = match self.peek_tok() {
@@ -319,6 +351,15 @@ impl<'a, I: Iterator<Item = (ValueToken, &'a str)>> Parser<'a, ValueToken, I> {
 
             ValueToken::AtSign => ParsedValue::Address(self.parse_address()?),
 
+            ValueToken::Minus
+                if matches!(
+                    self.peek_tok(),
+                    Some(ValueToken::Number | ValueToken::NumberTyped)
+                ) =>
+            {
+                bail!("negative integer literals are not supported in Move; values are unsigned")
+            }
+
             ValueToken::Ident if contents == "vector" => {
                 self.advance(ValueToken::LBracket)?;
                 let values = self.parse_list(
@@ -465,7 +506,12 @@ mod tests {
         types::{ParsedStructType, ParsedType},
         values::ParsedValue,
     };
-    use move_core_types::{account_address::AccountAddress, identifier::Identifier, u256::U256};
+    use move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        runtime_value::{MoveStructLayout, MoveTypeLayout},
+        u256::U256,
+    };
     use proptest::prelude::*;
     use proptest::proptest;
 
@@ -613,6 +659,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tests_parse_value_negative_integer() {
+        for s in &["-3", "-0u8"] {
+            let err = ParsedValue::<()>::parse(s).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "negative integer literals are not supported in Move; values are unsigned",
+                "Unexpected error when parsing: {}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn tests_parse_value_strict() {
+        use ParsedValue as V;
+        let cases: &[(&str, V)] = &[
+            ("0u8", V::U8(0)),
+            ("1_000u64", V::U64(1_000)),
+            ("0x1u256", V::U256(U256::from(1u64))),
+            ("true", V::Bool(true)),
+            ("vector[0u16, 1u16]", V::Vector(vec![V::U16(0), V::U16(1)])),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(&ParsedValue::parse_strict(s).unwrap(), expected)
+        }
+
+        for s in &[
+            "0",
+            "1_000",
+            "0x1",
+            "vector[0u8, 1]",
+            "struct(0u8, struct(2))",
+        ] {
+            assert!(ParsedValue::<()>::parse(s).is_ok());
+            let err = ParsedValue::<()>::parse_strict(s).unwrap_err();
+            assert!(
+                err.to_string().contains("missing an explicit type suffix"),
+                "Unexpected error when strictly parsing {}: {}",
+                s,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn tests_value_bcs_roundtrip() {
+        use MoveTypeLayout as L;
+        let cases: Vec<(&str, L)> = vec![
+            ("0u8", L::U8),
+            ("0xF__FF__Eu16", L::U16),
+            ("0xFFF_FF__FF_Cu32", L::U32),
+            ("18446744073709551615u64", L::U64),
+            ("340282366920938463463374607431768211455u128", L::U128),
+            ("255u256", L::U256),
+            ("true", L::Bool),
+            ("@0x54afa3526", L::Address),
+            ("x\"deadbeef\"", L::Vector(Box::new(L::U8))),
+            ("vector[]", L::Vector(Box::new(L::U64))),
+            ("vector[1u64, 2u64]", L::Vector(Box::new(L::U64))),
+            (
+                "struct(1u8, vector[true, false], struct(@0x1, b\"hello\"))",
+                L::Struct(MoveStructLayout::new(vec![
+                    L::U8,
+                    L::Vector(Box::new(L::Bool)),
+                    L::Struct(MoveStructLayout::new(vec![
+                        L::Address,
+                        L::Vector(Box::new(L::U8)),
+                    ])),
+                ])),
+            ),
+        ];
+
+        for (s, layout) in cases {
+            let value = ParsedValue::<()>::parse(s).unwrap();
+            let bytes = value
+                .clone()
+                .into_concrete_value(&|_| None)
+                .unwrap()
+                .simple_serialize()
+                .unwrap();
+            assert_eq!(
+                ParsedValue::from_bcs(&bytes, &layout).unwrap(),
+                value,
+                "Failed to round-trip: {}",
+                s
+            );
+        }
+
+        // Bytes that do not match the layout are rejected
+        assert!(ParsedValue::<()>::from_bcs(&[0, 1], &L::U64).is_err());
+    }
+
     #[test]
     fn test_parse_type_negative() {
         for s in &[
@@ -633,6 +772,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_type_is_concrete() {
+        for s in &[
+            "u64",
+            "vector<address>",
+            "0x2::coin::Coin<0x2::sui::SUI>",
+            "0x1::table::Table<u64, vector<0x2::coin::Coin<0x2::sui::SUI>>>",
+        ] {
+            let ty = ParsedType::parse(s).unwrap();
+            assert!(ty.is_concrete(), "{s} should be concrete");
+            assert!(ty.into_type_tag(&|_| None).is_ok());
+        }
+
+        for s in &[
+            "T",
+            "vector<T>",
+            "0x2::coin::Coin<T>",
+            "0x1::table::Table<u64, vector<0x2::coin::Coin<T0>>>",
+        ] {
+            let ty = ParsedType::parse(s).unwrap();
+            assert!(!ty.is_concrete(), "{s} should not be concrete");
+            assert!(ty.into_type_tag(&|_| None).is_err());
+        }
+        assert_eq!(
+            ParsedType::parse("T").unwrap(),
+            ParsedType::TypeParameter("T".to_owned())
+        );
+    }
+
     #[test]
     fn test_parse_struct_negative() {
         for s in &[
@@ -54,6 +54,9 @@ pub enum ParsedType {
     Signer,
     Vector(Box<ParsedType>),
     Struct(ParsedStructType),
+    /// A type parameter, e.g. `T`, which must be substituted before the type can be converted to
+    /// a `TypeTag`.
+    TypeParameter(String),
 }
 
 impl Display for TypeToken {
@@ -171,6 +174,25 @@ impl ParsedStructType {
 }
 
 impl ParsedType {
+    /// Whether the type is free of type parameters, including in any vector element or struct
+    /// type argument, so that it can be instantiated.
+    pub fn is_concrete(&self) -> bool {
+        match self {
+            ParsedType::U8
+            | ParsedType::U16
+            | ParsedType::U32
+            | ParsedType::U64
+            | ParsedType::U128
+            | ParsedType::U256
+            | ParsedType::Bool
+            | ParsedType::Address
+            | ParsedType::Signer => true,
+            ParsedType::Vector(inner) => inner.is_concrete(),
+            ParsedType::Struct(s) => s.type_args.iter().all(ParsedType::is_concrete),
+            ParsedType::TypeParameter(_) => false,
+        }
+    }
+
     pub fn into_type_tag(
         self,
         mapping: &impl Fn(&str) -> Option<AccountAddress>,
@@ -187,6 +209,9 @@ impl ParsedType {
             ParsedType::Signer => TypeTag::Signer,
             ParsedType::Vector(inner) => TypeTag::Vector(Box::new(inner.into_type_tag(mapping)?)),
             ParsedType::Struct(s) => TypeTag::Struct(Box::new(s.into_struct_tag(mapping)?)),
+            ParsedType::TypeParameter(name) => {
+                bail!("Type parameter {name} must be instantiated with a concrete type")
+            }
         })
     }
 }
@@ -2,14 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    address::ParsedAddress,
-    parser::{Parser, Token},
+    address::{NumericalAddress, ParsedAddress},
+    parser::{NumberFormat, Parser, Token},
 };
 use anyhow::bail;
 use move_core_types::{
     account_address::AccountAddress,
     identifier,
-    runtime_value::{MoveStruct, MoveValue},
+    runtime_value::{MoveStruct, MoveTypeLayout, MoveValue},
 };
 use std::fmt::{self, Display};
 
@@ -33,6 +33,7 @@ pub enum ValueToken {
     Comma,
     Colon,
     ColonColon,
+    Minus,
     Whitespace,
 }
 
@@ -115,6 +116,7 @@ impl Display for ValueToken {
             ValueToken::Comma => ",",
             ValueToken::Colon => ":",
             ValueToken::ColonColon => "::",
+            ValueToken::Minus => "-",
         };
         fmt::Display::fmt(s, formatter)
     }
@@ -163,6 +165,7 @@ impl Token for ValueToken {
             ',' => (Self::Comma, 1),
             ':' if matches!(chars.peek(), Some(':')) => (Self::ColonColon, 2),
             ':' => (Self::Colon, 1),
+            '-' => (Self::Minus, 1),
             '0' if matches!(chars.peek(), Some('x')) => {
                 chars.next().unwrap();
                 match chars.next() {
@@ -311,4 +314,39 @@ impl<Extra: ParsableValue> ParsedValue<Extra> {
             ParsedValue::Custom(c) => Extra::into_concrete_value(c, mapping),
         }
     }
+
+    /// Decodes the BCS `bytes` of a value of the given `layout` into a `ParsedValue`, which is the
+    /// reverse of parsing a value and serializing it. Addresses are decoded as hex numerical
+    /// addresses, and a `vector<u8>` is decoded to the same value as its `x"..."` literal.
+    pub fn from_bcs(bytes: &[u8], layout: &MoveTypeLayout) -> anyhow::Result<Self> {
+        Self::from_move_value(MoveValue::simple_deserialize(bytes, layout)?)
+    }
+
+    fn from_move_value(value: MoveValue) -> anyhow::Result<Self> {
+        Ok(match value {
+            MoveValue::U8(u) => ParsedValue::U8(u),
+            MoveValue::U16(u) => ParsedValue::U16(u),
+            MoveValue::U32(u) => ParsedValue::U32(u),
+            MoveValue::U64(u) => ParsedValue::U64(u),
+            MoveValue::U128(u) => ParsedValue::U128(u),
+            MoveValue::U256(u) => ParsedValue::U256(u),
+            MoveValue::Bool(b) => ParsedValue::Bool(b),
+            MoveValue::Address(a) | MoveValue::Signer(a) => ParsedValue::Address(
+                ParsedAddress::Numerical(NumericalAddress::new(a.into_bytes(), NumberFormat::Hex)),
+            ),
+            MoveValue::Vector(values) => ParsedValue::Vector(
+                values
+                    .into_iter()
+                    .map(Self::from_move_value)
+                    .collect::<anyhow::Result<_>>()?,
+            ),
+            MoveValue::Struct(s) => ParsedValue::Struct(
+                s.into_fields()
+                    .into_iter()
+                    .map(Self::from_move_value)
+                    .collect::<anyhow::Result<_>>()?,
+            ),
+            MoveValue::Variant(_) => bail!("Enum values cannot be represented as a parsed value"),
+        })
+    }
 }
@@ -2,7 +2,10 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{borrow_graph::BorrowGraph, error::VMError};
+use crate::{
+    borrow_graph::{BorrowGraph, PartitionID},
+    error::VMError,
+};
 use move_binary_format::file_format::{
     empty_module, Ability, AbilitySet, CompiledModule, FieldInstantiation, FieldInstantiationIndex,
     FunctionHandleIndex, FunctionInstantiation, FunctionInstantiationIndex, Signature,
@@ -136,6 +139,9 @@ impl CallGraph {
         self.calls.entry(caller).or_default().insert(callee);
     }
 
+    /// Returns, in ascending index order, the function handles that `my_index` can call without
+    /// making the call graph recursive. `my_index` itself is never included, since `call_depth`
+    /// treats a self call as recursive.
     pub fn can_call(&self, my_index: FunctionHandleIndex) -> Vec<FunctionHandleIndex> {
         // We want the set of function handles that don't lead to a recursive call-graph
         (0..self.max_function_handle_index)
@@ -192,6 +198,16 @@ impl CallGraph {
     }
 }
 
+/// The number of entries in each of the instantiation tables of an `InstantiableModule`. Each
+/// table can hold at most `TableIndex::MAX + 1` entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableSizes {
+    pub signatures: usize,
+    pub struct_instantiations: usize,
+    pub function_instantiations: usize,
+    pub field_instantiations: usize,
+}
+
 /// During the generation of a bytecode sequence, specific instantiations may need to be made, that
 /// may not yet exist in the underlying module. Instead of mutating the underlying module in order to record these instantiations in the
 /// locals signature table, we instead build wrapper around the underlying module containing the
@@ -389,6 +405,17 @@ impl InstantiableModule {
         }
     }
 
+    /// Returns the current size of each of the instantiation tables, including the instantiations
+    /// added so far, e.g. to decide whether to reuse an instantiation rather than add a new one.
+    pub fn table_sizes(&self) -> TableSizes {
+        TableSizes {
+            signatures: self.sig_instance_for_offset.len(),
+            struct_instantiations: self.struct_instance_for_offset.len(),
+            function_instantiations: self.func_instance_for_offset.len(),
+            field_instantiations: self.field_instance_for_offset.len(),
+        }
+    }
+
     /// Consumes self, and adds the instantiations that have been built up to the underlying
     /// module, and returns the resultant compiled module.
     pub fn instantiate(self) -> CompiledModule {
@@ -414,6 +441,10 @@ pub struct AbstractState {
     /// A Vector of `AbstractValue`s representing the VM value stack
     stack: Vec<AbstractValue>,
 
+    /// For each value on the stack, the borrow graph partition of the reference if it is a
+    /// borrow of a local, so that the borrow can be released once the reference is consumed
+    stack_borrows: Vec<Option<PartitionID>>,
+
     /// A vector of type kinds for any generic function type parameters of the function that we are
     /// in.
     pub instantiation: Vec<AbilitySet>,
@@ -425,6 +456,9 @@ pub struct AbstractState {
     /// access by subsequent instructions' effects
     register: Option<AbstractValue>,
 
+    /// The borrow graph partition of the reference in the register if it is a borrow of a local
+    register_borrow: Option<PartitionID>,
+
     /// The module state
     pub module: InstantiableModule,
 
@@ -435,13 +469,15 @@ pub struct AbstractState {
     /// in the VM runtime.
     aborted: bool,
 
+    /// This flag controls whether an `aborted` state is considered to be final.
+    aborted_is_final: bool,
+
     /// This flag controls whether or not control flow operators are allowed to be applied to the
     /// abstract state.
     control_flow_allowed: bool,
 
     /// This graph stores borrow information needed to ensure that bytecode instructions
     /// are memory safe
-    #[allow(dead_code)]
     borrow_graph: BorrowGraph,
 
     pub call_graph: CallGraph,
@@ -453,12 +489,15 @@ impl AbstractState {
         let compiled_module = empty_module();
         AbstractState {
             stack: Vec::new(),
+            stack_borrows: Vec::new(),
             instantiation: Vec::new(),
             locals: HashMap::new(),
             register: None,
+            register_borrow: None,
             module: InstantiableModule::new(compiled_module),
             acquires_global_resources: Vec::new(),
             aborted: false,
+            aborted_is_final: false,
             control_flow_allowed: false,
             borrow_graph: BorrowGraph::new(0),
             call_graph: CallGraph::new(0),
@@ -478,12 +517,15 @@ impl AbstractState {
         let module = InstantiableModule::new(module);
         AbstractState {
             stack: Vec::new(),
+            stack_borrows: Vec::new(),
             instantiation,
             locals,
             module,
             register: None,
+            register_borrow: None,
             acquires_global_resources,
             aborted: false,
+            aborted_is_final: false,
             control_flow_allowed: false,
             borrow_graph: BorrowGraph::new(locals_len as u8),
             call_graph,
@@ -495,16 +537,19 @@ impl AbstractState {
         self.register.clone()
     }
 
-    /// Get the register value and set it to `None`
+    /// Get the register value and set it to `None`. Any borrow held by the register value
+    /// is no longer tracked with it.
     pub fn register_move(&mut self) -> Option<AbstractValue> {
         let value = self.register.clone();
         self.register = None;
+        self.register_borrow = None;
         value
     }
 
     /// Set the register value and set it to `None`
     pub fn register_set(&mut self, value: AbstractValue) {
         self.register = Some(value);
+        self.register_borrow = None;
     }
 
     /// Add a `AbstractValue` to the stack
@@ -513,16 +558,19 @@ impl AbstractState {
         // will not be generated
         debug_assert!(self.stack.len() < usize::max_value());
         self.stack.push(item);
+        self.stack_borrows.push(None);
     }
 
     /// Add a `AbstractValue` to the stack from the register
     /// If the register is `None` return a `VMError`
     pub fn stack_push_register(&mut self) -> Result<(), VMError> {
+        let borrow = self.register_borrow;
         if let Some(abstract_value) = self.register_move() {
             // Programs that are large enough to exceed this bound
             // will not be generated
             debug_assert!(self.stack.len() < usize::max_value());
             self.stack.push(abstract_value);
+            self.stack_borrows.push(borrow);
             Ok(())
         } else {
             Err(VMError::new("Error: No value in register".to_string()))
@@ -536,10 +584,51 @@ impl AbstractState {
             Err(VMError::new("Pop attempted on empty stack".to_string()))
         } else {
             self.register = self.stack.pop();
+            self.register_borrow = self.stack_borrows.pop().flatten();
             Ok(())
         }
     }
 
+    /// Release the borrow held by the reference in the register, if it is a borrow of a local.
+    /// This is done when the reference is consumed.
+    pub fn register_release_borrow(&mut self) -> Result<(), VMError> {
+        if let Some(partition_id) = self.register_borrow.take() {
+            self.borrow_graph
+                .release_borrow(partition_id)
+                .map_err(VMError::new)?;
+        }
+        Ok(())
+    }
+
+    /// Freeze the mutable reference in the register into an immutable one, making its borrow
+    /// immutable as well if it is a borrow of a local.
+    /// If the register does not contain a mutable reference return a `VMError`.
+    pub fn register_freeze(&mut self) -> Result<(), VMError> {
+        match self.register.take() {
+            Some(AbstractValue {
+                token: SignatureToken::MutableReference(token),
+                abilities,
+            }) => {
+                self.register = Some(AbstractValue::new_reference(
+                    SignatureToken::Reference(token),
+                    abilities,
+                ));
+                if let Some(partition_id) = self.register_borrow {
+                    self.borrow_graph
+                        .freeze_borrow(partition_id)
+                        .map_err(VMError::new)?;
+                }
+                Ok(())
+            }
+            register => {
+                self.register = register;
+                Err(VMError::new(
+                    "Register does not contain a mutable reference".to_string(),
+                ))
+            }
+        }
+    }
+
     /// Get the `AbstractValue` at index `index` on the stack if it exists.
     /// Index 0 is the top of the stack.
     pub fn stack_peek(&self, index: usize) -> Option<AbstractValue> {
@@ -550,6 +639,13 @@ impl AbstractState {
         }
     }
 
+    /// Get the types of all values on the stack, ordered from the bottom to the top of the
+    /// stack. This is the order in which a function's parameters are pushed, so the signature
+    /// can be compared directly against the parameters of a function to call.
+    pub fn stack_signature(&self) -> Vec<SignatureToken> {
+        self.stack.iter().map(|value| value.token.clone()).collect()
+    }
+
     /// Get the length of the stack.
     pub fn stack_len(&self) -> usize {
         self.stack.len()
@@ -577,30 +673,64 @@ impl AbstractState {
     }
 
     /// Place a reference to the local at index `i` if it exists into the register
-    /// If it does not exist return a `VMError`.
+    /// and record the borrow in the borrow graph.
+    /// If it does not exist, or the borrow conflicts with an existing borrow of the
+    /// local, return a `VMError`.
     pub fn local_take_borrow(&mut self, i: usize, mutability: Mutability) -> Result<(), VMError> {
         if let Some((abstract_value, _)) = self.locals.get(&i) {
-            let ref_token = match mutability {
-                Mutability::Mutable => {
-                    SignatureToken::MutableReference(Box::new(abstract_value.token.clone()))
-                }
-                Mutability::Immutable => {
-                    SignatureToken::Reference(Box::new(abstract_value.token.clone()))
-                }
+            let (ref_token, mutable) = match mutability {
+                Mutability::Mutable => (
+                    SignatureToken::MutableReference(Box::new(abstract_value.token.clone())),
+                    true,
+                ),
+                Mutability::Immutable => (
+                    SignatureToken::Reference(Box::new(abstract_value.token.clone())),
+                    false,
+                ),
                 Mutability::Either => {
                     return Err(VMError::new("Mutability cannot be Either".to_string()))
                 }
             };
-            self.register = Some(AbstractValue::new_reference(
-                ref_token,
-                abstract_value.abilities,
-            ));
+            let reference = AbstractValue::new_reference(ref_token, abstract_value.abilities);
+            if !self.can_borrow_local(i, mutable) {
+                return Err(VMError::new(format!(
+                    "Local at index {} cannot be borrowed as {:?}",
+                    i, mutability
+                )));
+            }
+            let partition_id = self
+                .borrow_graph
+                .add_local_borrow(i as u8, mutability)
+                .map_err(VMError::new)?;
+            self.register = Some(reference);
+            self.register_borrow = Some(partition_id);
             Ok(())
         } else {
             Err(VMError::new(format!("Local does not exist at index {}", i)))
         }
     }
 
+    /// Check whether the local at index `i` exists, is `Available`, and can be borrowed
+    /// without conflicting with its existing borrows: a mutable borrow requires that the local
+    /// is not borrowed, an immutable borrow requires that it is not mutably borrowed.
+    pub fn can_borrow_local(&self, i: usize, mutable: bool) -> bool {
+        if !matches!(self.locals.get(&i), Some((_, BorrowState::Available))) {
+            return false;
+        }
+        let borrows = match u8::try_from(i)
+            .map_err(|e| e.to_string())
+            .and_then(|local| self.borrow_graph.local_borrows(local))
+        {
+            Ok(borrows) => borrows,
+            Err(_) => return false,
+        };
+        if mutable {
+            borrows.is_empty()
+        } else {
+            !borrows.contains(&Mutability::Mutable)
+        }
+    }
+
     /// Set the availability of the local at index `i`
     /// If it does not exist return a `VMError`.
     pub fn local_set(&mut self, i: usize, availability: BorrowState) -> Result<(), VMError> {
@@ -666,17 +796,54 @@ impl AbstractState {
         &self.locals
     }
 
+    /// Count the locals by `BorrowState`, returning the number of `Available` and the number of
+    /// `Unavailable` locals, in that order
+    pub fn local_availability_census(&self) -> (usize, usize) {
+        let available = self
+            .locals
+            .values()
+            .filter(|(_, availability)| *availability == BorrowState::Available)
+            .count();
+        (available, self.locals.len() - available)
+    }
+
+    /// Get the indices, in ascending order, of the locals of type `token` that are in the
+    /// `availability` `BorrowState`
+    pub fn locals_of_type(&self, token: &SignatureToken, availability: BorrowState) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .locals
+            .iter()
+            .filter(|(_, (abstract_value, local_availability))| {
+                abstract_value.token == *token && *local_availability == availability
+            })
+            .map(|(i, _)| *i)
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
     /// Set the abstract state to be `aborted` when a precondition of an instruction
     /// fails. (This will happen if `NEGATE_PRECONDITIONs` is true).
     pub fn abort(&mut self) {
         self.aborted = true;
     }
 
+    /// Clear the `aborted` flag of the abstract state, so that a state that was
+    /// speculatively aborted can be reused.
+    pub fn clear_abort(&mut self) {
+        self.aborted = false;
+    }
+
     /// Whether the state is aborted
     pub fn has_aborted(&self) -> bool {
         self.aborted
     }
 
+    /// Set whether an `aborted` state is considered to be final by `is_final`.
+    pub fn set_aborted_is_final(&mut self, aborted_is_final: bool) {
+        self.aborted_is_final = aborted_is_final;
+    }
+
     /// Set the abstract state to allow generation of control flow operations.
     pub fn allow_control_flow(&mut self) {
         self.control_flow_allowed = true;
@@ -687,9 +854,10 @@ impl AbstractState {
         self.control_flow_allowed
     }
 
-    /// The final state is one where the stack is empty
+    /// The final state is one where the stack is empty, or one that has aborted
+    /// if aborted states are configured to be final.
     pub fn is_final(&self) -> bool {
-        self.stack.is_empty()
+        self.stack.is_empty() || (self.aborted_is_final && self.aborted)
     }
 }
 
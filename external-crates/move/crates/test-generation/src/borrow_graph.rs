@@ -6,7 +6,7 @@ use crate::abstract_state::Mutability;
 use std::collections::HashMap;
 
 /// Each partition is associated with a (unique) ID
-type PartitionID = u16;
+pub(crate) type PartitionID = u16;
 
 /// A nonce represents a runtime reference. It has a unique identifier and a mutability
 type Nonce = (u16, Mutability);
@@ -47,34 +47,92 @@ pub struct BorrowGraph {
 }
 
 impl BorrowGraph {
-    /// Construct a new `BorrowGraph` given the number of locals it has
+    /// Construct a new `BorrowGraph` given the number of locals it has.
+    /// The partition ID of a local is its index, so the IDs that a local index
+    /// can take are never used for fresh partitions.
     pub fn new(num_locals: u8) -> BorrowGraph {
         BorrowGraph {
             partitions: Vec::with_capacity(num_locals as usize),
             partition_map: HashMap::new(),
             edges: Vec::new(),
-            partition_counter: u16::from(num_locals),
+            partition_counter: u16::from(u8::MAX) + 1,
         }
     }
 
-    /// Add a new partition to the graph containing nonce `n`
+    /// Add a new partition to the graph containing nonce `n` and return its ID
     /// This operation may fail with an error a fresh partition ID
     /// cannot be chosen.
-    pub fn fresh_partition(&mut self, n: Nonce) -> Result<(), String> {
-        if self.partition_counter.checked_add(1).is_some() {
+    pub fn fresh_partition(&mut self, n: Nonce) -> Result<PartitionID, String> {
+        if let Some(next_partition_counter) = self.partition_counter.checked_add(1) {
             if self.partition_map.get(&self.partition_counter).is_some() {
                 return Err("Partition map already contains ID".to_string());
             }
-            self.partition_map.insert(self.partition_counter, vec![n]);
+            let partition_id = self.partition_counter;
+            self.partition_map.insert(partition_id, vec![n]);
             // Implication of `checked_add`
             debug_assert!(self.partitions.len() < usize::max_value());
-            self.partitions.push(self.partition_counter);
-            Ok(())
+            self.partitions.push(partition_id);
+            self.partition_counter = next_partition_counter;
+            Ok(partition_id)
         } else {
             Err("Partition map is full".to_string())
         }
     }
 
+    /// Record a borrow of the local at index `local` by a fresh reference with the given
+    /// mutability, and return the partition of that reference. This operation may fail with
+    /// an error if a fresh partition cannot be chosen.
+    pub fn add_local_borrow(
+        &mut self,
+        local: u8,
+        mutability: Mutability,
+    ) -> Result<PartitionID, String> {
+        let nonce_id = self.partition_counter;
+        let partition_id = self.fresh_partition((nonce_id, mutability))?;
+        self.edges.push((
+            PartitionID::from(local),
+            partition_id,
+            vec![],
+            EdgeType::Strong,
+        ));
+        Ok(partition_id)
+    }
+
+    /// Release the borrow held by the reference in the given partition, once that reference
+    /// has been consumed. This operation may fail with an error if the given partition does
+    /// not exist in the graph.
+    pub fn release_borrow(&mut self, partition_id: PartitionID) -> Result<(), String> {
+        if self.partition_map.remove(&partition_id).is_none() {
+            return Err("Partition map does not contain given partition ID".to_string());
+        }
+        self.partitions.retain(|p| *p != partition_id);
+        self.edges
+            .retain(|(p1, p2, _, _)| *p1 != partition_id && *p2 != partition_id);
+        Ok(())
+    }
+
+    /// Make the references in the given partition immutable, as done by freezing them.
+    /// This operation may fail with an error if the given partition does not exist in the graph.
+    pub fn freeze_borrow(&mut self, partition_id: PartitionID) -> Result<(), String> {
+        if let Some(nonce_set) = self.partition_map.get_mut(&partition_id) {
+            for (_, mutability) in nonce_set.iter_mut() {
+                *mutability = Mutability::Immutable;
+            }
+            Ok(())
+        } else {
+            Err("Partition map does not contain given partition ID".to_string())
+        }
+    }
+
+    /// Get the mutability of all the references borrowing the local at index `local`
+    pub fn local_borrows(&self, local: u8) -> Result<Vec<Mutability>, String> {
+        self.edges
+            .iter()
+            .filter(|(p1, _, _, _)| *p1 == PartitionID::from(local))
+            .map(|(_, p2, _, _)| self.partition_mutability(*p2))
+            .collect()
+    }
+
     /// Determine whether a partition is mutable, immutable, or either.
     /// This operation may fail with an error if the given partition does
     /// not exist in the graph.
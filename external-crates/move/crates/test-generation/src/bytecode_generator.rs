@@ -621,9 +621,9 @@ impl<'a> BytecodeGenerator<'a> {
                         next_instruction,
                         false,
                     )?;
-                    if state.is_final() {
-                        break;
-                    } else if state.has_aborted() {
+                    // An aborted state may also be final, so check for the abort first, so that the
+                    // block still ends with the abort epilogue
+                    if state.has_aborted() {
                         state = self.apply_instruction(
                             fn_context,
                             state,
@@ -639,6 +639,8 @@ impl<'a> BytecodeGenerator<'a> {
                             true,
                         )?;
                         return Some((bytecode, state));
+                    } else if state.is_final() {
+                        break;
                     }
                 }
                 Err(err) => {
@@ -6,15 +6,15 @@ use crate::{
     abstract_state::{AbstractState, AbstractValue, BorrowState, Mutability},
     error::VMError,
     function_instantiation_for_state, state_control_flow, state_create_struct,
-    state_create_struct_from_inst, state_local_availability_is, state_local_exists,
-    state_local_has_ability, state_local_place, state_local_set, state_local_take,
-    state_local_take_borrow, state_memory_safe, state_never, state_register_dereference,
-    state_stack_bin_op, state_stack_function_call, state_stack_function_inst_call,
-    state_stack_function_inst_popn, state_stack_function_popn, state_stack_has,
-    state_stack_has_ability, state_stack_has_integer, state_stack_has_polymorphic_eq,
-    state_stack_has_reference, state_stack_has_struct, state_stack_has_struct_inst,
-    state_stack_is_castable, state_stack_local_polymorphic_eq, state_stack_pop, state_stack_push,
-    state_stack_push_register, state_stack_push_register_borrow, state_stack_ref_polymorphic_eq,
+    state_create_struct_from_inst, state_local_availability_is, state_local_can_borrow,
+    state_local_exists, state_local_has_ability, state_local_place, state_local_set,
+    state_local_take, state_local_take_borrow, state_memory_safe, state_register_dereference,
+    state_register_freeze, state_register_release_borrow, state_stack_bin_op,
+    state_stack_function_call, state_stack_function_inst_call, state_stack_function_inst_popn,
+    state_stack_function_popn, state_stack_has, state_stack_has_ability, state_stack_has_integer,
+    state_stack_has_polymorphic_eq, state_stack_has_reference, state_stack_has_struct,
+    state_stack_has_struct_inst, state_stack_is_castable, state_stack_local_polymorphic_eq,
+    state_stack_pop, state_stack_push, state_stack_push_register, state_stack_ref_polymorphic_eq,
     state_stack_satisfies_function_inst_signature, state_stack_satisfies_function_signature,
     state_stack_satisfies_struct_signature, state_stack_struct_borrow_field,
     state_stack_struct_borrow_field_inst, state_stack_struct_has_field,
@@ -73,7 +73,10 @@ pub fn instruction_summary(instruction: Bytecode, exact: bool) -> Summary {
                 state_stack_has_ability!(0, Ability::Copy),
                 state_memory_safe!(Some(0)),
             ],
-            effects: Effects::NoTyParams(vec![state_stack_pop!()]),
+            effects: Effects::NoTyParams(vec![
+                state_stack_pop!(),
+                state_register_release_borrow!(),
+            ]),
         },
         Bytecode::LdU8(_) => Summary {
             preconditions: vec![],
@@ -211,26 +214,24 @@ pub fn instruction_summary(instruction: Bytecode, exact: bool) -> Summary {
             ]),
         },
         Bytecode::MutBorrowLoc(i) => Summary {
-            // TODO: Add these back in when borrow graph is added
-            // preconditions: vec![
-            //     state_local_exists!(i),
-            //     state_local_availability_is!(i, BorrowState::Available),
-            //     state_memory_safe!(None),
-            // ],
-            preconditions: vec![state_never!()],
+            preconditions: vec![
+                state_local_exists!(i),
+                state_local_availability_is!(i, BorrowState::Available),
+                state_local_can_borrow!(i, Mutability::Mutable),
+                state_memory_safe!(None),
+            ],
             effects: Effects::NoTyParams(vec![
                 state_local_take_borrow!(i, Mutability::Mutable),
                 state_stack_push_register!(),
             ]),
         },
         Bytecode::ImmBorrowLoc(i) => Summary {
-            // TODO: Add these back in when the borrow graph is added
-            //preconditions: vec![
-            //    state_local_exists!(i),
-            //    state_local_availability_is!(i, BorrowState::Available),
-            //    state_memory_safe!(None),
-            //],
-            preconditions: vec![state_never!()],
+            preconditions: vec![
+                state_local_exists!(i),
+                state_local_availability_is!(i, BorrowState::Available),
+                state_local_can_borrow!(i, Mutability::Immutable),
+                state_memory_safe!(None),
+            ],
             effects: Effects::NoTyParams(vec![
                 state_local_take_borrow!(i, Mutability::Immutable),
                 state_stack_push_register!(),
@@ -243,6 +244,7 @@ pub fn instruction_summary(instruction: Bytecode, exact: bool) -> Summary {
             ],
             effects: Effects::NoTyParams(vec![
                 state_stack_pop!(),
+                state_register_release_borrow!(),
                 state_register_dereference!(),
                 state_stack_push_register!(),
             ]),
@@ -254,7 +256,11 @@ pub fn instruction_summary(instruction: Bytecode, exact: bool) -> Summary {
                 state_stack_ref_polymorphic_eq!(0, 1),
                 state_memory_safe!(None),
             ],
-            effects: Effects::NoTyParams(vec![state_stack_pop!(), state_stack_pop!()]),
+            effects: Effects::NoTyParams(vec![
+                state_stack_pop!(),
+                state_register_release_borrow!(),
+                state_stack_pop!(),
+            ]),
         },
         Bytecode::FreezeRef => Summary {
             preconditions: vec![
@@ -263,8 +269,8 @@ pub fn instruction_summary(instruction: Bytecode, exact: bool) -> Summary {
             ],
             effects: Effects::NoTyParams(vec![
                 state_stack_pop!(),
-                state_register_dereference!(),
-                state_stack_push_register_borrow!(Mutability::Immutable),
+                state_register_freeze!(),
+                state_stack_push_register!(),
             ]),
         },
         Bytecode::Add
@@ -318,6 +318,16 @@ pub fn local_availability_is(state: &AbstractState, index: u8, availability: Bor
         .unwrap_or(false)
 }
 
+/// Check whether the local at `index` can be borrowed with the given mutability without
+/// conflicting with its existing borrows
+pub fn local_can_borrow(state: &AbstractState, index: u8, mutability: Mutability) -> bool {
+    match mutability {
+        Mutability::Mutable => state.can_borrow_local(index as usize, true),
+        Mutability::Immutable => state.can_borrow_local(index as usize, false),
+        Mutability::Either => false,
+    }
+}
+
 /// Determine whether an abstract value on the stack that is a reference points to something of the
 /// same type as another abstract value on the stack
 pub fn stack_ref_polymorphic_eq(state: &AbstractState, index1: usize, index2: usize) -> bool {
@@ -917,6 +927,20 @@ pub fn register_dereference(state: &AbstractState) -> Result<AbstractState, VMEr
     }
 }
 
+/// Release the borrow held by the reference in the register, as the reference is consumed.
+pub fn register_release_borrow(state: &AbstractState) -> Result<AbstractState, VMError> {
+    let mut state = state.clone();
+    state.register_release_borrow()?;
+    Ok(state)
+}
+
+/// Freeze the mutable reference in the register into an immutable one.
+pub fn register_freeze(state: &AbstractState) -> Result<AbstractState, VMError> {
+    let mut state = state.clone();
+    state.register_freeze()?;
+    Ok(state)
+}
+
 /// Push a reference to a register value with the given mutability.
 pub fn stack_push_register_borrow(
     state: &AbstractState,
@@ -1209,6 +1233,15 @@ macro_rules! state_local_availability_is {
     };
 }
 
+/// Wrapper for enclosing the arguments of `local_can_borrow` so that only the `state` needs
+/// to be given.
+#[macro_export]
+macro_rules! state_local_can_borrow {
+    ($e: expr, $mutability: expr) => {
+        Box::new(move |state| local_can_borrow(state, $e, $mutability))
+    };
+}
+
 /// Wrapper for enclosing the arguments of `state_local_has_ability` so that only the `state` needs
 /// to be given.
 #[macro_export]
@@ -1414,6 +1447,24 @@ macro_rules! state_register_dereference {
     };
 }
 
+/// Wrapper for enclosing the arguments of `register_release_borrow` so that only the
+/// `state` needs to be given.
+#[macro_export]
+macro_rules! state_register_release_borrow {
+    () => {
+        Box::new(move |state| register_release_borrow(state))
+    };
+}
+
+/// Wrapper for enclosing the arguments of `register_freeze` so that only the
+/// `state` needs to be given.
+#[macro_export]
+macro_rules! state_register_freeze {
+    () => {
+        Box::new(move |state| register_freeze(state))
+    };
+}
+
 /// Wrapper for enclosing the arguments of `stack_push_register_borrow` so that only the
 /// `state` needs to be given.
 #[macro_export]
@@ -0,0 +1,216 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+extern crate test_generation;
+use move_binary_format::file_format::{
+    empty_module, FieldHandleIndex, FieldInstantiation, FunctionHandleIndex, FunctionInstantiation,
+    SignatureToken, StructDefInstantiation, StructDefinitionIndex,
+};
+use test_generation::abstract_state::{
+    AbstractState, AbstractValue, BorrowState, InstantiableModule, Mutability, TableSizes,
+};
+
+#[test]
+fn locals_of_type() {
+    let mut state = AbstractState::new();
+    let locals = [
+        (SignatureToken::U64, BorrowState::Available),
+        (SignatureToken::Bool, BorrowState::Available),
+        (SignatureToken::U64, BorrowState::Unavailable),
+        (SignatureToken::U64, BorrowState::Available),
+        (SignatureToken::Address, BorrowState::Available),
+    ];
+    for (i, (token, availability)) in locals.into_iter().enumerate() {
+        state.local_insert(i, AbstractValue::new_primitive(token), availability);
+    }
+
+    assert_eq!(
+        state.locals_of_type(&SignatureToken::U64, BorrowState::Available),
+        vec![0, 3]
+    );
+    assert_eq!(
+        state.locals_of_type(&SignatureToken::U64, BorrowState::Unavailable),
+        vec![2]
+    );
+    assert_eq!(
+        state.locals_of_type(&SignatureToken::Bool, BorrowState::Available),
+        vec![1]
+    );
+    assert!(state
+        .locals_of_type(&SignatureToken::U8, BorrowState::Available)
+        .is_empty());
+}
+
+#[test]
+fn second_mutable_borrow_rejected() {
+    let mut state = AbstractState::new();
+    state.local_insert(
+        0,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Available,
+    );
+    assert!(state.can_borrow_local(0, true));
+    state.local_take_borrow(0, Mutability::Mutable).unwrap();
+
+    assert!(!state.can_borrow_local(0, true));
+    assert!(!state.can_borrow_local(0, false));
+    assert!(state.local_take_borrow(0, Mutability::Mutable).is_err());
+    assert!(state.local_take_borrow(0, Mutability::Immutable).is_err());
+}
+
+#[test]
+fn immutable_borrows_do_not_conflict() {
+    let mut state = AbstractState::new();
+    state.local_insert(
+        0,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Available,
+    );
+    state.local_insert(
+        1,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Available,
+    );
+    state.local_take_borrow(0, Mutability::Immutable).unwrap();
+    state.local_take_borrow(0, Mutability::Immutable).unwrap();
+
+    // A mutable borrow conflicts with the existing immutable ones
+    assert!(!state.can_borrow_local(0, true));
+    assert!(state.local_take_borrow(0, Mutability::Mutable).is_err());
+
+    // Borrows of other locals are unaffected
+    assert!(state.can_borrow_local(1, true));
+    state.local_take_borrow(1, Mutability::Mutable).unwrap();
+}
+
+#[test]
+fn cannot_borrow_unavailable_local() {
+    let mut state = AbstractState::new();
+    state.local_insert(
+        0,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Unavailable,
+    );
+    assert!(!state.can_borrow_local(0, false));
+    assert!(!state.can_borrow_local(1, false));
+}
+
+#[test]
+fn local_availability_census() {
+    let mut state = AbstractState::new();
+    assert_eq!(state.local_availability_census(), (0, 0));
+
+    let availabilities = [
+        BorrowState::Available,
+        BorrowState::Unavailable,
+        BorrowState::Available,
+        BorrowState::Available,
+        BorrowState::Unavailable,
+    ];
+    for (i, availability) in availabilities.into_iter().enumerate() {
+        state.local_insert(
+            i,
+            AbstractValue::new_primitive(SignatureToken::U64),
+            availability,
+        );
+    }
+    assert_eq!(state.local_availability_census(), (3, 2));
+
+    state.local_set(0, BorrowState::Unavailable).unwrap();
+    assert_eq!(state.local_availability_census(), (2, 3));
+}
+
+#[test]
+fn stack_signature() {
+    let mut state = AbstractState::new();
+    assert!(state.stack_signature().is_empty());
+
+    let tokens = [
+        SignatureToken::U64,
+        SignatureToken::Bool,
+        SignatureToken::Address,
+        SignatureToken::U8,
+    ];
+    for token in &tokens {
+        state.stack_push(AbstractValue::new_primitive(token.clone()));
+    }
+    // Bottom to top, i.e. in push order
+    assert_eq!(state.stack_signature(), tokens.to_vec());
+    assert_eq!(
+        state.stack_peek(0).map(|value| value.token),
+        state.stack_signature().last().cloned()
+    );
+
+    state.stack_pop().unwrap();
+    assert_eq!(state.stack_signature(), tokens[..3].to_vec());
+}
+
+#[test]
+fn abort_and_clear_abort() {
+    let mut state = AbstractState::new();
+    state.stack_push(AbstractValue::new_primitive(SignatureToken::U64));
+    assert!(!state.has_aborted());
+    assert!(!state.is_final());
+
+    state.abort();
+    assert!(state.has_aborted());
+    // Aborted states are not final unless configured to be
+    assert!(!state.is_final());
+    state.set_aborted_is_final(true);
+    assert!(state.is_final());
+
+    state.clear_abort();
+    assert!(!state.has_aborted());
+    assert!(!state.is_final());
+
+    state.abort();
+    assert!(state.has_aborted());
+    assert!(state.is_final());
+}
+
+#[test]
+fn instantiable_module_table_sizes() {
+    let module = empty_module();
+    let initial_signatures = module.signatures.len();
+    let mut instantiable = InstantiableModule::new(module);
+    assert_eq!(
+        instantiable.table_sizes(),
+        TableSizes {
+            signatures: initial_signatures,
+            struct_instantiations: 0,
+            function_instantiations: 0,
+            field_instantiations: 0,
+        }
+    );
+
+    let sig_u64 = instantiable.add_instantiation(vec![SignatureToken::U64]);
+    let sig_bool = instantiable.add_instantiation(vec![SignatureToken::Bool]);
+    // Existing instantiations are reused rather than added again
+    instantiable.add_instantiation(vec![SignatureToken::U64]);
+    for type_parameters in [sig_u64, sig_bool] {
+        instantiable.add_struct_instantiation(StructDefInstantiation {
+            def: StructDefinitionIndex(0),
+            type_parameters,
+        });
+    }
+    instantiable.add_function_instantiation(FunctionInstantiation {
+        handle: FunctionHandleIndex(0),
+        type_parameters: sig_u64,
+    });
+    for _ in 0..2 {
+        instantiable.add_field_instantiation(FieldInstantiation {
+            handle: FieldHandleIndex(0),
+            type_parameters: sig_bool,
+        });
+    }
+
+    assert_eq!(
+        instantiable.table_sizes(),
+        TableSizes {
+            signatures: initial_signatures + 2,
+            struct_instantiations: 2,
+            function_instantiations: 1,
+            field_instantiations: 1,
+        }
+    );
+}
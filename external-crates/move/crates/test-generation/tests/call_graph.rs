@@ -37,6 +37,42 @@ fn call_graph_transitive_recursive_call() {
     assert!(can_call0.len() == 7);
 }
 
+#[test]
+fn call_graph_can_call_excludes_self() {
+    let mut call_graph = CallGraph::new(6);
+    call_graph.add_call(FunctionHandleIndex(0), FunctionHandleIndex(1));
+    call_graph.add_call(FunctionHandleIndex(1), FunctionHandleIndex(2));
+    call_graph.add_call(FunctionHandleIndex(3), FunctionHandleIndex(4));
+    for index in 0..6 {
+        let can_call = call_graph.can_call(FunctionHandleIndex(index));
+        assert!(!can_call.contains(&FunctionHandleIndex(index)));
+        // candidates are returned in ascending index order
+        assert!(can_call.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
+
+#[test]
+fn call_graph_cycle_forming_edge_removes_candidate() {
+    let mut call_graph = CallGraph::new(5);
+    call_graph.add_call(FunctionHandleIndex(0), FunctionHandleIndex(1));
+    assert_eq!(
+        call_graph.can_call(FunctionHandleIndex(2)),
+        vec![
+            FunctionHandleIndex(0),
+            FunctionHandleIndex(1),
+            FunctionHandleIndex(3),
+            FunctionHandleIndex(4)
+        ]
+    );
+
+    // Once 1 calls 2, 2 can no longer call 1, nor 0 which calls 1
+    call_graph.add_call(FunctionHandleIndex(1), FunctionHandleIndex(2));
+    assert_eq!(
+        call_graph.can_call(FunctionHandleIndex(2)),
+        vec![FunctionHandleIndex(3), FunctionHandleIndex(4)]
+    );
+}
+
 #[test]
 fn call_graph_call_graph_depth() {
     let mut call_graph = CallGraph::new(10);
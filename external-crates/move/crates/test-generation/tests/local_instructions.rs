@@ -162,27 +162,39 @@ fn bytecode_immborrowloc_no_local() {
     common::run_instruction(Bytecode::ImmBorrowLoc(0), state1);
 }
 
-// TODO: Turn back on when borrow graph and references are allowed
-// #[test]
-// #[should_panic]
-// fn bytecode_mutborrowloc_local_unavailable() {
-//     let mut state1 = AbstractState::new();
-//     state1.local_insert(
-//         0,
-//         AbstractValue::new_primitive(SignatureToken::U64),
-//         BorrowState::Unavailable,
-//     );
-//     common::run_instruction(Bytecode::MutBorrowLoc(0), state1);
-// }
-//
-// #[test]
-// #[should_panic]
-// fn bytecode_immborrowloc_local_unavailable() {
-//     let mut state1 = AbstractState::new();
-//     state1.local_insert(
-//         0,
-//         AbstractValue::new_primitive(SignatureToken::U64),
-//         BorrowState::Unavailable,
-//     );
-//     common::run_instruction(Bytecode::ImmBorrowLoc(0), state1);
-// }
+#[test]
+#[should_panic]
+fn bytecode_mutborrowloc_local_unavailable() {
+    let mut state1 = AbstractState::new();
+    state1.local_insert(
+        0,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Unavailable,
+    );
+    common::run_instruction(Bytecode::MutBorrowLoc(0), state1);
+}
+
+#[test]
+#[should_panic]
+fn bytecode_immborrowloc_local_unavailable() {
+    let mut state1 = AbstractState::new();
+    state1.local_insert(
+        0,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Unavailable,
+    );
+    common::run_instruction(Bytecode::ImmBorrowLoc(0), state1);
+}
+
+#[test]
+#[should_panic]
+fn bytecode_mutborrowloc_already_borrowed() {
+    let mut state1 = AbstractState::new();
+    state1.local_insert(
+        0,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Available,
+    );
+    let (state2, _) = common::run_instruction(Bytecode::ImmBorrowLoc(0), state1);
+    common::run_instruction(Bytecode::MutBorrowLoc(0), state2);
+}
@@ -4,7 +4,7 @@
 
 extern crate test_generation;
 use move_binary_format::file_format::{AbilitySet, Bytecode, SignatureToken};
-use test_generation::abstract_state::{AbstractState, AbstractValue};
+use test_generation::abstract_state::{AbstractState, AbstractValue, BorrowState};
 
 mod common;
 
@@ -118,3 +118,51 @@ fn bytecode_feezeref_already_immutable() {
     ));
     common::run_instruction(Bytecode::FreezeRef, state1);
 }
+
+fn state_with_local() -> AbstractState {
+    let mut state = AbstractState::new();
+    state.local_insert(
+        0,
+        AbstractValue::new_primitive(SignatureToken::U64),
+        BorrowState::Available,
+    );
+    state
+}
+
+#[test]
+fn bytecode_readref_releases_borrow() {
+    let (state1, _) = common::run_instruction(Bytecode::MutBorrowLoc(0), state_with_local());
+    assert!(!state1.can_borrow_local(0, false));
+    let (state2, _) = common::run_instruction(Bytecode::ReadRef, state1);
+    assert!(state2.can_borrow_local(0, true));
+}
+
+#[test]
+fn bytecode_writeref_releases_borrow() {
+    let mut state1 = state_with_local();
+    state1.stack_push(AbstractValue::new_primitive(SignatureToken::U64));
+    let (state2, _) = common::run_instruction(Bytecode::MutBorrowLoc(0), state1);
+    assert!(!state2.can_borrow_local(0, false));
+    let (state3, _) = common::run_instruction(Bytecode::WriteRef, state2);
+    assert!(state3.can_borrow_local(0, true));
+}
+
+#[test]
+fn bytecode_pop_releases_borrow() {
+    let (state1, _) = common::run_instruction(Bytecode::ImmBorrowLoc(0), state_with_local());
+    assert!(!state1.can_borrow_local(0, true));
+    let (state2, _) = common::run_instruction(Bytecode::Pop, state1);
+    assert!(state2.can_borrow_local(0, true));
+}
+
+#[test]
+fn bytecode_freezeref_freezes_borrow() {
+    let (state1, _) = common::run_instruction(Bytecode::MutBorrowLoc(0), state_with_local());
+    assert!(!state1.can_borrow_local(0, false));
+    let (state2, _) = common::run_instruction(Bytecode::FreezeRef, state1);
+    // The frozen reference still borrows the local, but only immutably
+    assert!(state2.can_borrow_local(0, false));
+    assert!(!state2.can_borrow_local(0, true));
+    let (state3, _) = common::run_instruction(Bytecode::ReadRef, state2);
+    assert!(state3.can_borrow_local(0, true));
+}
@@ -25,6 +25,7 @@ use move_vm_types::{
 use object_store::ChildObjectStore;
 use std::{
     collections::{BTreeMap, BTreeSet},
+    ops::Range,
     sync::Arc,
 };
 use sui_protocol_config::{check_limit_by_meter, LimitThresholdCrossed, ProtocolConfig};
@@ -35,7 +36,7 @@ use sui_types::{
     execution::DynamicallyLoadedObjectMetadata,
     id::UID,
     metrics::LimitsMetrics,
-    object::{MoveObject, Owner},
+    object::{MoveObject, Object, Owner},
     storage::ChildObjectResolver,
     SUI_AUTHENTICATOR_STATE_OBJECT_ID, SUI_BRIDGE_OBJECT_ID, SUI_CLOCK_OBJECT_ID,
     SUI_DENY_LIST_OBJECT_ID, SUI_RANDOMNESS_STATE_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID,
@@ -418,6 +419,31 @@ impl<'a> ObjectRuntime<'a> {
         self.child_object_store.all_active_objects()
     }
 
+    /// The child objects loaded so far with an ID in `range`, where a `None` entry means the
+    /// child object was looked up but not found. Intended for debugging.
+    pub fn cached_child_objects_in_range(
+        &self,
+        range: Range<ObjectID>,
+    ) -> impl Iterator<Item = (&ObjectID, &Option<Object>)> {
+        self.child_object_store.cached_objects_in_range(range)
+    }
+
+    /// Resolves a known set of children of `parent` in a single call to the resolver, caching
+    /// them ahead of their use. Children not preloaded are still resolved individually on access.
+    pub fn preload_child_objects(
+        &mut self,
+        parent: ObjectID,
+        children: &[ObjectID],
+    ) -> PartialVMResult<()> {
+        self.child_object_store.preload_children(parent, children)
+    }
+
+    /// The parent a child object loaded in this transaction is associated with. This allows
+    /// checking whether a child is already associated with another parent before fetching it.
+    pub fn child_object_parent(&self, child: ObjectID) -> Option<ObjectID> {
+        self.child_object_store.object_parent(child)
+    }
+
     pub fn loaded_runtime_objects(&self) -> BTreeMap<ObjectID, DynamicallyLoadedObjectMetadata> {
         // The loaded child objects, and the received objects, should be disjoint. If they are not,
         // this is an error since it could lead to incorrect transaction dependency computations.
@@ -11,7 +11,8 @@ use move_vm_types::{
     values::{GlobalValue, StructRef, Value},
 };
 use std::{
-    collections::{btree_map, BTreeMap},
+    collections::{btree_map, BTreeMap, BTreeSet},
+    ops::Range,
     sync::Arc,
 };
 use sui_protocol_config::{check_limit_by_meter, LimitThresholdCrossed, ProtocolConfig};
@@ -60,6 +61,13 @@ struct Inner<'a> {
     metrics: Arc<LimitsMetrics>,
     // Epoch ID for the current transaction. Used for receiving objects.
     current_epoch_id: EpochId,
+    // If set by the protocol config, the `None` entries of `cached_objects` are evicted once it
+    // holds this many entries. Non-existence is re-derived from the resolver if the object is
+    // looked up again, while loaded objects are kept, as they may have been used.
+    cached_objects_eviction_threshold: Option<u64>,
+    // The number of objects cached so far, including evicted ones, which is metered against the
+    // limit on cached objects so that eviction does not change when the limit is reached
+    num_cached_objects: u64,
 }
 
 // maintains the runtime GlobalValues for child objects and manages the fetching of objects
@@ -153,80 +161,26 @@ impl<'a> Inner<'a> {
         parent: ObjectID,
         child: ObjectID,
     ) -> PartialVMResult<Option<&MoveObject>> {
-        let cached_objects_count = self.cached_objects.len() as u64;
+        if !self.cached_objects.contains_key(&child) {
+            self.maybe_evict_cached_objects();
+        }
         let parents_root_version = self.root_version.get(&parent).copied();
         let had_parent_root_version = parents_root_version.is_some();
         // if not found, it must be new so it won't have any child objects, thus
         // we can return SequenceNumber(0) as no child object will be found
         let parents_root_version = parents_root_version.unwrap_or(SequenceNumber::new());
-        if let btree_map::Entry::Vacant(e) = self.cached_objects.entry(child) {
+        if !self.cached_objects.contains_key(&child) {
             let child_opt = self
                 .resolver
                 .read_child_object(&parent, &child, parents_root_version)
                 .map_err(|msg| {
                     PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(format!("{msg}"))
                 })?;
-            let obj_opt = if let Some(object) = child_opt {
-                // if there was no root version, guard against reading a child object. A newly
-                // created parent should not have a child in storage
-                if !had_parent_root_version {
-                    return Err(PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(
-                        format!("A new parent {parent} should not have a child object {child}."),
-                    ));
-                }
-                // guard against bugs in `read_child_object`: if it returns a child object such that
-                // C.parent != parent, we raise an invariant violation
-                match &object.owner {
-                    Owner::ObjectOwner(id) => {
-                        if ObjectID::from(*id) != parent {
-                            return Err(PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(
-                                format!("Bad owner for {child}. \
-                                Expected owner {parent} but found owner {id}")
-                            ))
-                        }
-                    }
-                    Owner::AddressOwner(_) | Owner::Immutable | Owner::Shared { .. } => {
-                        return Err(PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(
-                            format!("Bad owner for {child}. \
-                            Expected an id owner {parent} but found an address, immutable, or shared owner")
-                        ))
-                    }
-                };
-                match object.data {
-                    Data::Package(_) => {
-                        return Err(PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(
-                            format!(
-                                "Mismatched object type for {child}. \
-                                Expected a Move object but found a Move package"
-                            ),
-                        ))
-                    }
-                    Data::Move(_) => Some(object),
-                }
-            } else {
-                None
-            };
-
-            if let LimitThresholdCrossed::Hard(_, lim) = check_limit_by_meter!(
-                self.is_metered,
-                cached_objects_count,
-                self.protocol_config.object_runtime_max_num_cached_objects(),
-                self.protocol_config
-                    .object_runtime_max_num_cached_objects_system_tx(),
-                self.metrics.excessive_object_runtime_cached_objects
-            ) {
-                return Err(PartialVMError::new(StatusCode::MEMORY_LIMIT_EXCEEDED)
-                    .with_message(format!(
-                        "Object runtime cached objects limit ({} entries) reached",
-                        lim
-                    ))
-                    .with_sub_status(
-                        VMMemoryLimitExceededSubStatusCode::OBJECT_RUNTIME_CACHE_LIMIT_EXCEEDED
-                            as u64,
-                    ));
-            };
-
-            e.insert(obj_opt);
+            let obj_opt =
+                Self::check_child_object(parent, child, had_parent_root_version, child_opt)?;
+            self.check_cached_objects_limit()?;
+            self.cached_objects.insert(child, obj_opt);
+            self.num_cached_objects += 1;
         }
         Ok(self
             .cached_objects
@@ -241,6 +195,133 @@ impl<'a> Inner<'a> {
             }))
     }
 
+    // Resolves all of the `children` of `parent` that are not cached yet with a single call to the
+    // resolver, and caches them, so that later accesses do not go to the resolver one at a time.
+    fn preload_children(&mut self, parent: ObjectID, children: &[ObjectID]) -> PartialVMResult<()> {
+        self.maybe_evict_cached_objects();
+        let uncached: Vec<ObjectID> = children
+            .iter()
+            .filter(|child| !self.cached_objects.contains_key(child))
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if uncached.is_empty() {
+            return Ok(());
+        }
+        let parents_root_version = self.root_version.get(&parent).copied();
+        let had_parent_root_version = parents_root_version.is_some();
+        // see `get_or_fetch_object_from_store`
+        let parents_root_version = parents_root_version.unwrap_or(SequenceNumber::new());
+        let children_opt = self
+            .resolver
+            .read_child_objects(&parent, &uncached, parents_root_version)
+            .map_err(|msg| {
+                PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(format!("{msg}"))
+            })?;
+        if children_opt.len() != uncached.len() {
+            return Err(
+                PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(format!(
+                    "Expected {} child objects of {parent} but the resolver returned {}",
+                    uncached.len(),
+                    children_opt.len()
+                )),
+            );
+        }
+        for (child, child_opt) in uncached.into_iter().zip(children_opt) {
+            let obj_opt =
+                Self::check_child_object(parent, child, had_parent_root_version, child_opt)?;
+            self.check_cached_objects_limit()?;
+            self.cached_objects.insert(child, obj_opt);
+            self.num_cached_objects += 1;
+        }
+        Ok(())
+    }
+
+    // Checks a child object returned by the resolver before it is cached.
+    fn check_child_object(
+        parent: ObjectID,
+        child: ObjectID,
+        had_parent_root_version: bool,
+        child_opt: Option<Object>,
+    ) -> PartialVMResult<Option<Object>> {
+        let Some(object) = child_opt else {
+            return Ok(None);
+        };
+        // if there was no root version, guard against reading a child object. A newly
+        // created parent should not have a child in storage
+        if !had_parent_root_version {
+            return Err(
+                PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(format!(
+                    "A new parent {parent} should not have a child object {child}."
+                )),
+            );
+        }
+        // guard against bugs in `read_child_object`: if it returns a child object such that
+        // C.parent != parent, we raise an invariant violation
+        match &object.owner {
+            Owner::ObjectOwner(id) => {
+                if ObjectID::from(*id) != parent {
+                    return Err(PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(
+                        format!(
+                            "Bad owner for {child}. \
+                        Expected owner {parent} but found owner {id}"
+                        ),
+                    ));
+                }
+            }
+            Owner::AddressOwner(_) | Owner::Immutable | Owner::Shared { .. } => {
+                return Err(
+                    PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(format!(
+                        "Bad owner for {child}. \
+                    Expected an id owner {parent} but found an address, immutable, or shared owner"
+                    )),
+                )
+            }
+        };
+        match object.data {
+            Data::Package(_) => Err(PartialVMError::new(StatusCode::STORAGE_ERROR).with_message(
+                format!(
+                    "Mismatched object type for {child}. \
+                    Expected a Move object but found a Move package"
+                ),
+            )),
+            Data::Move(_) => Ok(Some(object)),
+        }
+    }
+
+    // Checks that another object can be cached without exceeding the limit on cached objects.
+    fn check_cached_objects_limit(&self) -> PartialVMResult<()> {
+        let cached_objects_count = self.num_cached_objects;
+        if let LimitThresholdCrossed::Hard(_, lim) = check_limit_by_meter!(
+            self.is_metered,
+            cached_objects_count,
+            self.protocol_config.object_runtime_max_num_cached_objects(),
+            self.protocol_config
+                .object_runtime_max_num_cached_objects_system_tx(),
+            self.metrics.excessive_object_runtime_cached_objects
+        ) {
+            return Err(PartialVMError::new(StatusCode::MEMORY_LIMIT_EXCEEDED)
+                .with_message(format!(
+                    "Object runtime cached objects limit ({} entries) reached",
+                    lim
+                ))
+                .with_sub_status(
+                    VMMemoryLimitExceededSubStatusCode::OBJECT_RUNTIME_CACHE_LIMIT_EXCEEDED as u64,
+                ));
+        };
+        Ok(())
+    }
+
+    fn maybe_evict_cached_objects(&mut self) {
+        if self
+            .cached_objects_eviction_threshold
+            .is_some_and(|threshold| self.cached_objects.len() as u64 >= threshold)
+        {
+            self.cached_objects.retain(|_, object| object.is_some());
+        }
+    }
+
     fn fetch_object_impl(
         &mut self,
         parent: ObjectID,
@@ -356,12 +437,26 @@ impl<'a> ChildObjectStore<'a> {
                 protocol_config,
                 metrics,
                 current_epoch_id,
+                cached_objects_eviction_threshold: protocol_config
+                    .object_runtime_cached_objects_eviction_threshold_as_option(),
+                num_cached_objects: 0,
             },
             store: BTreeMap::new(),
             is_metered,
         }
     }
 
+    /// Resolves the given children of `parent` up front, with a single call to the resolver, so
+    /// that accessing them later does not go to the resolver one child at a time. Children that
+    /// are already cached are not resolved again.
+    pub(super) fn preload_children(
+        &mut self,
+        parent: ObjectID,
+        children: &[ObjectID],
+    ) -> PartialVMResult<()> {
+        self.inner.preload_children(parent, children)
+    }
+
     pub(super) fn receive_object(
         &mut self,
         parent: ObjectID,
@@ -577,10 +672,28 @@ impl<'a> ChildObjectStore<'a> {
         Ok(())
     }
 
+    /// The parent a loaded child object is currently associated with, or `None` if the child has
+    /// not been loaded or added in this transaction.
+    pub(super) fn object_parent(&self, child: ObjectID) -> Option<ObjectID> {
+        self.store
+            .get(&child)
+            .map(|child_object| child_object.owner)
+    }
+
     pub(super) fn cached_objects(&self) -> &BTreeMap<ObjectID, Option<Object>> {
         &self.inner.cached_objects
     }
 
+    /// The cached objects with an ID in `range`, in ID order. A `None` entry means the object was
+    /// looked up but not found. An empty iterator is returned if `range` is empty or reversed.
+    pub(super) fn cached_objects_in_range(
+        &self,
+        range: Range<ObjectID>,
+    ) -> impl Iterator<Item = (&ObjectID, &Option<Object>)> {
+        let end = range.end.max(range.start);
+        self.inner.cached_objects.range(range.start..end)
+    }
+
     pub(super) fn wrapped_object_containers(&self) -> &BTreeMap<ObjectID, ObjectID> {
         &self.inner.wrapped_object_containers
     }
@@ -622,3 +735,217 @@ impl<'a> ChildObjectStore<'a> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::errors::Location;
+    use move_vm_types::values::Struct;
+    use std::sync::OnceLock;
+    use sui_types::error::SuiResult;
+    use sui_types::in_memory_storage::InMemoryStorage;
+
+    fn new_store(resolver: &dyn ChildObjectResolver) -> ChildObjectStore<'_> {
+        static PROTOCOL_CONFIG: OnceLock<ProtocolConfig> = OnceLock::new();
+        let protocol_config =
+            PROTOCOL_CONFIG.get_or_init(ProtocolConfig::get_for_max_version_UNSAFE);
+        new_store_with_config(resolver, protocol_config)
+    }
+
+    fn new_store_with_config<'a>(
+        resolver: &'a dyn ChildObjectResolver,
+        protocol_config: &'a ProtocolConfig,
+    ) -> ChildObjectStore<'a> {
+        let metrics = Arc::new(LimitsMetrics::new(&prometheus::Registry::new()));
+        ChildObjectStore::new(
+            resolver,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            false,
+            protocol_config,
+            metrics,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_cached_objects_eviction() {
+        let resolver = InMemoryStorage::default();
+        let mut protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+        protocol_config.set_object_runtime_cached_objects_eviction_threshold_for_testing(4);
+        protocol_config.set_object_runtime_max_num_cached_objects_for_testing(22);
+        protocol_config.set_object_runtime_max_num_cached_objects_system_tx_for_testing(22);
+        let mut store = new_store_with_config(&resolver, &protocol_config);
+
+        // a loaded object is never evicted
+        let loaded = ObjectID::from_single_byte(0xff);
+        store
+            .inner
+            .cached_objects
+            .insert(loaded, Some(Object::immutable_with_id_for_testing(loaded)));
+        store.inner.num_cached_objects += 1;
+
+        let parent = ObjectID::from_single_byte(1);
+        for i in 0..20u64 {
+            let child = ObjectID::from_single_byte(0x10 + i as u8);
+            assert!(!store.object_exists(parent, child).unwrap());
+            assert!(store.cached_objects().len() <= 4);
+            assert!(store.cached_objects().contains_key(&child));
+            assert!(store.cached_objects()[&loaded].is_some());
+        }
+
+        // an evicted lookup is re-derived from the resolver
+        let evicted = ObjectID::from_single_byte(0x10);
+        assert!(!store.cached_objects().contains_key(&evicted));
+        assert!(!store.object_exists(parent, evicted).unwrap());
+
+        // evicted lookups still count towards the limit on cached objects
+        assert!(store.cached_objects().len() <= 4);
+        let err = store
+            .object_exists(parent, ObjectID::from_single_byte(0x40))
+            .unwrap_err()
+            .finish(Location::Undefined);
+        assert_eq!(err.major_status(), StatusCode::MEMORY_LIMIT_EXCEEDED);
+        assert_eq!(
+            err.sub_status(),
+            Some(VMMemoryLimitExceededSubStatusCode::OBJECT_RUNTIME_CACHE_LIMIT_EXCEEDED as u64)
+        );
+    }
+
+    // Counts the calls made to the wrapped resolver
+    #[derive(Default)]
+    struct CountingResolver {
+        inner: InMemoryStorage,
+        single_reads: std::cell::Cell<usize>,
+        batch_reads: std::cell::Cell<usize>,
+    }
+
+    impl ChildObjectResolver for CountingResolver {
+        fn read_child_object(
+            &self,
+            parent: &ObjectID,
+            child: &ObjectID,
+            child_version_upper_bound: SequenceNumber,
+        ) -> SuiResult<Option<Object>> {
+            self.single_reads.set(self.single_reads.get() + 1);
+            self.inner
+                .read_child_object(parent, child, child_version_upper_bound)
+        }
+
+        fn read_child_objects(
+            &self,
+            parent: &ObjectID,
+            children: &[ObjectID],
+            child_version_upper_bound: SequenceNumber,
+        ) -> SuiResult<Vec<Option<Object>>> {
+            self.batch_reads.set(self.batch_reads.get() + 1);
+            children
+                .iter()
+                .map(|child| {
+                    self.inner
+                        .read_child_object(parent, child, child_version_upper_bound)
+                })
+                .collect()
+        }
+
+        fn get_object_received_at_version(
+            &self,
+            owner: &ObjectID,
+            receiving_object_id: &ObjectID,
+            receive_object_at_version: SequenceNumber,
+            epoch_id: EpochId,
+        ) -> SuiResult<Option<Object>> {
+            self.inner.get_object_received_at_version(
+                owner,
+                receiving_object_id,
+                receive_object_at_version,
+                epoch_id,
+            )
+        }
+    }
+
+    #[test]
+    fn test_preload_children() {
+        let resolver = CountingResolver::default();
+        let mut store = new_store(&resolver);
+
+        let parent = ObjectID::from_single_byte(1);
+        let children: Vec<_> = (0x10..0x18).map(ObjectID::from_single_byte).collect();
+        store.preload_children(parent, &children).unwrap();
+        assert_eq!(resolver.batch_reads.get(), 1);
+        assert_eq!(store.cached_objects().len(), children.len());
+
+        // preloaded children are served from the cache
+        for child in &children {
+            assert!(!store.object_exists(parent, *child).unwrap());
+        }
+        assert_eq!(resolver.single_reads.get(), 0);
+
+        // preloading again only resolves the children which are not cached yet
+        let other = ObjectID::from_single_byte(0x20);
+        store.preload_children(parent, &children).unwrap();
+        assert_eq!(resolver.batch_reads.get(), 1);
+        store
+            .preload_children(parent, &[children[0], other])
+            .unwrap();
+        assert_eq!(resolver.batch_reads.get(), 2);
+        assert_eq!(store.cached_objects().len(), children.len() + 1);
+
+        // children which were not preloaded are still resolved one at a time
+        let missed = ObjectID::from_single_byte(0x30);
+        assert!(!store.object_exists(parent, missed).unwrap());
+        assert_eq!(resolver.single_reads.get(), 1);
+    }
+
+    #[test]
+    fn test_object_parent() {
+        let resolver = InMemoryStorage::default();
+        let mut store = new_store(&resolver);
+
+        let parent_a = ObjectID::from_single_byte(1);
+        let child = ObjectID::from_single_byte(2);
+        assert_eq!(store.object_parent(child), None);
+
+        store
+            .add_object(
+                parent_a,
+                child,
+                &Type::U64,
+                MoveObjectType::gas_coin(),
+                Value::struct_(Struct::pack(vec![Value::u64(0)])),
+            )
+            .unwrap();
+        assert_eq!(store.object_parent(child), Some(parent_a));
+        assert_eq!(store.object_parent(parent_a), None);
+    }
+
+    #[test]
+    fn test_cached_objects_in_range() {
+        let resolver = InMemoryStorage::default();
+        let mut store = new_store(&resolver);
+
+        let ids: Vec<_> = (1..=5).map(ObjectID::from_single_byte).collect();
+        for (i, id) in ids.iter().enumerate() {
+            // Cache every other object as not found
+            let object = (i % 2 == 0).then(|| Object::immutable_with_id_for_testing(*id));
+            store.inner.cached_objects.insert(*id, object);
+        }
+
+        let in_range: Vec<_> = store
+            .cached_objects_in_range(ids[1]..ids[4])
+            .map(|(id, object)| (*id, object.is_some()))
+            .collect();
+        assert_eq!(
+            in_range,
+            vec![(ids[1], false), (ids[2], true), (ids[3], false)]
+        );
+        assert_eq!(store.cached_objects_in_range(ids[4]..ids[4]).count(), 0);
+        assert_eq!(store.cached_objects_in_range(ids[4]..ids[1]).count(), 0);
+        assert_eq!(
+            store
+                .cached_objects_in_range(ObjectID::ZERO..ObjectID::MAX)
+                .count(),
+            ids.len()
+        );
+    }
+}